@@ -17,19 +17,26 @@ import type { Manifest, ResourceRef } from "@contentauth/c2pa-types";
 import * as fs from "fs-extra";
 import path from "path";
 import * as crypto from "crypto";
+import * as http from "http";
+import type { AddressInfo } from "net";
 
 import type {
   BuilderInterface,
   C2paSettings,
   JsCallbackSignerConfig,
   DestinationBufferAsset,
+  DestinationStreamAsset,
   SourceBufferAsset,
+  SourceStreamAsset,
   FileAsset,
+  ProgressReport,
 } from "./types.d.ts";
 import { isActionsAssertion } from "./assertions.js";
 import { CallbackSigner, LocalSigner } from "./Signer.js";
 import { Reader } from "./Reader.js";
 import { Builder } from "./Builder.js";
+import { Context } from "./Context.js";
+import { AbortedError } from "./abort.js";
 
 const tempDir = path.join(__dirname, "tmp");
 
@@ -174,6 +181,560 @@ describe("Builder", () => {
     expect(definition.label).toBe("ABCDE");
   });
 
+  it("should reject an invalid trust configuration", () => {
+    expect(() =>
+      Builder.withJson(manifestDefinition, {
+        trust: { ekuOids: ["1.3.6.1.5.5.7.3.4", "not-an-oid"] },
+      }),
+    ).toThrow(/ekuOids: entry at index 1/);
+  });
+
+  it("should create builders from a shared context", () => {
+    const context = Context.new({
+      settings: { builder: { thumbnail: { enabled: false } } },
+    });
+
+    const first = Builder.new({ settings: context });
+    const second = Builder.withJson(manifestDefinition, { settings: context });
+    expect(first.getManifestDefinition()).toBeDefined();
+    expect(second.getManifestDefinition().title).toBe(
+      "builder-test-manifest",
+    );
+  });
+
+  it("should reject a trust configuration combined with a shared context", () => {
+    expect(() =>
+      Builder.new({ settings: Context.new(), trust: { verifyTrust: false } }),
+    ).toThrow(/A trust config cannot be combined with a shared Context/);
+  });
+
+  describe("signBatch", () => {
+    const template: Manifest = {
+      claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+      title: "Batch_Template",
+      format: "image/jpeg",
+      assertions: [],
+      resources: { resources: {} },
+    };
+
+    it("should sign a batch of assets with per-item overrides", async () => {
+      const signer = LocalSigner.newSigner(publicKey, privateKey, "es256");
+      const bufferDest: DestinationBufferAsset = { buffer: null };
+      const fileDest = { path: path.join(tempDir, "batch-signed.jpg") };
+
+      const results = await Builder.signBatch(
+        template,
+        signer,
+        [
+          { input: source, output: fileDest, title: "rendition-1.jpg" },
+          { input: source, output: bufferDest, instanceId: "xmp:iid:2" },
+        ],
+        { concurrency: 2 },
+      );
+
+      expect(results.map((result) => result.index)).toEqual([0, 1]);
+      expect(results.every((result) => result.manifest!.length > 0)).toBe(true);
+
+      const fileReader = await Reader.fromAsset(fileDest);
+      expect(fileReader!.getActive()?.title).toBe("rendition-1.jpg");
+
+      const bufferReader = await Reader.fromAsset({
+        buffer: bufferDest.buffer!,
+        mimeType: "image/jpeg",
+      });
+      const active = bufferReader!.getActive();
+      expect(active?.title).toBe("Batch_Template");
+      expect(active?.instance_id).toBe("xmp:iid:2");
+    });
+
+    it("should isolate items that fail to sign", async () => {
+      const signer = LocalSigner.newSigner(publicKey, privateKey, "es256");
+
+      const results = await Builder.signBatch(template, signer, [
+        {
+          input: { buffer: Buffer.from("not an image"), mimeType: "image/jpeg" },
+          output: { buffer: null },
+        },
+        { input: source, output: { buffer: null } },
+      ]);
+
+      expect(results[0].error).toBeDefined();
+      expect(results[0].manifest).toBeUndefined();
+      expect(results[1].error).toBeUndefined();
+      expect(results[1].manifest!.length).toBeGreaterThan(0);
+    });
+
+    it("should try each time stamp authority in order", async () => {
+      const requests: {
+        url?: string;
+        headers: http.IncomingHttpHeaders;
+        body: Buffer;
+      }[] = [];
+      // A stub authority: the primary is unavailable and the fallback rejects the request.
+      const server = http.createServer((req, res) => {
+        const chunks: Buffer[] = [];
+        req.on("data", (chunk: Buffer) => chunks.push(chunk));
+        req.on("end", () => {
+          requests.push({ url: req.url, headers: req.headers, body: Buffer.concat(chunks) });
+          if (req.url === "/primary") {
+            res.writeHead(503).end();
+            return;
+          }
+          // A TimeStampResp whose PKIStatusInfo has status 2, rejection.
+          res
+            .writeHead(200, { "Content-Type": "application/timestamp-reply" })
+            .end(Buffer.from([0x30, 0x05, 0x30, 0x03, 0x02, 0x01, 0x02]));
+        });
+      });
+      await new Promise<void>((resolve) => server.listen(0, "127.0.0.1", resolve));
+      const { port } = server.address() as AddressInfo;
+
+      try {
+        const signer = LocalSigner.newSigner(publicKey, privateKey, "es256", {
+          urls: [`http://127.0.0.1:${port}/primary`, `http://127.0.0.1:${port}/fallback`],
+          headers: [["Authorization", "Bearer test-token"]],
+          nonce: "none",
+        });
+        expect(signer.timeAuthorityUrl()).toBe(`http://127.0.0.1:${port}/primary`);
+
+        // signBatch signs off the JS thread, so the stub can answer.
+        const [result] = await Builder.signBatch(template, signer, [
+          { input: source, output: { buffer: null } },
+        ]);
+        expect(result.error).toBeDefined();
+      } finally {
+        server.close();
+      }
+
+      expect(requests.map((request) => request.url)).toEqual(["/primary", "/fallback"]);
+      for (const request of requests) {
+        expect(request.headers["content-type"]).toBe("application/timestamp-query");
+        expect(request.headers.authorization).toBe("Bearer test-token");
+        // Without a nonce the request holds only the version, SHA-256 imprint and certReq.
+        expect(request.body.length).toBe(59);
+      }
+    });
+
+    it("should reject time stamp options without a URL", () => {
+      expect(() =>
+        LocalSigner.newSigner(publicKey, privateKey, "es256", { urls: [] }),
+      ).toThrow("at least one URL");
+    });
+  });
+
+  describe("abort", () => {
+    const definition: Manifest = {
+      claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+      title: "Abort_Test",
+      format: "image/jpeg",
+      assertions: [],
+      resources: { resources: {} },
+    };
+
+    const signerConfig = (): JsCallbackSignerConfig => ({
+      alg: "es256",
+      certs: [publicKey],
+      reserveSize: 10000,
+      tsaUrl: undefined,
+      directCoseHandling: false,
+    });
+
+    it("should reject without signing when the signal is already aborted", async () => {
+      const controller = new AbortController();
+      controller.abort();
+      const testSigner = new TestSigner(privateKey);
+      const signer = CallbackSigner.newSigner(signerConfig(), testSigner.sign);
+      const dest: FileAsset = { path: path.join(tempDir, "aborted-early.jpg") };
+
+      await expect(
+        Builder.withJson(definition).signAsync(signer, source, dest, {
+          signal: controller.signal,
+        }),
+      ).rejects.toBeInstanceOf(AbortedError);
+      expect(await fs.pathExists(dest.path)).toBe(false);
+    });
+
+    it("should remove a partially written file when aborted while signing", async () => {
+      const controller = new AbortController();
+      const testSigner = new TestSigner(privateKey);
+      const signer = CallbackSigner.newSigner(
+        signerConfig(),
+        async (bytes: Buffer) => {
+          controller.abort(new Error("cancelled by test"));
+          return testSigner.sign(bytes);
+        },
+      );
+      const dest: FileAsset = { path: path.join(tempDir, "aborted-signing.jpg") };
+
+      const error = await Builder.withJson(definition)
+        .signAsync(signer, source, dest, { signal: controller.signal })
+        .catch((err: unknown) => err);
+      expect(error).toBeInstanceOf(AbortedError);
+      expect((error as AbortedError).cause).toEqual(new Error("cancelled by test"));
+      expect(await fs.pathExists(dest.path)).toBe(false);
+    });
+
+    it("should sign again with the same builder after an aborted sign", async () => {
+      const controller = new AbortController();
+      const testSigner = new TestSigner(privateKey);
+      const abortingSigner = CallbackSigner.newSigner(
+        signerConfig(),
+        async (bytes: Buffer) => {
+          controller.abort();
+          return testSigner.sign(bytes);
+        },
+      );
+      const builder = Builder.withJson(definition);
+
+      await expect(
+        builder.signAsync(
+          abortingSigner,
+          source,
+          { buffer: null },
+          { signal: controller.signal },
+        ),
+      ).rejects.toBeInstanceOf(AbortedError);
+
+      const signer = CallbackSigner.newSigner(signerConfig(), testSigner.sign);
+      const dest: DestinationBufferAsset = { buffer: null };
+      const bytes = await builder.signAsync(signer, source, dest);
+      expect(bytes.length).toBeGreaterThan(0);
+
+      const reader = await Reader.fromAsset({
+        buffer: dest.buffer!,
+        mimeType: "image/jpeg",
+      });
+      expect(reader!.getActive()?.title).toBe("Abort_Test");
+    });
+
+    it("should sign normally with a signal that is never aborted", async () => {
+      const controller = new AbortController();
+      const testSigner = new TestSigner(privateKey);
+      const signer = CallbackSigner.newSigner(signerConfig(), testSigner.sign);
+      const dest: DestinationBufferAsset = { buffer: null };
+
+      const bytes = await Builder.withJson(definition).signAsync(signer, source, dest, {
+        signal: controller.signal,
+      });
+      expect(bytes.length).toBeGreaterThan(0);
+      expect(dest.buffer!.length).toBeGreaterThan(0);
+    });
+  });
+
+  describe("sign timeout", () => {
+    const definition: Manifest = {
+      claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+      title: "Timeout_Test",
+      format: "image/jpeg",
+      assertions: [],
+      resources: { resources: {} },
+    };
+
+    const signerConfig = (
+      overrides: Partial<JsCallbackSignerConfig>,
+    ): JsCallbackSignerConfig => ({
+      alg: "es256",
+      certs: [publicKey],
+      reserveSize: 10000,
+      tsaUrl: undefined,
+      directCoseHandling: false,
+      ...overrides,
+    });
+
+    it("should fail a callback that never settles without wedging the builder", async () => {
+      const testSigner = new TestSigner(privateKey);
+      const hanging = CallbackSigner.newSigner(
+        signerConfig({ signTimeoutMs: 50 }),
+        () => new Promise<Buffer>(() => {}),
+      );
+      const builder = Builder.withJson(definition);
+
+      await expect(
+        builder.signAsync(hanging, source, { buffer: null }),
+      ).rejects.toThrow("deadline has elapsed");
+
+      const signer = CallbackSigner.newSigner(signerConfig({}), testSigner.sign);
+      const bytes = await builder.signAsync(signer, source, { buffer: null });
+      expect(bytes.length).toBeGreaterThan(0);
+    });
+
+    const retryableError = (message: string) =>
+      Object.assign(new Error(message), { retryable: true });
+
+    it("should retry a callback that rejects with a retryable error", async () => {
+      const testSigner = new TestSigner(privateKey);
+      let calls = 0;
+      const signer = CallbackSigner.newSigner(
+        signerConfig({ signRetries: 2, signRetryDelayMs: 1 }),
+        async (bytes: Buffer) => {
+          calls += 1;
+          if (calls === 1) {
+            throw retryableError("HSM unavailable");
+          }
+          return testSigner.sign(bytes);
+        },
+      );
+
+      const bytes = await Builder.withJson(definition).signAsync(signer, source, {
+        buffer: null,
+      });
+      expect(bytes.length).toBeGreaterThan(0);
+      expect(calls).toBe(2);
+    });
+
+    it("should give up once the retries are used", async () => {
+      let calls = 0;
+      const signer = CallbackSigner.newSigner(
+        signerConfig({ signRetries: 2, signRetryDelayMs: 1 }),
+        async () => {
+          calls += 1;
+          throw retryableError("HSM unavailable");
+        },
+      );
+
+      await expect(
+        Builder.withJson(definition).signAsync(signer, source, { buffer: null }),
+      ).rejects.toThrow("HSM unavailable");
+      expect(calls).toBe(3);
+    });
+
+    it("should not retry a rejected sign", async () => {
+      let calls = 0;
+      const signer = CallbackSigner.newSigner(
+        signerConfig({ signRetries: 2, signRetryDelayMs: 1 }),
+        async () => {
+          calls += 1;
+          throw new Error("Signing refused");
+        },
+      );
+
+      await expect(
+        Builder.withJson(definition).signAsync(signer, source, { buffer: null }),
+      ).rejects.toThrow("Signing refused");
+      expect(calls).toBe(1);
+    });
+
+    it("should retry a callback that times out", async () => {
+      const testSigner = new TestSigner(privateKey);
+      let calls = 0;
+      const signer = CallbackSigner.newSigner(
+        signerConfig({ signTimeoutMs: 50, signRetries: 1, signRetryDelayMs: 1 }),
+        (bytes: Buffer) => {
+          calls += 1;
+          return calls === 1
+            ? new Promise<Buffer>(() => {})
+            : testSigner.sign(bytes);
+        },
+      );
+
+      const bytes = await Builder.withJson(definition).signAsync(signer, source, {
+        buffer: null,
+      });
+      expect(bytes.length).toBeGreaterThan(0);
+      expect(calls).toBe(2);
+    });
+  });
+
+  describe("ocsp stapling", () => {
+    const definition: Manifest = {
+      claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+      title: "Ocsp_Test",
+      format: "image/jpeg",
+      assertions: [],
+      resources: { resources: {} },
+    };
+    // Stands in for a DER OCSP response; stapling does not parse it.
+    const ocspResponse = Buffer.from("30030a0100", "hex");
+
+    it("should fetch the OCSP response from a callback signer's callback", async () => {
+      const testSigner = new TestSigner(privateKey);
+      let fetches = 0;
+      const signer = CallbackSigner.newSigner(
+        {
+          alg: "es256",
+          certs: [publicKey],
+          reserveSize: 10000,
+          directCoseHandling: false,
+          ocspResponse: async () => {
+            fetches += 1;
+            return ocspResponse;
+          },
+        },
+        testSigner.sign,
+      );
+
+      const bytes = await Builder.withJson(definition).signAsync(signer, source, {
+        buffer: null,
+      });
+      expect(bytes.length).toBeGreaterThan(0);
+      expect(fetches).toBeGreaterThan(0);
+    });
+
+    it("should accept a synchronous or throwing OCSP callback", async () => {
+      const testSigner = new TestSigner(privateKey);
+      const callbacks = [
+        () => ocspResponse,
+        () => {
+          throw new Error("OCSP responder unavailable");
+        },
+      ];
+
+      for (const callback of callbacks) {
+        const signer = CallbackSigner.newSigner(
+          {
+            alg: "es256",
+            certs: [publicKey],
+            reserveSize: 10000,
+            directCoseHandling: false,
+            ocspResponse: callback,
+          },
+          testSigner.sign,
+        );
+        const bytes = await Builder.withJson(definition).signAsync(
+          signer,
+          source,
+          { buffer: null },
+        );
+        expect(bytes.length).toBeGreaterThan(0);
+      }
+    });
+
+    it("should reserve room for a realistically sized OCSP response", async () => {
+      // CA responses with an embedded responder certificate run 1.5–3 KB.
+      const largeResponse = Buffer.alloc(3000, 0x5a);
+      const config = {
+        alg: "es256" as const,
+        certs: [publicKey],
+        reserveSize: 10000,
+        directCoseHandling: false,
+      };
+      const testSigner = new TestSigner(privateKey);
+
+      const bufferSigner = CallbackSigner.newSigner(
+        { ...config, ocspResponse: largeResponse },
+        testSigner.sign,
+      );
+      expect(bufferSigner.reserveSize()).toBe(10000 + largeResponse.length);
+
+      const callbackSigner = CallbackSigner.newSigner(
+        { ...config, ocspResponse: async () => largeResponse },
+        testSigner.sign,
+      );
+      expect(callbackSigner.reserveSize()).toBe(10000 + 8 * 1024);
+
+      for (const signer of [bufferSigner, callbackSigner]) {
+        const bytes = await Builder.withJson(definition).signAsync(
+          signer,
+          source,
+          { buffer: null },
+        );
+        expect(bytes.length).toBeGreaterThan(0);
+      }
+    });
+
+    it("should staple an OCSP response from a local signer", () => {
+      const signer = LocalSigner.newSigner(
+        publicKey,
+        privateKey,
+        "es256",
+        undefined,
+        { ocspResponse },
+      );
+      const bytes = Builder.withJson(definition).sign(signer, source, {
+        buffer: null,
+      });
+      expect(bytes.length).toBeGreaterThan(0);
+    });
+
+    it("should reject an OCSP callback on a local signer", () => {
+      expect(() =>
+        LocalSigner.newSigner(publicKey, privateKey, "es256", undefined, {
+          ocspResponse: (async () => ocspResponse) as unknown as Buffer,
+        }),
+      ).toThrow("not a callback");
+    });
+  });
+
+  describe("progress", () => {
+    it("should report hashing, signing and writing while signing", async () => {
+      const definition: Manifest = {
+        claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+        title: "Progress_Test",
+        format: "image/jpeg",
+        assertions: [],
+        resources: { resources: {} },
+      };
+      const testSigner = new TestSigner(privateKey);
+      const signer = CallbackSigner.newSigner(
+        {
+          alg: "es256",
+          certs: [publicKey],
+          reserveSize: 10000,
+          tsaUrl: undefined,
+          directCoseHandling: false,
+        },
+        testSigner.sign,
+      );
+      const dest: DestinationBufferAsset = { buffer: null };
+      const reports: ProgressReport[] = [];
+
+      await Builder.withJson(definition).signAsync(signer, source, dest, {
+        onProgress: (report) => reports.push(report),
+      });
+      // Reports are delivered through their own queue, so let it drain.
+      await new Promise((resolve) => setImmediate(resolve));
+
+      const phases = new Set(reports.map((report) => report.phase));
+      expect(phases).toEqual(new Set(["hashing", "signing", "writing"]));
+
+      const hashing = reports.filter((report) => report.phase === "hashing");
+      expect(hashing.at(-1)).toEqual({
+        phase: "hashing",
+        bytes: source.buffer.length,
+        totalBytes: source.buffer.length,
+      });
+      const writing = reports.filter((report) => report.phase === "writing");
+      expect(writing.at(-1)!.bytes).toBe(dest.buffer!.length);
+    });
+
+    it("should ignore an onProgress callback that throws", async () => {
+      const definition: Manifest = {
+        claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+        title: "Progress_Test",
+        format: "image/jpeg",
+        assertions: [],
+        resources: { resources: {} },
+      };
+      const testSigner = new TestSigner(privateKey);
+      const signer = CallbackSigner.newSigner(
+        {
+          alg: "es256",
+          certs: [publicKey],
+          reserveSize: 10000,
+          directCoseHandling: false,
+        },
+        testSigner.sign,
+      );
+      const dest: DestinationBufferAsset = { buffer: null };
+      let calls = 0;
+
+      await Builder.withJson(definition).signAsync(signer, source, dest, {
+        onProgress: () => {
+          calls += 1;
+          throw new Error("progress listener failed");
+        },
+      });
+      await new Promise((resolve) => setImmediate(resolve));
+
+      expect(calls).toBeGreaterThan(0);
+      const reader = await Reader.fromAsset({
+        buffer: dest.buffer!,
+        mimeType: "image/jpeg",
+      });
+      expect(reader!.getActive()?.title).toBe("Progress_Test");
+    });
+  });
+
   describe("Sign and Archive", () => {
     let builder: BuilderInterface;
 
@@ -322,7 +883,7 @@ describe("Builder", () => {
         resources: { resources: {} },
       };
 
-      const testBuilder = Builder.withJson(simpleManifestDefinition, settings);
+      const testBuilder = Builder.withJson(simpleManifestDefinition, { settings });
 
       // Add an ingredient
       await testBuilder.addIngredient(parent_json, source);
@@ -343,7 +904,7 @@ describe("Builder", () => {
           buffer: archive.buffer! as Buffer,
           mimeType: "application/c2pa",
         },
-        settings,
+        { settings },
       );
       expect(reader).not.toBeNull();
     });
@@ -446,6 +1007,248 @@ describe("Builder", () => {
       expect(activeManifest?.title).toBe("Test_Manifest");
     });
 
+    it("should sign data from a stream asset to a stream asset", async () => {
+      let output = Buffer.alloc(0);
+      const dest: DestinationStreamAsset = {
+        write: async (offset, data) => {
+          const end = offset + data.length;
+          if (end > output.length) {
+            output = Buffer.concat([output, Buffer.alloc(end - output.length)]);
+          }
+          data.copy(output, offset);
+        },
+        read: async (offset, length) =>
+          output.subarray(offset, offset + length),
+      };
+      const input: SourceStreamAsset = {
+        mimeType: source.mimeType,
+        size: source.buffer.length,
+        read: async (offset, length) =>
+          source.buffer.subarray(offset, offset + length),
+      };
+      const signerConfig: JsCallbackSignerConfig = {
+        alg: "es256",
+        certs: [publicKey],
+        reserveSize: 10000,
+        tsaUrl: undefined,
+        directCoseHandling: false,
+      };
+      const testSigner = new TestSigner(privateKey);
+      const signer = CallbackSigner.newSigner(signerConfig, testSigner.sign);
+
+      const bytes = await builder.signAsync(signer, input, dest);
+      expect(bytes.length).toBeGreaterThan(0);
+
+      const reader = await Reader.fromAsset({
+        buffer: output,
+        mimeType: "jpeg",
+      });
+      expect(reader).not.toBeNull();
+      expect(reader!.getActive()?.title).toBe("Test_Manifest");
+    });
+
+    it("should reject stream assets when signing synchronously", () => {
+      const signer = LocalSigner.newSigner(publicKey, privateKey, "es256");
+      const dest: DestinationStreamAsset = {
+        write: async () => {},
+      };
+
+      expect(() => builder.sign(signer, source, dest)).toThrow(
+        "Stream assets are only supported by signAsync",
+      );
+    });
+
+    it("should sign with a sidecar manifest to files", async () => {
+      const signer = LocalSigner.newSigner(publicKey, privateKey, "es256");
+      const dest = { path: path.join(tempDir, "sidecar.jpg") };
+      const manifestDest = { path: path.join(tempDir, "sidecar.jpg.c2pa") };
+
+      const manifest = builder.signSidecar(signer, source, dest, manifestDest);
+      expect(manifest.length).toBeGreaterThan(0);
+      expect(await fs.readFile(manifestDest.path)).toEqual(manifest);
+      expect(await fs.pathExists(`${dest.path}.partial`)).toBe(false);
+
+      // The asset itself carries no manifest
+      expect(await Reader.fromAsset(dest)).toBeNull();
+
+      const reader = await Reader.fromAsset(dest, { sidecar: true });
+      expect(reader).not.toBeNull();
+      expect(reader!.isEmbedded()).toBeFalsy();
+      expect(reader!.getActive()?.title).toBe("Test_Manifest");
+    });
+
+    it("should sign with a sidecar manifest to buffers using a callback signer", async () => {
+      const signerConfig: JsCallbackSignerConfig = {
+        alg: "es256",
+        certs: [publicKey],
+        reserveSize: 10000,
+        tsaUrl: undefined,
+        directCoseHandling: false,
+      };
+      const testSigner = new TestSigner(privateKey);
+      const signer = CallbackSigner.newSigner(signerConfig, testSigner.sign);
+      const dest: DestinationBufferAsset = { buffer: null };
+      const manifestDest: DestinationBufferAsset = { buffer: null };
+
+      const manifest = await builder.signSidecarAsync(
+        signer,
+        source,
+        dest,
+        manifestDest,
+      );
+      expect(manifestDest.buffer).toEqual(manifest);
+
+      const reader = await Reader.fromManifestDataAndAsset(manifest, {
+        buffer: dest.buffer! as Buffer,
+        mimeType: "image/jpeg",
+      });
+      expect(reader!.getActive()?.title).toBe("Test_Manifest");
+    });
+
+    it("should leave no files behind when sidecar signing fails", async () => {
+      const signer = LocalSigner.newSigner(publicKey, privateKey, "es256");
+      const dest = { path: path.join(tempDir, "sidecar_failed.jpg") };
+      const manifestDest = {
+        path: path.join(tempDir, "sidecar_failed.jpg.c2pa"),
+      };
+
+      expect(() =>
+        builder.signSidecar(
+          signer,
+          { buffer: Buffer.from("not an image"), mimeType: "image/jpeg" },
+          dest,
+          manifestDest,
+        ),
+      ).toThrow();
+      expect(await fs.pathExists(dest.path)).toBe(false);
+      expect(await fs.pathExists(`${dest.path}.partial`)).toBe(false);
+      expect(await fs.pathExists(manifestDest.path)).toBe(false);
+    });
+
+    it("should restore the previous manifest when the asset cannot be moved into place", async () => {
+      const signer = LocalSigner.newSigner(publicKey, privateKey, "es256");
+      // A directory in the asset's place makes moving the signed asset fail.
+      const dest = { path: path.join(tempDir, "sidecar_blocked.jpg") };
+      await fs.ensureDir(path.join(dest.path, "blocked"));
+      const manifestDest = {
+        path: path.join(tempDir, "sidecar_blocked.jpg.c2pa"),
+      };
+      await fs.writeFile(manifestDest.path, "previous manifest");
+
+      expect(() =>
+        builder.signSidecar(signer, source, dest, manifestDest),
+      ).toThrow();
+      expect(await fs.readFile(manifestDest.path, "utf8")).toBe(
+        "previous manifest",
+      );
+      expect(await fs.pathExists(`${manifestDest.path}.previous`)).toBe(false);
+      expect(await fs.pathExists(`${manifestDest.path}.partial`)).toBe(false);
+      expect(await fs.pathExists(`${dest.path}.partial`)).toBe(false);
+    });
+
+    describe("fragmented MP4", () => {
+      const definition = {
+        claim_generator_info: [
+          {
+            name: "c2pa_test",
+            version: "1.0.0",
+          },
+        ],
+        title: "Fragmented_Manifest",
+        format: "video/mp4",
+        assertions: [
+          {
+            label: "c2pa.actions",
+            data: {
+              actions: [
+                {
+                  action: "c2pa.created",
+                  digitalSourceType: "http://c2pa.org/digitalsourcetype/empty",
+                },
+              ],
+            },
+          },
+        ],
+        resources: { resources: {} },
+      };
+      const init = "./tests/fixtures/fragmented/unsigned_dashinit.mp4";
+      const fragment = "./tests/fixtures/fragmented/unsigned_dash1.m4s";
+
+      it("should sign the given fragments so that each one verifies", async () => {
+        // A second fragment from another directory, listed before the fixture.
+        const otherFragment = path.join(tempDir, "segments", "dash2.m4s");
+        await fs.ensureDir(path.dirname(otherFragment));
+        await fs.copy(fragment, otherFragment);
+        const outputDir = path.join(tempDir, "fragmented");
+
+        const signed = await Builder.withJson(definition).signFragmented(
+          LocalSigner.newSigner(publicKey, privateKey, "es256"),
+          init,
+          [otherFragment, fragment],
+          outputDir,
+        );
+        expect(signed.init).toBe(
+          path.join(outputDir, "fragmented", "unsigned_dashinit.mp4"),
+        );
+        expect(signed.fragments).toEqual([
+          path.join(outputDir, "fragmented", "dash2.m4s"),
+          path.join(outputDir, "fragmented", "unsigned_dash1.m4s"),
+        ]);
+
+        const results = await Reader.fromFragments(
+          { path: signed.init, mimeType: "video/mp4" },
+          signed.fragments.map((fragment) => ({ path: fragment })),
+          { trust: { verifyTrust: false } },
+        );
+        expect(results).toHaveLength(2);
+        for (const { reader, error } of results) {
+          expect(error).toBeUndefined();
+          expect(reader!.getActive()?.title).toBe("Fragmented_Manifest");
+          const report = reader!.validationReport();
+          expect(report.state).toEqual("Valid");
+          expect(report.manifests[0].failure).toEqual([]);
+        }
+      });
+
+      it("should sign fragments with a callback signer", async () => {
+        const signerConfig: JsCallbackSignerConfig = {
+          alg: "es256",
+          certs: [publicKey],
+          reserveSize: 10000,
+          tsaUrl: undefined,
+          directCoseHandling: false,
+        };
+        const testSigner = new TestSigner(privateKey);
+        const outputDir = path.join(tempDir, "fragmented-callback");
+
+        const signed = await Builder.withJson(definition).signFragmented(
+          CallbackSigner.newSigner(signerConfig, testSigner.sign),
+          init,
+          [fragment],
+          outputDir,
+        );
+
+        const reader = await Reader.fromFragment(
+          { path: signed.init, mimeType: "video/mp4" },
+          { path: signed.fragments[0] },
+          { trust: { verifyTrust: false } },
+        );
+        expect(reader!.validationReport().state).toEqual("Valid");
+        expect(reader!.validationReport().manifests[0].failure).toEqual([]);
+      });
+
+      it("should reject fragments with the same file name", async () => {
+        await expect(
+          Builder.withJson(definition).signFragmented(
+            LocalSigner.newSigner(publicKey, privateKey, "es256"),
+            init,
+            [fragment, fragment],
+            path.join(tempDir, "fragmented-duplicate"),
+          ),
+        ).rejects.toThrow();
+      });
+    });
+
     it("should preserve JSON assertion characters without escaping", async () => {
       const fingerprintAssertion = JSON.stringify({
         alg: "sha256",
@@ -20,10 +20,16 @@ import type {
   ManifestStore,
 } from "@contentauth/c2pa-types";
 
+import { withAbortSignal } from "./abort.js";
 import { getNeonBinary } from "./binary.js";
+import { settingsArgument } from "./Context.js";
 import type {
+  AbortOptions,
+  BatchSignItem,
+  BatchSignOptions,
+  BatchSignResult,
   BuilderInterface,
-  C2paSettings,
+  C2paOptions,
   CallbackSignerInterface,
   ClaimVersion,
   DestinationAsset,
@@ -32,26 +38,31 @@ import type {
   JsCallbackSignerConfig,
   LocalSignerInterface,
   ManifestAssertionKind,
+  Pkcs11SignerInterface,
   ReaderInterface,
+  SignedFragments,
+  SignOptions,
   SourceAsset,
   NeonBuilderHandle,
+  SettingsOptions,
+  TrustmarkInterface,
+  WatermarkSignOptions,
+  WatermarkSignResult,
 } from "./types.d.ts";
 import { IdentityAssertionSigner } from "./IdentityAssertion.js";
 
 export class Builder implements BuilderInterface {
   constructor(private builder: NeonBuilderHandle) {}
 
-  static new(settings?: C2paSettings): Builder {
-    const settingsStr = settings
-      ? typeof settings === "string"
-        ? settings
-        : JSON.stringify(settings)
-      : undefined;
-    const builder: NeonBuilderHandle = getNeonBinary().builderNew(settingsStr);
+  static new(options?: SettingsOptions): Builder {
+    const builder: NeonBuilderHandle = getNeonBinary().builderNew(
+      settingsArgument(options?.settings),
+      { trust: options?.trust },
+    );
     return new Builder(builder);
   }
 
-  static withJson(json: Manifest, settings?: C2paSettings): Builder {
+  static withJson(json: Manifest, options?: SettingsOptions): Builder {
     let jsonString: string;
     try {
       jsonString = JSON.stringify(json);
@@ -66,14 +77,10 @@ export class Builder implements BuilderInterface {
         "Failed to stringify JSON Manifest Definition: Unknown error",
       );
     }
-    const settingsStr = settings
-      ? typeof settings === "string"
-        ? settings
-        : JSON.stringify(settings)
-      : undefined;
     const builder: NeonBuilderHandle = getNeonBinary().builderWithJson(
       jsonString,
-      settingsStr,
+      settingsArgument(options?.settings),
+      { trust: options?.trust },
     );
     return new Builder(builder);
   }
@@ -108,19 +115,34 @@ export class Builder implements BuilderInterface {
     );
   }
 
-  async addResource(uri: string, resource: SourceAsset): Promise<void> {
-    return getNeonBinary().builderAddResource.call(this.builder, uri, resource);
+  async addResource(
+    uri: string,
+    resource: SourceAsset,
+    options?: AbortOptions,
+  ): Promise<void> {
+    return withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().builderAddResource.call(
+        this.builder,
+        uri,
+        resource,
+        abort,
+      ),
+    );
   }
 
   async addIngredient(
     ingredientJson: string,
     ingredient?: SourceAsset,
+    options?: AbortOptions,
   ): Promise<void> {
     if (ingredient) {
-      return getNeonBinary().builderAddIngredientFromAsset.call(
-        this.builder,
-        ingredientJson,
-        ingredient,
+      return withAbortSignal(options?.signal, (abort) =>
+        getNeonBinary().builderAddIngredientFromAsset.call(
+          this.builder,
+          ingredientJson,
+          ingredient,
+          abort,
+        ),
       );
     } else {
       return getNeonBinary().builderAddIngredient.call(
@@ -139,26 +161,72 @@ export class Builder implements BuilderInterface {
     return JSON.parse(result);
   }
 
-  async toArchive(asset: DestinationAsset): Promise<void> {
-    return getNeonBinary().builderToArchive.call(this.builder, asset);
+  async toArchive(
+    asset: DestinationAsset,
+    options?: AbortOptions,
+  ): Promise<void> {
+    return withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().builderToArchive.call(this.builder, asset, abort),
+    );
+  }
+
+  /**
+   * Sign many assets concurrently with one manifest definition and one signer, for example
+   * the renditions of an image. Each item can override the title, instance_id and format of
+   * the definition. Resolves to one result per item, in order; an item that fails to sign gets
+   * a result with an error rather than failing the whole batch.
+   */
+  static async signBatch(
+    definition: Manifest,
+    signer:
+      | LocalSignerInterface
+      | Pkcs11SignerInterface
+      | CallbackSignerInterface,
+    items: BatchSignItem[],
+    options?: BatchSignOptions,
+  ): Promise<BatchSignResult[]> {
+    const results: {
+      index: number;
+      manifest?: Buffer;
+      signedAsset?: Buffer;
+      error?: string;
+    }[] = await withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().builderSignBatch(
+        JSON.stringify(definition),
+        signer.getHandle(),
+        items,
+        settingsArgument(options?.settings),
+        options,
+        abort,
+      ),
+    );
+    return results.map(({ signedAsset, ...result }) => {
+      const { output } = items[result.index];
+      if (signedAsset && "buffer" in output) {
+        output.buffer = signedAsset;
+      }
+      return result;
+    });
   }
 
   static async fromArchive(
     asset: SourceAsset,
-    settings?: C2paSettings,
+    options?: C2paOptions,
   ): Promise<Builder> {
-    const settingsStr = settings
-      ? typeof settings === "string"
-        ? settings
-        : JSON.stringify(settings)
-      : undefined;
     return new Builder(
-      await getNeonBinary().builderFromArchive(asset, settingsStr),
+      await withAbortSignal(options?.signal, (abort) =>
+        getNeonBinary().builderFromArchive(
+          asset,
+          settingsArgument(options?.settings),
+          { trust: options?.trust },
+          abort,
+        ),
+      ),
     );
   }
 
   sign(
-    signer: LocalSignerInterface,
+    signer: LocalSignerInterface | Pkcs11SignerInterface,
     input: SourceAsset,
     output: DestinationAsset,
   ): Buffer {
@@ -170,8 +238,65 @@ export class Builder implements BuilderInterface {
     );
   }
 
+  signSidecar(
+    signer: LocalSignerInterface | Pkcs11SignerInterface,
+    input: SourceAsset,
+    output: DestinationAsset,
+    manifestOutput: DestinationAsset,
+  ): Buffer {
+    return getNeonBinary().builderSignSidecar.call(
+      this.builder,
+      signer.getHandle(),
+      input,
+      output,
+      manifestOutput,
+    );
+  }
+
+  async signSidecarAsync(
+    callbackSigner: CallbackSignerInterface,
+    input: SourceAsset,
+    output: DestinationAsset,
+    manifestOutput: DestinationAsset,
+    options?: SignOptions,
+  ): Promise<Buffer> {
+    return withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().builderSignSidecarAsync.call(
+        this.builder,
+        callbackSigner.getHandle(),
+        input,
+        output,
+        manifestOutput,
+        abort,
+        options?.onProgress,
+      ),
+    );
+  }
+
+  async signFragmented(
+    signer:
+      | LocalSignerInterface
+      | Pkcs11SignerInterface
+      | CallbackSignerInterface,
+    initPath: string,
+    fragmentPaths: string[],
+    outputDir: string,
+    options?: AbortOptions,
+  ): Promise<SignedFragments> {
+    return withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().builderSignFragmented.call(
+        this.builder,
+        signer.getHandle(),
+        initPath,
+        fragmentPaths,
+        outputDir,
+        abort,
+      ),
+    );
+  }
+
   signFile(
-    signer: LocalSignerInterface,
+    signer: LocalSignerInterface | Pkcs11SignerInterface,
     filePath: string,
     output: DestinationAsset,
   ): Buffer {
@@ -189,15 +314,19 @@ export class Builder implements BuilderInterface {
     signerConfig: JsCallbackSignerConfig,
     input: SourceAsset,
     output: DestinationAsset,
+    options?: SignOptions,
   ): Promise<Buffer> {
-    return getNeonBinary()
-      .builderSignConfigAsync.call(
+    return withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().builderSignConfigAsync.call(
         this.builder,
         callback,
         signerConfig,
         input,
         output,
-      )
+        abort,
+        options?.onProgress,
+      ),
+    )
       .then((result: Buffer | { manifest: Buffer; signedAsset: Buffer }) => {
         // output is a buffer and result is the manifest and the signed asset.
         if ("buffer" in output) {
@@ -218,17 +347,29 @@ export class Builder implements BuilderInterface {
   }
 
   async signAsync(
-    signer: CallbackSignerInterface | IdentityAssertionSignerInterface,
+    signer:
+      | CallbackSignerInterface
+      | IdentityAssertionSignerInterface
+      | Pkcs11SignerInterface,
     input: SourceAsset,
     output: DestinationAsset,
+    options?: SignOptions,
   ): Promise<Buffer> {
     const neonHandle = signer.getHandle();
     const isIdentity = signer instanceof IdentityAssertionSigner;
     const neonFn = isIdentity
       ? getNeonBinary().builderIdentitySignAsync
       : getNeonBinary().builderSignAsync;
-    return neonFn
-      .call(this.builder, neonHandle, input, output)
+    return withAbortSignal(options?.signal, (abort) =>
+      neonFn.call(
+        this.builder,
+        neonHandle,
+        input,
+        output,
+        abort,
+        options?.onProgress,
+      ),
+    )
       .then((result: Buffer | { manifest: Buffer; signedAsset: Buffer }) => {
         // output is a buffer and result is the manifest and the signed asset.
         if ("buffer" in output) {
@@ -248,6 +389,35 @@ export class Builder implements BuilderInterface {
       });
   }
 
+  async signWatermarked(
+    signer:
+      | LocalSignerInterface
+      | Pkcs11SignerInterface
+      | CallbackSignerInterface,
+    trustmark: TrustmarkInterface,
+    input: SourceAsset,
+    output: DestinationAsset,
+    options?: WatermarkSignOptions,
+  ): Promise<WatermarkSignResult> {
+    const { signedAsset, ...result } = await withAbortSignal(
+      options?.signal,
+      (abort) =>
+        getNeonBinary().builderSignWatermarked.call(
+          this.builder,
+          signer.getHandle(),
+          trustmark.getHandle(),
+          input,
+          output,
+          { strength: options?.strength, watermark: options?.watermark },
+          abort,
+        ),
+    );
+    if (signedAsset && "buffer" in output) {
+      output.buffer = signedAsset;
+    }
+    return result;
+  }
+
   getManifestDefinition(): Manifest {
     return JSON.parse(
       getNeonBinary().builderManifestDefinition.call(this.builder),
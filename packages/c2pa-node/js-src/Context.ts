@@ -0,0 +1,60 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+import { getNeonBinary } from "./binary.js";
+import type {
+  C2paSettings,
+  ContextInterface,
+  ContextOptions,
+  NeonContextHandle,
+} from "./types.d.ts";
+
+/**
+ * Settings parsed once and shared by every Reader and Builder it is passed to in place of
+ * settings, so trust lists are not decoded again for each asset.
+ */
+export class Context implements ContextInterface {
+  constructor(private context: NeonContextHandle) {}
+
+  static new(options?: ContextOptions): Context {
+    const context: NeonContextHandle = getNeonBinary().contextNew(
+      toSettingsString(options?.settings),
+      { trust: options?.trust },
+    );
+    return new Context(context);
+  }
+
+  getHandle(): NeonContextHandle {
+    return this.context;
+  }
+}
+
+function toSettingsString(settings?: C2paSettings): string | undefined {
+  return settings
+    ? typeof settings === "string"
+      ? settings
+      : JSON.stringify(settings)
+    : undefined;
+}
+
+/**
+ * Converts the settings passed to a Reader or Builder constructor into the argument the Neon
+ * bindings take: a settings string, or the handle of a shared Context.
+ */
+export function settingsArgument(
+  settings?: C2paSettings | ContextInterface,
+): string | NeonContextHandle | undefined {
+  return settings instanceof Context
+    ? settings.getHandle()
+    : toSettingsString(settings);
+}
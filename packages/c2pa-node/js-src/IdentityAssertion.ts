@@ -16,6 +16,7 @@ import type {
   CallbackCredentialHolderInterface,
   IdentityAssertionBuilderInterface,
   IdentityAssertionSignerInterface,
+  IdentityAssertionSignerOptions,
   NeonCallbackCredentialHolderHandle,
   NeonIdentityAssertionSignerHandle,
   NeonIdentityAssertionBuilderHandle,
@@ -58,8 +59,11 @@ export class IdentityAssertionSigner
 {
   constructor(private _signer: NeonIdentityAssertionSignerHandle) {}
 
-  static new(signer: NeonCallbackSignerHandle): IdentityAssertionSigner {
-    const identitySigner = getNeonBinary().identitySignerNew(signer);
+  static new(
+    signer: NeonCallbackSignerHandle,
+    options?: IdentityAssertionSignerOptions,
+  ): IdentityAssertionSigner {
+    const identitySigner = getNeonBinary().identitySignerNew(signer, options);
     return new IdentityAssertionSigner(identitySigner);
   }
 
@@ -15,11 +15,18 @@
 // import native objects from built native code
 import type { ManifestStore } from "@contentauth/c2pa-types";
 import path from "path";
+import * as http from "http";
+import type { AddressInfo } from "net";
 import * as fs from "fs-extra";
 import { AssetTooLargeError } from "@contentauth/c2pa-utilities";
 
+import { Builder } from "./Builder.js";
+import { Context } from "./Context.js";
 import { Reader } from "./Reader.js";
+import { LocalSigner } from "./Signer.js";
 import { MAX_SIZE_IN_BYTES } from "./assetSize.js";
+import { AbortedError } from "./abort.js";
+import type { ProgressReport } from "./types.d.ts";
 
 const tempDir = path.join(__dirname, "tmp");
 
@@ -142,6 +149,65 @@ describe("Reader", () => {
     expect(json.active_manifest).toEqual(manifestStore.active_manifest);
   });
 
+  it("should read from a stream asset", async () => {
+    const buffer = await fs.readFile("./tests/fixtures/CA.jpg");
+    const requests: number[] = [];
+    const reader = await Reader.fromAsset({
+      mimeType: "image/jpeg",
+      size: buffer.length,
+      read: async (offset, length) => {
+        requests.push(offset);
+        return buffer.subarray(offset, offset + length);
+      },
+    });
+    expect(reader).not.toBeNull();
+    expect(requests.length).toBeGreaterThan(0);
+
+    const json = reader!.json();
+    expect(json.manifests).toEqual(manifestStore.manifests);
+    expect(json.active_manifest).toEqual(manifestStore.active_manifest);
+  });
+
+  it("should reject when a stream asset read fails", async () => {
+    await expect(
+      Reader.fromAsset({
+        mimeType: "image/jpeg",
+        read: async () => {
+          throw new Error("range request failed");
+        },
+      }),
+    ).rejects.toThrow("range request failed");
+  });
+
+  it("should reject with AbortedError when the signal is already aborted", async () => {
+    const controller = new AbortController();
+    controller.abort();
+    await expect(
+      Reader.fromAsset({ path: "./tests/fixtures/CA.jpg" }, {
+        signal: controller.signal,
+      }),
+    ).rejects.toBeInstanceOf(AbortedError);
+  });
+
+  it("should report hashing progress up to the size of the asset", async () => {
+    const buffer = await fs.readFile("./tests/fixtures/CA.jpg");
+    const reports: ProgressReport[] = [];
+    const reader = await Reader.fromAsset({ buffer, mimeType: "image/jpeg" }, {
+      onProgress: (report) => reports.push(report),
+    });
+    // Reports are delivered through their own queue, so let it drain.
+    await new Promise((resolve) => setImmediate(resolve));
+
+    expect(reader).not.toBeNull();
+    expect(reports.length).toBeGreaterThan(0);
+    expect(reports.every((report) => report.phase === "hashing")).toBe(true);
+    expect(reports.at(-1)).toEqual({
+      phase: "hashing",
+      bytes: buffer.length,
+      totalBytes: buffer.length,
+    });
+  });
+
   it("should return null for a file without C2PA data", async () => {
     const reader = await Reader.fromAsset({
       path: "./tests/fixtures/A.jpg",
@@ -206,7 +272,7 @@ describe("Reader", () => {
         buffer,
         mimeType: "jpeg",
       },
-      settings,
+      { settings },
     );
 
     expect(reader).not.toBeNull();
@@ -238,6 +304,169 @@ describe("Reader", () => {
     expect(fs.existsSync(outputPath));
   });
 
+  it("should write a resource to a stream asset", async () => {
+    const reader = await Reader.fromAsset({
+      path: "./tests/fixtures/CA.jpg",
+    });
+    const uri = reader!.getActive()?.thumbnail?.identifier;
+    expect(uri).toBeDefined();
+
+    const chunks: Buffer[] = [];
+    const result = await reader!.resourceToAsset(uri!, {
+      write: async (offset, data) => {
+        chunks.push(Buffer.from(data));
+      },
+    });
+
+    expect(result.bytes_written).toBe(49690);
+    expect(Buffer.concat(chunks).length).toBe(49690);
+  });
+
+  it("should summarize validation results in a validation report", async () => {
+    const reader = await Reader.fromAsset({
+      path: "./tests/fixtures/CA.jpg",
+    });
+    expect(reader).not.toBeNull();
+
+    const report = reader!.validationReport();
+    expect(report.state).toEqual("Valid");
+    expect(report.trust_evaluated).toBe(true);
+    expect(report.manifests[0].label).toEqual(manifestStore.active_manifest);
+    expect(report.manifests[0].success).toContain("claimSignature.validated");
+    expect(report.manifests[0].failure).toContain(
+      "signingCredential.untrusted",
+    );
+  });
+
+  it("should report when trust was not evaluated", async () => {
+    const reader = await Reader.fromAsset(
+      { path: "./tests/fixtures/CA.jpg" },
+      { settings: { verify: { verify_trust: false } } },
+    );
+    expect(reader).not.toBeNull();
+
+    const report = reader!.validationReport();
+    expect(report.trust_evaluated).toBe(false);
+    expect(report.manifests[0].failure).not.toContain(
+      "signingCredential.untrusted",
+    );
+  });
+
+  it("should apply a trust configuration passed in the reader options", async () => {
+    const reader = await Reader.fromAsset(
+      { path: "./tests/fixtures/CA.jpg" },
+      { trust: { verifyTrust: false } },
+    );
+    expect(reader).not.toBeNull();
+    expect(reader!.validationReport().trust_evaluated).toBe(false);
+  });
+
+  it("should read from manifest data with a trust configuration and a signal", async () => {
+    const manifestData = await fs.readFile(
+      "./tests/fixtures/CA/manifest_data.c2pa",
+    );
+    const reader = await Reader.fromManifestDataAndAsset(
+      manifestData,
+      { path: "./tests/fixtures/CA.jpg" },
+      { trust: { verifyTrust: false }, signal: new AbortController().signal },
+    );
+    expect(reader.validationReport().trust_evaluated).toBe(false);
+  });
+
+  it("should reject a trust configuration with a non-certificate PEM block", async () => {
+    const certificates = await fs.readFile(
+      "./tests/fixtures/certs/es256.pub",
+      "utf8",
+    );
+    const privateKey = await fs.readFile(
+      "./tests/fixtures/certs/es256.pem",
+      "utf8",
+    );
+
+    await expect(
+      Reader.fromAsset({ path: "./tests/fixtures/CA.jpg" }, {
+        trust: { anchors: certificates + privateKey },
+      }),
+    ).rejects.toThrow(
+      "anchors: PEM block at index 2 is a PRIVATE KEY, expected a CERTIFICATE",
+    );
+  });
+
+  it("should share one context across readers", async () => {
+    const context = Context.new({
+      settings: { verify: { verify_trust: false } },
+    });
+
+    const first = await Reader.fromAsset(
+      { path: "./tests/fixtures/CA.jpg" },
+      { settings: context },
+    );
+    const second = await Reader.fromManifestDataAndAsset(
+      await fs.readFile("./tests/fixtures/CA/manifest_data.c2pa"),
+      { path: "./tests/fixtures/CA.jpg" },
+      { settings: context },
+    );
+
+    expect(first!.validationReport().trust_evaluated).toBe(false);
+    expect(second.validationReport().trust_evaluated).toBe(false);
+  });
+
+  it("should reject a trust configuration combined with a shared context", async () => {
+    const context = Context.new();
+
+    await expect(
+      Reader.fromAsset({ path: "./tests/fixtures/CA.jpg" }, {
+        settings: context,
+        trust: { verifyTrust: false },
+      }),
+    ).rejects.toThrow("A trust config cannot be combined with a shared Context");
+  });
+
+  it("should verify a batch of assets", async () => {
+    const results = await Reader.verifyBatch(
+      [
+        { path: "./tests/fixtures/CA.jpg" },
+        { path: "./tests/fixtures/A.jpg" },
+        { buffer: Buffer.from("not an asset"), mimeType: "text/plain" },
+        {
+          buffer: await fs.readFile("./tests/fixtures/CA.jpg"),
+          mimeType: "image/jpeg",
+        },
+      ],
+      { concurrency: 2, settings: { verify: { verify_trust: false } } },
+    );
+
+    expect(results.map((result) => result.index)).toEqual([0, 1, 2, 3]);
+    expect(results[0].manifestStore!.active_manifest).toEqual(
+      manifestStore.active_manifest,
+    );
+    expect(results[0].validationReport!.trust_evaluated).toBe(false);
+    expect(results[1]).toEqual({
+      index: 1,
+      manifestStore: null,
+      validationReport: null,
+    });
+    expect(results[2].manifestStore).toBeNull();
+    expect(results[2].error).toBeDefined();
+    expect(results[3].manifestStore!.active_manifest).toEqual(
+      manifestStore.active_manifest,
+    );
+  });
+
+  it("should report oversized assets in a batch without failing it", async () => {
+    const oversizedPath = path.join(tempDir, "oversized-batch.jpg");
+    await fs.ensureFile(oversizedPath);
+    await fs.truncate(oversizedPath, MAX_SIZE_IN_BYTES + 1);
+
+    const results = await Reader.verifyBatch([
+      { path: oversizedPath },
+      { path: "./tests/fixtures/CA.jpg" },
+    ]);
+
+    expect(results[0].error).toBeDefined();
+    expect(results[1].validationReport!.state).toEqual("Valid");
+  });
+
   it("should report manifest is embedded", async () => {
     const reader = await Reader.fromAsset({
       path: "./tests/fixtures/CA.jpg",
@@ -317,4 +546,192 @@ describe("Reader", () => {
     const reader = await Reader.fromAsset(asset);
     expect(reader).toBeNull();
   });
+
+  describe("fragmented MP4", () => {
+    const init = {
+      path: "./tests/fixtures/fragmented/dashinit.mp4",
+      mimeType: "video/mp4",
+    };
+    const fragment = { path: "./tests/fixtures/fragmented/dash1.m4s" };
+    const activeLabel =
+      "contoso:urn:uuid:FA0E000D-FA0E-000D-FA0E-000DFA0E000D";
+
+    it("should read a fragment against its init segment", async () => {
+      const reader = await Reader.fromFragment(init, fragment);
+      expect(reader).not.toBeNull();
+      expect(reader!.activeLabel()).toEqual(activeLabel);
+    });
+
+    it("should read a fragment with a trust configuration and a signal", async () => {
+      const reader = await Reader.fromFragment(
+        init,
+        fragment,
+        { trust: { verifyTrust: false }, signal: new AbortController().signal },
+      );
+      expect(reader!.activeLabel()).toEqual(activeLabel);
+      expect(reader!.validationReport().trust_evaluated).toBe(false);
+    });
+
+    it("should return null when the init segment has no C2PA data", async () => {
+      const reader = await Reader.fromFragment(
+        { path: "./tests/fixtures/A.jpg", mimeType: "image/jpeg" },
+        fragment,
+      );
+      expect(reader).toBeNull();
+    });
+
+    it("should read a list of fragments", async () => {
+      const fragmentBuffer = await fs.readFile(fragment.path);
+      const results = await Reader.fromFragments(init, [
+        fragment,
+        { buffer: fragmentBuffer, mimeType: "video/mp4" },
+      ]);
+      expect(results).toHaveLength(2);
+      for (const [index, { reader, error }] of results.entries()) {
+        expect(results[index].index).toBe(index);
+        expect(error).toBeUndefined();
+        expect(reader!.activeLabel()).toEqual(activeLabel);
+      }
+    });
+
+    it("should report a fragment that cannot be read without failing the list", async () => {
+      const results = await Reader.fromFragments(init, [
+        { path: "./tests/fixtures/fragmented/missing.m4s" },
+        fragment,
+      ]);
+      expect(results[0].reader).toBeNull();
+      expect(results[0].error).toBeDefined();
+      expect(results[1].error).toBeUndefined();
+      expect(results[1].reader!.activeLabel()).toEqual(activeLabel);
+    });
+
+    it("should read a list of fragments with a trust configuration and a signal", async () => {
+      const results = await Reader.fromFragments(
+        init,
+        [fragment],
+        { trust: { verifyTrust: false }, signal: new AbortController().signal },
+      );
+      expect(results[0].error).toBeUndefined();
+      expect(results[0].reader!.validationReport().trust_evaluated).toBe(false);
+    });
+
+    it("should resolve to an empty list for no fragments", async () => {
+      expect(await Reader.fromFragments(init, [])).toEqual([]);
+    });
+  });
+
+  describe("remote manifests", () => {
+    let server: http.Server;
+    let manifestUrl: string;
+    let manifestData: Buffer;
+    let asset: { buffer: Buffer; mimeType: string };
+
+    beforeAll(async () => {
+      server = http.createServer((req, res) => {
+        if (req.url === "/manifest.c2pa") {
+          res.writeHead(200, { "Content-Type": "application/c2pa" });
+          res.end(manifestData);
+        } else {
+          res.writeHead(404);
+          res.end();
+        }
+      });
+      await new Promise<void>((resolve) =>
+        server.listen(0, "127.0.0.1", resolve),
+      );
+      const { port } = server.address() as AddressInfo;
+      manifestUrl = `http://127.0.0.1:${port}/manifest.c2pa`;
+
+      // Sign an asset that only references its manifest
+      const builder = Builder.withJson({
+        claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+        title: "Remote_Manifest",
+        format: "image/jpeg",
+        assertions: [],
+        resources: { resources: {} },
+      });
+      builder.setIntent({ create: "http://c2pa.org/digitalsourcetype/empty" });
+      builder.setRemoteUrl(manifestUrl);
+      builder.setNoEmbed(true);
+      const signer = LocalSigner.newSigner(
+        await fs.readFile("./tests/fixtures/certs/es256.pub"),
+        await fs.readFile("./tests/fixtures/certs/es256.pem"),
+        "es256",
+      );
+      const dest = { buffer: null };
+      manifestData = builder.sign(
+        signer,
+        { path: "./tests/fixtures/A.jpg" },
+        dest,
+      );
+      asset = { buffer: dest.buffer! as Buffer, mimeType: "image/jpeg" };
+    });
+
+    afterAll(async () => {
+      await new Promise((resolve) => server.close(resolve));
+    });
+
+    it("should fetch a remote manifest from an allowed host", async () => {
+      const reader = await Reader.fromAsset(asset, {
+        remoteManifest: { allowedHosts: ["127.0.0.1"], timeoutMs: 5000 },
+      });
+      expect(reader).not.toBeNull();
+      expect(reader!.getActive()?.title).toBe("Remote_Manifest");
+    });
+
+    it("should reject a remote manifest from a host that is not allowed", async () => {
+      await expect(
+        Reader.fromAsset(asset, {
+          remoteManifest: { allowedHosts: ["example.com"] },
+        }),
+      ).rejects.toThrow("not permitted by the allowed list");
+    });
+
+    it("should apply the limits when a shared Context is passed", async () => {
+      const context = Context.new();
+      await expect(
+        Reader.fromAsset(asset, {
+          settings: context,
+          remoteManifest: { allowedHosts: ["example.com"] },
+        }),
+      ).rejects.toThrow("could not fetch the remote manifest");
+    });
+
+    it("should reject a remote manifest larger than maxBytes", async () => {
+      await expect(
+        Reader.fromAsset(asset, {
+          remoteManifest: { maxBytes: 16 },
+        }),
+      ).rejects.toThrow("exceeded maximum allowed size");
+    });
+
+    it("should use a custom resolver", async () => {
+      const urls: string[] = [];
+      const reader = await Reader.fromAsset(asset, {
+        remoteManifest: {
+          resolve: async (url) => {
+            urls.push(url);
+            return manifestData;
+          },
+        },
+      });
+      expect(urls).toEqual([manifestUrl]);
+      expect(reader!.getActive()?.title).toBe("Remote_Manifest");
+    });
+
+    it("should stop waiting on a resolver when aborted", async () => {
+      const controller = new AbortController();
+      await expect(
+        Reader.fromAsset(asset, {
+          signal: controller.signal,
+          remoteManifest: {
+            resolve: () => {
+              controller.abort();
+              return new Promise<Uint8Array>(() => {});
+            },
+          },
+        }),
+      ).rejects.toBeInstanceOf(AbortedError);
+    });
+  });
 });
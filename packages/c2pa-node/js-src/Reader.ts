@@ -11,14 +11,25 @@
 // specific language governing permissions and limitations under
 // each license.
 
-import type { Manifest, ManifestStore } from "@contentauth/c2pa-types";
+import type {
+  Manifest,
+  ManifestStore,
+  ValidationReport,
+} from "@contentauth/c2pa-types";
 
+import { withAbortSignal } from "./abort.js";
 import { getNeonBinary } from "./binary.js";
+import { settingsArgument } from "./Context.js";
 import { validateSourceAssetSize } from "./assetSize.js";
 import type {
-  C2paSettings,
+  AbortOptions,
+  BatchVerifyOptions,
+  BatchVerifyResult,
+  C2paOptions,
   DestinationAsset,
+  FragmentReadResult,
   ReaderInterface,
+  ReaderOptions,
   ResourceAsset,
   SourceAsset,
   NeonReaderHandle,
@@ -31,6 +42,10 @@ export class Reader implements ReaderInterface {
     return JSON.parse(getNeonBinary().readerJson.call(this.reader));
   }
 
+  validationReport(): ValidationReport {
+    return JSON.parse(getNeonBinary().readerValidationReport.call(this.reader));
+  }
+
   remoteUrl(): string {
     return getNeonBinary().readerRemoteUrl.call(this.reader);
   }
@@ -39,30 +54,159 @@ export class Reader implements ReaderInterface {
     return getNeonBinary().readerIsEmbedded.call(this.reader);
   }
 
-  async resourceToAsset(uri: string, asset: DestinationAsset): Promise<ResourceAsset> {
-    return getNeonBinary().readerResourceToAsset.call(this.reader, uri, asset);
+  async resourceToAsset(
+    uri: string,
+    asset: DestinationAsset,
+    options?: AbortOptions,
+  ): Promise<ResourceAsset> {
+    return withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().readerResourceToAsset.call(this.reader, uri, asset, abort),
+    );
   }
 
-  static async fromAsset(asset: SourceAsset, settings?: C2paSettings): Promise<Reader | null> {
+  static async fromAsset(
+    asset: SourceAsset,
+    options?: ReaderOptions,
+  ): Promise<Reader | null> {
     await validateSourceAssetSize(asset);
-    const settingsStr = settings ? (typeof settings === 'string' ? settings : JSON.stringify(settings)) : undefined;
-    const reader: NeonReaderHandle | null =
-      await getNeonBinary().readerFromAsset(asset, settingsStr);
+    const reader: NeonReaderHandle | null = await withAbortSignal(
+      options?.signal,
+      (abort) =>
+        getNeonBinary().readerFromAsset(
+          asset,
+          settingsArgument(options?.settings),
+          neonReaderOptions(options),
+          abort,
+        ),
+    );
     return reader ? new Reader(reader) : null;
   }
 
   static async fromManifestDataAndAsset(
     manifestData: Buffer,
     asset: SourceAsset,
-    settings?: C2paSettings,
+    options?: C2paOptions,
   ): Promise<Reader> {
     await validateSourceAssetSize(asset);
-    const settingsStr = settings ? (typeof settings === 'string' ? settings : JSON.stringify(settings)) : undefined;
-    const reader: NeonReaderHandle =
-      await getNeonBinary().readerFromManifestDataAndAsset(manifestData, asset, settingsStr);
+    const reader: NeonReaderHandle = await withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().readerFromManifestDataAndAsset(
+        manifestData,
+        asset,
+        settingsArgument(options?.settings),
+        { trust: options?.trust },
+        abort,
+      ),
+    );
     return new Reader(reader);
   }
 
+  /**
+   * Read a fragmented MP4 (for instance DASH or HLS fMP4) fragment against its init segment.
+   * Returns null when the init segment has no C2PA data.
+   */
+  static async fromFragment(
+    init: SourceAsset,
+    fragment: SourceAsset,
+    options?: C2paOptions,
+  ): Promise<Reader | null> {
+    await validateSourceAssetSize(init);
+    await validateSourceAssetSize(fragment);
+    const reader: NeonReaderHandle | null = await withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().readerFromFragment(
+        init,
+        fragment,
+        settingsArgument(options?.settings),
+        { trust: options?.trust },
+        abort,
+      ),
+    );
+    return reader ? new Reader(reader) : null;
+  }
+
+  /**
+   * Read every fragment of a segment list against the same init segment.
+   * Resolves to one result per fragment, in order, with a null reader for fragments without
+   * C2PA data. A fragment that cannot be read gets a result with an error rather than failing
+   * the whole list.
+   */
+  static async fromFragments(
+    init: SourceAsset,
+    fragments: SourceAsset[],
+    options?: C2paOptions,
+  ): Promise<FragmentReadResult<Reader>[]> {
+    await validateSourceAssetSize(init);
+    const results: FragmentReadResult<Reader>[] = new Array(fragments.length);
+    const pending: number[] = [];
+    for (const [index, fragment] of fragments.entries()) {
+      try {
+        await validateSourceAssetSize(fragment);
+        pending.push(index);
+      } catch (err) {
+        const error = err instanceof Error ? err.message : String(err);
+        results[index] = { index, reader: null, error };
+      }
+    }
+
+    const read: FragmentReadResult<NeonReaderHandle>[] = await withAbortSignal(
+      options?.signal,
+      (abort) =>
+        getNeonBinary().readerFromFragments(
+          init,
+          pending.map((index) => fragments[index]),
+          settingsArgument(options?.settings),
+          { trust: options?.trust },
+          abort,
+        ),
+    );
+    for (const { reader, ...result } of read) {
+      const index = pending[result.index];
+      results[index] = {
+        ...result,
+        index,
+        reader: reader ? new Reader(reader) : null,
+      };
+    }
+    return results;
+  }
+
+  /**
+   * Read and validate many assets concurrently, for example to scan an archive.
+   * Resolves to one result per asset, in order. An asset that cannot be read gets a result
+   * with an error rather than failing the whole batch.
+   */
+  static async verifyBatch(
+    assets: SourceAsset[],
+    options?: BatchVerifyOptions,
+  ): Promise<BatchVerifyResult[]> {
+    const results: BatchVerifyResult[] = new Array(assets.length);
+    const pending: number[] = [];
+    for (const [index, asset] of assets.entries()) {
+      try {
+        await validateSourceAssetSize(asset);
+        pending.push(index);
+      } catch (err) {
+        const error = err instanceof Error ? err.message : String(err);
+        results[index] = { index, manifestStore: null, validationReport: null, error };
+      }
+    }
+
+    const verified: BatchVerifyResult[] = JSON.parse(
+      await withAbortSignal(options?.signal, (abort) =>
+        getNeonBinary().readerVerifyBatch(
+          pending.map((index) => assets[index]),
+          settingsArgument(options?.settings),
+          neonReaderOptions(options),
+          abort,
+        ),
+      ),
+    );
+    for (const result of verified) {
+      const index = pending[result.index];
+      results[index] = { ...result, index };
+    }
+    return results;
+  }
+
   // Non-neon methods, copied from c2pa-js
 
   activeLabel(): string | undefined {
@@ -85,3 +229,15 @@ export class Reader implements ReaderInterface {
     return this.reader;
   }
 }
+
+// Replaces the Trustmark of the soft binding options with its native handle.
+function neonReaderOptions<T extends ReaderOptions>(options?: T) {
+  if (!options?.softBinding) {
+    return options;
+  }
+  const { trustmark, ...softBinding } = options.softBinding;
+  return {
+    ...options,
+    softBinding: { ...softBinding, trustmark: trustmark.getHandle() },
+  };
+}
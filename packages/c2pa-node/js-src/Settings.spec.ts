@@ -4,11 +4,18 @@
 // or the MIT license (http://opensource.org/licenses/MIT),
 // at your option.
 
+/* eslint-disable @typescript-eslint/no-non-null-assertion */
 import * as fs from "fs-extra";
 import * as path from "path";
 import * as os from "os";
 
-import { loadSettingsFromFile } from "./Settings.js";
+import { Reader } from "./Reader.js";
+import {
+  getSettings,
+  loadSettings,
+  loadSettingsFromFile,
+  resetSettings,
+} from "./Settings.js";
 
 describe("Settings", () => {
   describe("loadSettingsFromFile", () => {
@@ -57,4 +64,50 @@ verify_after_sign = false`;
       await expect(loadSettingsFromFile(filePath)).rejects.toThrow();
     });
   });
+
+  describe("global settings", () => {
+    // The native module, and so its global settings, outlive each test file
+    afterEach(() => {
+      resetSettings();
+    });
+
+    it("merges loaded settings into the effective settings", () => {
+      loadSettings({ verify: { verify_trust: false } });
+      loadSettings(`[builder.thumbnail]
+enabled = false`);
+
+      const settings = getSettings() as {
+        verify: { verify_trust: boolean; verify_after_reading: boolean };
+        builder: { thumbnail: { enabled: boolean } };
+      };
+      expect(settings.verify.verify_trust).toBe(false);
+      expect(settings.verify.verify_after_reading).toBe(true);
+      expect(settings.builder.thumbnail.enabled).toBe(false);
+    });
+
+    it("applies loaded settings to readers", async () => {
+      loadSettings({ verify: { verify_trust: false } });
+
+      const reader = await Reader.fromAsset({
+        path: "./tests/fixtures/CA.jpg",
+      });
+      expect(reader!.validationReport().trust_evaluated).toBe(false);
+    });
+
+    it("restores the defaults on reset", () => {
+      loadSettings({ verify: { verify_trust: false } });
+      resetSettings();
+
+      const settings = getSettings() as { verify: { verify_trust: boolean } };
+      expect(settings.verify.verify_trust).toBe(true);
+    });
+
+    it("rejects invalid settings and keeps the loaded ones", () => {
+      loadSettings({ verify: { verify_trust: false } });
+
+      expect(() => loadSettings("not = [valid")).toThrow("Invalid settings");
+      const settings = getSettings() as { verify: { verify_trust: boolean } };
+      expect(settings.verify.verify_trust).toBe(false);
+    });
+  });
 });
@@ -13,6 +13,9 @@
 
 import * as fs from "fs-extra";
 
+import { getNeonBinary } from "./binary.js";
+import type { C2paSettings } from "./types.d.ts";
+
 /**
  * This file contains only Settings functions that are unique to the Node SDK.
  * 
@@ -29,3 +32,31 @@ export async function loadSettingsFromFile(filePath: string): Promise<string> {
   const content = await fs.readFile(filePath, "utf8");
   return content;
 }
+
+/**
+ * Load settings for the whole process, for example to configure verification once at startup.
+ * They are merged into any settings loaded before, and apply to every Reader, Builder and
+ * Context created afterwards. Settings passed to those constructors override them.
+ * @param settings Settings as a JSON or TOML string, or a settings object
+ */
+export function loadSettings(settings: C2paSettings): void {
+  getNeonBinary().loadSettings(
+    typeof settings === "string" ? settings : JSON.stringify(settings),
+  );
+}
+
+/**
+ * Discard the settings loaded with {@link loadSettings}, restoring the SDK defaults.
+ */
+export function resetSettings(): void {
+  getNeonBinary().resetSettings();
+}
+
+/**
+ * Get the effective process-wide settings: the SDK defaults with the settings loaded by
+ * {@link loadSettings} applied.
+ * @returns The settings object, with snake_case keys as in a settings file
+ */
+export function getSettings(): Record<string, unknown> {
+  return JSON.parse(getNeonBinary().getSettings());
+}
@@ -14,11 +14,18 @@
 /* eslint-disable @typescript-eslint/no-non-null-assertion */
 import * as fs from "fs-extra";
 import * as crypto from "crypto";
+import * as os from "os";
+import * as path from "path";
+import { execFileSync } from "child_process";
 
-import { CallbackSigner } from "./Signer.js";
+import { CallbackSigner, LocalSigner, Pkcs11Signer } from "./Signer.js";
 import { Builder } from "./Builder.js";
 import { Reader } from "./Reader.js";
-import type { JsCallbackSignerConfig, SigningAlg } from "./types.d.ts";
+import type {
+  JsCallbackSignerConfig,
+  SignerCertificateError,
+  SigningAlg,
+} from "./types.d.ts";
 import type { Manifest } from "@contentauth/c2pa-types";
 
 class TestSigner {
@@ -111,6 +118,22 @@ describe("CallbackSigner", () => {
     expect(signer.timeAuthorityUrl()).toBeUndefined();
   });
 
+  it("should time out a callback that never settles", async () => {
+    const signer = CallbackSigner.newSigner(
+      { ...config, signTimeoutMs: 20 },
+      () => new Promise<Buffer>(() => {}),
+    );
+    await expect(signer.sign(Buffer.from("test data"))).rejects.toThrow(
+      "deadline has elapsed",
+    );
+  });
+
+  it("should reject a negative sign timeout", () => {
+    expect(() =>
+      CallbackSigner.newSigner({ ...config, signTimeoutMs: -1 }, async (data) => data),
+    ).toThrow("signTimeoutMs must be a non-negative integer");
+  });
+
   it("should create valid COSE signature with buffer output", async () => {
     const testSigner = new TestSigner(
       await fs.readFile("./tests/fixtures/certs/es256.pem"),
@@ -185,3 +208,251 @@ describe("CallbackSigner", () => {
     expect(activeManifest?.title).toBe("Test_Manifest_Buffer");
   });
 });
+
+describe("LocalSigner key loading", () => {
+  const certs = fs.readFileSync("./tests/fixtures/certs/es256.pub");
+  const encryptedKey = fs.readFileSync(
+    "./tests/fixtures/certs/es256_encrypted.pem",
+  );
+
+  const signWith = async (signer: LocalSigner) => {
+    const manifestDefinition: Manifest = {
+      claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+      title: "Key_Loading_Test",
+      format: "image/jpeg",
+      assertions: [],
+      resources: { resources: {} },
+    };
+    const dest = { buffer: null };
+    Builder.withJson(manifestDefinition).sign(
+      signer,
+      {
+        buffer: await fs.readFile("./tests/fixtures/CA.jpg"),
+        mimeType: "image/jpeg",
+      },
+      dest,
+    );
+    const reader = await Reader.fromAsset({
+      buffer: dest.buffer! as Buffer,
+      mimeType: "image/jpeg",
+    });
+    return reader!.json().validation_status!.map(({ code }) => code);
+  };
+
+  it("should sign with an encrypted PKCS#8 key", async () => {
+    const signer = LocalSigner.newSigner(
+      certs,
+      encryptedKey,
+      undefined,
+      undefined,
+      { passphrase: "c2pa-test" },
+    );
+
+    expect(signer.alg()).toBe("es256");
+    expect(await signWith(signer)).toEqual(["signingCredential.untrusted"]);
+  });
+
+  it("should reject an encrypted key without the right passphrase", () => {
+    expect(() => LocalSigner.newSigner(certs, encryptedKey, "es256")).toThrow(
+      "no passphrase was given",
+    );
+    expect(() =>
+      LocalSigner.newSigner(certs, encryptedKey, "es256", undefined, {
+        passphrase: "wrong",
+      }),
+    ).toThrow("could not be decrypted");
+  });
+
+  it("should sign with the key and chain of a PKCS#12 bundle", async () => {
+    const signer = LocalSigner.fromPkcs12(
+      await fs.readFile("./tests/fixtures/certs/es256.p12"),
+      "c2pa-test",
+    );
+
+    expect(signer.alg()).toBe("es256");
+    expect(signer.certs()).toHaveLength(2);
+    expect(await signWith(signer)).toEqual(["signingCredential.untrusted"]);
+  });
+
+  it("should derive the algorithm of an Ed25519 key", async () => {
+    const signer = LocalSigner.newSigner(
+      await fs.readFile("./tests/fixtures/certs/ed25519.pub"),
+      await fs.readFile("./tests/fixtures/certs/ed25519.pem"),
+    );
+    expect(signer.alg()).toBe("ed25519");
+  });
+});
+
+describe("LocalSigner certificate checks", () => {
+  const certs = fs.readFileSync("./tests/fixtures/certs/es256.pub");
+  const privateKey = fs.readFileSync("./tests/fixtures/certs/es256.pem");
+
+  it("should report a usable chain and the algorithm of its key", () => {
+    const diagnostics = LocalSigner.checkCertificates(certs);
+
+    expect(diagnostics.valid).toBe(true);
+    expect(diagnostics.detectedAlg).toBe("es256");
+    expect(diagnostics.leaf?.subject).toContain("CN=C2PA Signer");
+    expect(diagnostics.issues).toEqual([]);
+    expect(diagnostics.warnings).toEqual([]);
+  });
+
+  it("should reject an algorithm that does not match the key", () => {
+    const diagnostics = LocalSigner.checkCertificates(certs, "ed25519");
+    expect(diagnostics.valid).toBe(false);
+    expect(diagnostics.issues.map(({ code }) => code)).toEqual([
+      "algorithmMismatch",
+    ]);
+
+    let error: unknown;
+    try {
+      LocalSigner.newSigner(certs, privateKey, "ps256");
+    } catch (err) {
+      error = err;
+    }
+    expect((error as Error).message).toContain("cannot sign with ps256");
+    expect((error as SignerCertificateError).diagnostics.issues[0].code).toBe(
+      "algorithmMismatch",
+    );
+  });
+
+  it("should report a chain that is out of order", () => {
+    const pem = certs.toString();
+    const [leaf, intermediate] = pem.split(/(?=-----BEGIN CERTIFICATE-----)/);
+    const diagnostics = LocalSigner.checkCertificates(
+      Buffer.from(intermediate + leaf),
+    );
+
+    expect(diagnostics.issues.map(({ code }) => code)).toContain("chainOrder");
+  });
+
+  it("should report an expired certificate without claim signing usage", () => {
+    const diagnostics = LocalSigner.checkCertificates(
+      fs.readFileSync("./tests/fixtures/certs/es256_expired_server.pub"),
+      "es256",
+    );
+
+    expect(diagnostics.issues.map(({ code }) => code)).toEqual(["expired"]);
+    expect(diagnostics.warnings.map(({ code }) => code)).toEqual([
+      "extendedKeyUsage",
+    ]);
+  });
+
+  it("should skip the checks when asked to", () => {
+    const signer = LocalSigner.newSigner(certs, privateKey, "es256", undefined, {
+      checkCertificates: false,
+    });
+    expect(signer.alg()).toBe("es256");
+  });
+});
+
+// SoftHSM stands in for an HSM. The tests are skipped where it is not installed.
+const softHsmModule = [
+  process.env.SOFTHSM2_MODULE,
+  "/usr/lib/softhsm/libsofthsm2.so",
+  "/usr/lib/x86_64-linux-gnu/softhsm/libsofthsm2.so",
+  "/usr/local/lib/softhsm/libsofthsm2.so",
+  "/opt/homebrew/lib/softhsm/libsofthsm2.so",
+].find((modulePath) => modulePath && fs.existsSync(modulePath));
+
+describe.skipIf(!softHsmModule)("Pkcs11Signer", () => {
+  let softHsmDir: string;
+
+  const newSigner = (keyLabel = "c2pa-es256") =>
+    Pkcs11Signer.newSigner({
+      modulePath: softHsmModule!,
+      tokenLabel: "c2pa-test",
+      pin: "1234",
+      keyLabel,
+      alg: "es256",
+      certs: [fs.readFileSync("./tests/fixtures/certs/es256.pub")],
+    });
+
+  const manifestDefinition: Manifest = {
+    claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+    title: "Pkcs11_Test",
+    format: "image/jpeg",
+    assertions: [],
+    resources: { resources: {} },
+  };
+
+  beforeAll(async () => {
+    softHsmDir = await fs.mkdtemp(path.join(os.tmpdir(), "c2pa-softhsm-"));
+    const tokenDir = path.join(softHsmDir, "tokens");
+    await fs.mkdir(tokenDir);
+    const conf = path.join(softHsmDir, "softhsm2.conf");
+    await fs.writeFile(conf, `directories.tokendir = ${tokenDir}\n`);
+    // Read by SoftHSM when the module is first initialized.
+    process.env.SOFTHSM2_CONF = conf;
+    execFileSync("softhsm2-util", [
+      "--init-token",
+      "--free",
+      "--label",
+      "c2pa-test",
+      "--pin",
+      "1234",
+      "--so-pin",
+      "5678",
+    ]);
+    execFileSync("softhsm2-util", [
+      "--import",
+      "./tests/fixtures/certs/es256.pem",
+      "--token",
+      "c2pa-test",
+      "--label",
+      "c2pa-es256",
+      "--id",
+      "01",
+      "--pin",
+      "1234",
+    ]);
+  });
+
+  afterAll(async () => {
+    await fs.remove(softHsmDir);
+  });
+
+  it("should sign a manifest with a key in the token", async () => {
+    const signer = newSigner();
+    expect(signer.alg()).toBe("es256");
+
+    const dest = { buffer: null };
+    Builder.withJson(manifestDefinition).sign(
+      signer,
+      {
+        buffer: await fs.readFile("./tests/fixtures/CA.jpg"),
+        mimeType: "image/jpeg",
+      },
+      dest,
+    );
+
+    const reader = await Reader.fromAsset({
+      buffer: dest.buffer! as Buffer,
+      mimeType: "image/jpeg",
+    });
+    // The signature verifies; only the test certificate is untrusted.
+    expect(reader!.json().validation_status!.map(({ code }) => code)).toEqual([
+      "signingCredential.untrusted",
+    ]);
+    expect(reader!.getActive()?.title).toBe("Pkcs11_Test");
+  });
+
+  it("should sign asynchronously", async () => {
+    const signer = newSigner();
+    const bytes = await Builder.withJson(manifestDefinition).signAsync(
+      signer,
+      {
+        buffer: await fs.readFile("./tests/fixtures/CA.jpg"),
+        mimeType: "image/jpeg",
+      },
+      { buffer: null },
+    );
+    expect(bytes.length).toBeGreaterThan(0);
+  });
+
+  it("should fail when no key matches", () => {
+    expect(() => newSigner("missing")).toThrow(
+      "No matching PKCS#11 private key",
+    );
+  });
+});
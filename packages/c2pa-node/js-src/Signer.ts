@@ -17,28 +17,75 @@ import type {
   JsCallbackSignerConfig,
   NeonCallbackSignerHandle,
   NeonLocalSignerHandle,
+  NeonPkcs11SignerHandle,
   LocalSignerInterface,
+  LocalSignerOptions,
+  Pkcs11SignerConfig,
+  Pkcs11SignerInterface,
+  SignerDiagnostics,
   SigningAlg,
+  TsaOptions,
 } from "./types.d.ts";
 
 export class LocalSigner implements LocalSignerInterface {
   constructor(private localSigner: NeonLocalSignerHandle) {}
 
+  /**
+   * @param algorithm Derived from the private key when not given. RSA keys use PS256.
+   */
   static newSigner(
     certificate: Buffer,
     privateKey: Buffer,
-    algorithm: SigningAlg,
-    tsaUrl?: string,
+    algorithm?: SigningAlg,
+    tsa?: string | TsaOptions,
+    options?: LocalSignerOptions,
   ) {
     const signer = getNeonBinary().localSignerNew(
       certificate,
       privateKey,
       algorithm,
-      tsaUrl,
+      tsa,
+      options,
     );
     return new LocalSigner(signer);
   }
 
+  /**
+   * Creates a signer from the private key and certificate chain in a PKCS#12 (.p12 or .pfx)
+   * bundle.
+   * @param algorithm Derived from the private key when not given. RSA keys use PS256.
+   */
+  static fromPkcs12(
+    bundle: Buffer,
+    passphrase: string,
+    algorithm?: SigningAlg,
+    tsa?: string | TsaOptions,
+    options?: LocalSignerOptions,
+  ) {
+    const signer = getNeonBinary().localSignerFromPkcs12(
+      bundle,
+      passphrase,
+      algorithm,
+      tsa,
+      options,
+    );
+    return new LocalSigner(signer);
+  }
+
+  /**
+   * Checks a PEM certificate chain without creating a signer: that it is in order, that the
+   * signing certificate is valid and allowed to sign claims, and that its key matches
+   * `algorithm`. `newSigner` and `fromPkcs12` run the same checks and throw when they fail.
+   * @param algorithm Only the chain is checked when not given; `detectedAlg` reports the
+   * algorithm that matches the key.
+   */
+  static checkCertificates(
+    certificate: Buffer,
+    algorithm?: SigningAlg,
+  ): SignerDiagnostics {
+    return getNeonBinary().localSignerCheckCertificates(certificate, algorithm);
+  }
+
   sign(data: Buffer): Buffer {
     return getNeonBinary().localSignerSign.call(this.localSigner, data);
   }
@@ -112,3 +159,38 @@ export class CallbackSigner implements CallbackSignerInterface {
     );
   }
 }
+
+export class Pkcs11Signer implements Pkcs11SignerInterface {
+  constructor(private pkcs11Signer: NeonPkcs11SignerHandle) {}
+
+  static newSigner(config: Pkcs11SignerConfig) {
+    const signer = getNeonBinary().pkcs11SignerNew(config);
+    return new Pkcs11Signer(signer);
+  }
+
+  async sign(data: Buffer): Promise<Buffer> {
+    return getNeonBinary().pkcs11SignerSign.call(this.pkcs11Signer, data);
+  }
+
+  alg(): SigningAlg {
+    return getNeonBinary().pkcs11SignerAlg.call(this.pkcs11Signer);
+  }
+
+  certs(): Array<Buffer> {
+    return getNeonBinary().pkcs11SignerCerts.call(this.pkcs11Signer);
+  }
+
+  reserveSize(): number {
+    return getNeonBinary().pkcs11SignerReserveSize.call(this.pkcs11Signer);
+  }
+
+  timeAuthorityUrl(): string | undefined {
+    return getNeonBinary().pkcs11SignerTimeAuthorityUrl.call(
+      this.pkcs11Signer,
+    );
+  }
+
+  getHandle(): NeonPkcs11SignerHandle {
+    return this.pkcs11Signer;
+  }
+}
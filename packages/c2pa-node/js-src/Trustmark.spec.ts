@@ -11,34 +11,24 @@
 // specific language governing permissions and limitations under
 // each license.
 
+import { createHash } from "crypto";
 import path from "path";
 import * as fs from "fs-extra";
 import sharp from "sharp";
 
+import { Builder } from "./Builder.js";
+import { Reader } from "./Reader.js";
+import { LocalSigner } from "./Signer.js";
 import { Trustmark } from "./Trustmark.js";
-import type { TrustmarkConfig, TrustmarkVariant } from "./types.d.ts";
+import type {
+  DestinationBufferAsset,
+  TrustmarkConfig,
+  TrustmarkOutputFormat,
+  TrustmarkVariant,
+} from "./types.d.ts";
 
 const tempDir = path.join(__dirname, "..", "tmp");
 
-// Helper function to convert raw RGB pixel data to JPEG format
-async function rawRgbToJpeg(
-  rawRgbData: Buffer,
-  width: number,
-  height: number,
-): Promise<Buffer> {
-  // Create a sharp image from raw RGB data
-  const image = sharp(rawRgbData, {
-    raw: {
-      width,
-      height,
-      channels: 3, // RGB
-    },
-  });
-
-  // Convert to JPEG format
-  return await image.jpeg().toBuffer();
-}
-
 describe("Trustmark", () => {
   let trustmark: Trustmark;
   let testImage: Buffer;
@@ -87,32 +77,179 @@ describe("Trustmark", () => {
     });
   });
 
+  describe("offline provisioning", () => {
+    const modelPath = path.join(tempDir, "trustmark_models");
+    const sha256 = (data: Buffer) =>
+      createHash("sha256").update(data).digest("hex");
+
+    it("should list missing models instead of downloading them", async () => {
+      const emptyDir = path.join(tempDir, "trustmark_offline_empty");
+      await fs.emptyDir(emptyDir);
+
+      await expect(
+        Trustmark.newTrustmark({
+          ...trustmarkConfig,
+          modelPath: emptyDir,
+          offline: true,
+        }),
+      ).rejects.toThrow("encoder_B.onnx, decoder_B.onnx is missing");
+      expect(await fs.readdir(emptyDir)).toEqual([]);
+    });
+
+    it("should reject models that do not match the pinned checksums", async () => {
+      await expect(
+        Trustmark.newTrustmark({
+          ...trustmarkConfig,
+          offline: true,
+          checksums: {
+            B: { encoder: "0".repeat(64), decoder: "0".repeat(64) },
+          },
+        }),
+      ).rejects.toThrow("does not match the pinned");
+    });
+
+    it("should verify models against the digests pinned in the package", async () => {
+      const tampered = path.join(tempDir, "trustmark_tampered");
+      await fs.emptyDir(tampered);
+      for (const name of ["encoder_B.onnx", "decoder_B.onnx"]) {
+        await fs.writeFile(path.join(tampered, name), "not a model");
+      }
+
+      await expect(
+        Trustmark.newTrustmark({
+          ...trustmarkConfig,
+          modelPath: tampered,
+          offline: true,
+        }),
+      ).rejects.toThrow(
+        `encoder_B.onnx has SHA-256 ${sha256(Buffer.from("not a model"))}, which does not match the pinned`,
+      );
+    });
+
+    it("should reject malformed checksums", async () => {
+      await expect(
+        Trustmark.newTrustmark({
+          ...trustmarkConfig,
+          checksums: { B: { encoder: "abc", decoder: "abc" } },
+        }),
+      ).rejects.toThrow(
+        "Watermark configuration error: checksums.B.encoder must be a hex SHA-256 digest",
+      );
+    });
+
+    it("should load verified models from buffers", async () => {
+      const encoder = await fs.readFile(
+        path.join(modelPath, "encoder_B.onnx"),
+      );
+      const decoder = await fs.readFile(
+        path.join(modelPath, "decoder_B.onnx"),
+      );
+
+      const offlineTrustmark = await Trustmark.newTrustmark({
+        variant: "B",
+        version: "BCH_3",
+        offline: true,
+        models: { encoder, decoder },
+        checksums: {
+          B: { encoder: sha256(encoder), decoder: sha256(decoder) },
+        },
+      });
+
+      const { image } = await offlineTrustmark.encode(testImage, 0.9);
+      expect(image.length).toBeGreaterThan(0);
+    }, 30000);
+  });
+
   describe("encode", () => {
     it("should encode a watermark into an image with default watermark", async () => {
       const strength = 0.9;
-      const encodedImage = await trustmark.encode(testImage, strength);
+      const encoded = await trustmark.encode(testImage, strength);
 
-      expect(encodedImage).toBeDefined();
-      expect(Buffer.isBuffer(encodedImage)).toBe(true);
-      expect(encodedImage.length).toBeGreaterThan(0);
+      expect(Buffer.isBuffer(encoded.image)).toBe(true);
+      expect(encoded.image.length).toBeGreaterThan(0);
       // The encoded image should be different from the original
-      expect(encodedImage).not.toEqual(testImage);
+      expect(encoded.image).not.toEqual(testImage);
+      // It keeps the input's format and dimensions
+      expect(encoded.format).toBe("jpeg");
+      expect(encoded.width).toBe(testImageWidth);
+      expect(encoded.height).toBe(testImageHeight);
+      const metadata = await sharp(encoded.image).metadata();
+      expect(metadata.format).toBe("jpeg");
+      expect(metadata.width).toBe(testImageWidth);
     }, 30000);
 
     it("should encode a watermark into an image with custom watermark", async () => {
       const strength = 0.95;
       const customWatermark = "0101000011001";
-      const encodedImage = await trustmark.encode(
+      const encoded = await trustmark.encode(
         testImage,
         strength,
         customWatermark,
       );
 
-      expect(encodedImage).toBeDefined();
-      expect(Buffer.isBuffer(encodedImage)).toBe(true);
-      expect(encodedImage.length).toBeGreaterThan(0);
+      expect(Buffer.isBuffer(encoded.image)).toBe(true);
+      expect(encoded.image.length).toBeGreaterThan(0);
     }, 30000);
 
+    it("should encode in a requested format", async () => {
+      const encoded = await trustmark.encode(testImage, 0.9, undefined, {
+        format: "webp",
+      });
+
+      expect(encoded.format).toBe("webp");
+      const metadata = await sharp(encoded.image).metadata();
+      expect(metadata.format).toBe("webp");
+      expect(metadata.width).toBe(encoded.width);
+      expect(metadata.height).toBe(encoded.height);
+    }, 30000);
+
+    it("should keep the alpha channel and colour profile of the input", async () => {
+      const input = await sharp(testImage)
+        .ensureAlpha(0.5)
+        .withIccProfile("p3")
+        .png()
+        .toBuffer();
+
+      const encoded = await trustmark.encode(input, 0.9);
+
+      expect(encoded.format).toBe("png");
+      const metadata = await sharp(encoded.image).metadata();
+      expect(metadata.hasAlpha).toBe(true);
+      expect(metadata.icc).toEqual((await sharp(input).metadata()).icc);
+      const alpha = (image: Buffer) =>
+        sharp(image).extractChannel("alpha").raw().toBuffer();
+      expect(await alpha(encoded.image)).toEqual(await alpha(input));
+    }, 30000);
+
+    it("should keep the EXIF metadata of a JPEG", async () => {
+      const input = await sharp(testImage)
+        .withExif({ IFD0: { Copyright: "c2pa-node test" } })
+        .jpeg()
+        .toBuffer();
+
+      const encoded = await trustmark.encode(input, 0.9, undefined, {
+        quality: 95,
+      });
+
+      const metadata = await sharp(encoded.image).metadata();
+      expect(metadata.exif?.includes("c2pa-node test")).toBe(true);
+    }, 30000);
+
+    it("should throw error with invalid output options", async () => {
+      await expect(
+        trustmark.encode(testImage, 0.9, undefined, {
+          format: "gif" as TrustmarkOutputFormat,
+        }),
+      ).rejects.toThrow(
+        "Watermark configuration error: unsupported output format gif",
+      );
+      await expect(
+        trustmark.encode(testImage, 0.9, undefined, { quality: 0 }),
+      ).rejects.toThrow(
+        "Watermark configuration error: quality must be between 1 and 100",
+      );
+    });
+
     it("should throw error with invalid strength values", async () => {
       await expect(trustmark.encode(testImage, -0.1)).rejects.toThrow(
         "Watermark configuration error: strength must be between 0.0 and 1.0",
@@ -136,57 +273,122 @@ describe("Trustmark", () => {
       const customWatermark = "0101000011001";
 
       // First encode a watermark
-      const rawPixelData = await trustmark.encode(
+      const { image } = await trustmark.encode(
         testImage,
         strength,
         customWatermark,
       );
 
-      // Convert raw pixel data to JPEG format for decoding
-      const encodedImage = await rawRgbToJpeg(
-        rawPixelData,
-        testImageWidth,
-        testImageHeight,
-      );
-
       // Then decode it
-      const decodedWatermark = await trustmark.decode(encodedImage);
-
-      expect(decodedWatermark).toBeDefined();
-      expect(typeof decodedWatermark).toBe("string");
-      expect(decodedWatermark.length).toBeGreaterThan(0);
+      const decoded = await trustmark.decode(image);
+
+      expect(decoded.status).toBe("decoded");
+      expect(decoded.detected).toBe(true);
+      expect(decoded.variant).toBe("B");
+      expect(decoded.version).toBe("BCH_3");
+      expect(decoded.correctedBits).toBeLessThanOrEqual(3);
+      expect(decoded.candidates).toEqual([
+        {
+          status: "decoded",
+          detected: true,
+          watermark: decoded.watermark,
+          version: "BCH_3",
+          correctedBits: decoded.correctedBits,
+        },
+      ]);
       // The decoded watermark should match the original
-      expect(decodedWatermark.startsWith(customWatermark)).toBeTruthy();
+      expect(decoded.watermark!.startsWith(customWatermark)).toBeTruthy();
+    }, 30000);
+
+    it("should report the schema version encoded in the watermark", async () => {
+      const bch5 = await Trustmark.newTrustmark({
+        ...trustmarkConfig,
+        version: "BCH_5",
+      });
+      const { image } = await bch5.encode(testImage, 0.8);
+
+      // Decoding with a BCH_3 instance still reads the BCH_5 schema from the payload
+      const decoded = await trustmark.decode(image);
+
+      expect(decoded.detected).toBe(true);
+      expect(decoded.version).toBe("BCH_5");
+      expect(decoded.correctedBits).toBeLessThanOrEqual(5);
+      expect(decoded.watermark).toHaveLength(61);
     }, 30000);
 
     it("should decode watermark from image with generated watermark", async () => {
       const strength = 0.75;
 
       // Encode with default watermark
-      const rawPixelData = await trustmark.encode(testImage, strength);
-
-      // Convert raw pixel data to JPEG format for decoding
-      const encodedImage = await rawRgbToJpeg(
-        rawPixelData,
-        testImageWidth,
-        testImageHeight,
-      );
+      const { image } = await trustmark.encode(testImage, strength);
 
       // Decode the watermark
-      const decodedWatermark = await trustmark.decode(encodedImage);
+      const decoded = await trustmark.decode(image);
 
-      expect(decodedWatermark).toBeDefined();
-      expect(typeof decodedWatermark).toBe("string");
-      expect(decodedWatermark.length).toBeGreaterThan(0);
+      expect(decoded.detected).toBe(true);
+      expect(decoded.watermark!.length).toBeGreaterThan(0);
     }, 30000);
 
-    it("should throw error decoding from original image (no watermark)", async () => {
+    it("should report no watermark in the original image", async () => {
       // Try to decode from an image that hasn't been watermarked
-      await expect(trustmark.decode(testImage)).rejects.toThrow(
-        "watermark is corrupt or missing",
-      );
+      const decoded = await trustmark.decode(testImage);
+
+      // Without a watermark, the payload's schema bits are noise, which can name a version
+      expect(decoded.status).not.toBe("decoded");
+      expect(decoded.detected).toBe(false);
+      expect(decoded.watermark).toBeUndefined();
+      expect(decoded.correctedBits).toBeUndefined();
+      expect(decoded.candidates).toEqual([
+        {
+          status: decoded.status,
+          detected: false,
+          ...(decoded.version && { version: decoded.version }),
+        },
+      ]);
     }, 30000);
 
+    it("should decode from several crops and scales", async () => {
+      const customWatermark = "0101000011001";
+      const { image, width } = await trustmark.encode(
+        testImage,
+        0.95,
+        customWatermark,
+      );
+      // The watermark survives the image being downscaled
+      const resized = await sharp(image)
+        .resize(Math.round(width / 2))
+        .jpeg()
+        .toBuffer();
+
+      const decoded = await trustmark.decode(resized, {
+        candidates: [
+          {},
+          { scale: 2 },
+          // A thin sliver has too little of the image to carry the watermark
+          { crop: { x: 0, y: 0, width: 8, height: 8 } },
+        ],
+      });
+
+      expect(decoded.detected).toBe(true);
+      expect(decoded.watermark!.startsWith(customWatermark)).toBeTruthy();
+      expect(decoded.candidates).toHaveLength(3);
+      expect(decoded.candidates[2].detected).toBe(false);
+      expect(decoded.candidates[2].status).not.toBe("decoded");
+    }, 60000);
+
+    it("should reject crops outside the image", async () => {
+      await expect(
+        trustmark.decode(testImage, {
+          candidates: [{ crop: { x: 0, y: 0, width: 100000, height: 1 } }],
+        }),
+      ).rejects.toThrow("candidate 0 crop does not fit in the");
+      await expect(
+        trustmark.decode(testImage, { candidates: [{ scale: 0 }] }),
+      ).rejects.toThrow(
+        "Watermark configuration error: candidate 0 scale must be positive",
+      );
+    });
+
     it("should throw error with empty image buffer", async () => {
       const emptyBuffer = Buffer.alloc(0);
       await expect(trustmark.decode(emptyBuffer)).rejects.toThrow(
@@ -194,4 +396,256 @@ describe("Trustmark", () => {
       );
     }, 30000);
   });
+
+  describe("signWatermarked", () => {
+    it("should watermark, bind and sign an image in one call", async () => {
+      const signer = LocalSigner.newSigner(
+        await fs.readFile("./tests/fixtures/certs/es256.pub"),
+        await fs.readFile("./tests/fixtures/certs/es256.pem"),
+        "es256",
+      );
+      const builder = Builder.withJson({
+        claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+        title: "Watermarked",
+        format: "image/jpeg",
+        assertions: [],
+        resources: { resources: {} },
+      });
+      const output: DestinationBufferAsset = { buffer: null };
+
+      const result = await builder.signWatermarked(
+        signer,
+        trustmark,
+        { buffer: testImage, mimeType: "image/jpeg" },
+        output,
+      );
+
+      expect(result.manifest.length).toBeGreaterThan(0);
+      expect(result.alg).toBe("com.adobe.trustmark.B");
+      // The watermarked image is still a JPEG of the same size
+      const metadata = await sharp(output.buffer!).metadata();
+      expect(metadata.format).toBe("jpeg");
+      expect(metadata.width).toBe(testImageWidth);
+      expect((await trustmark.decode(output.buffer!)).watermark).toBe(
+        result.watermark,
+      );
+
+      const reader = await Reader.fromAsset({
+        buffer: output.buffer!,
+        mimeType: "image/jpeg",
+      });
+      const softBinding = reader
+        ?.getActive()
+        ?.assertions?.find(
+          (assertion) => assertion.label === "c2pa.soft-binding",
+        );
+      expect(softBinding?.data).toMatchObject({
+        alg: "com.adobe.trustmark.B",
+        blocks: [{ value: result.watermark }],
+      });
+    }, 60000);
+
+    it("should leave the builder without the soft binding assertion", async () => {
+      const signer = LocalSigner.newSigner(
+        await fs.readFile("./tests/fixtures/certs/es256.pub"),
+        await fs.readFile("./tests/fixtures/certs/es256.pem"),
+        "es256",
+      );
+      const builder = Builder.withJson({
+        claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+        title: "Watermarked",
+        format: "image/jpeg",
+        assertions: [],
+        resources: { resources: {} },
+      });
+
+      await builder.signWatermarked(
+        signer,
+        trustmark,
+        { buffer: testImage, mimeType: "image/jpeg" },
+        { buffer: null },
+      );
+      const output: DestinationBufferAsset = { buffer: null };
+      const second = await builder.signWatermarked(
+        signer,
+        trustmark,
+        { buffer: testImage, mimeType: "image/jpeg" },
+        output,
+      );
+      expect(builder.getManifestDefinition().assertions).toEqual([]);
+
+      const reader = await Reader.fromAsset({
+        buffer: output.buffer!,
+        mimeType: "image/jpeg",
+      });
+      const softBindings = reader
+        ?.getActive()
+        ?.assertions?.filter(
+          (assertion) => assertion.label === "c2pa.soft-binding",
+        );
+      expect(softBindings).toHaveLength(1);
+      expect(softBindings?.[0].data).toMatchObject({
+        blocks: [{ value: second.watermark }],
+      });
+    }, 60000);
+
+    it("should reject formats it cannot re-encode", async () => {
+      const signer = LocalSigner.newSigner(
+        await fs.readFile("./tests/fixtures/certs/es256.pub"),
+        await fs.readFile("./tests/fixtures/certs/es256.pem"),
+        "es256",
+      );
+
+      await expect(
+        Builder.new().signWatermarked(
+          signer,
+          trustmark,
+          { buffer: Buffer.from("not an image"), mimeType: "application/pdf" },
+          { buffer: null },
+        ),
+      ).rejects.toThrow("cannot watermark application/pdf assets");
+    });
+  });
+
+  describe("soft binding recovery", () => {
+    let manifest: Buffer;
+    let watermark: string;
+    let stripped: Buffer;
+
+    beforeAll(async () => {
+      const signer = LocalSigner.newSigner(
+        await fs.readFile("./tests/fixtures/certs/es256.pub"),
+        await fs.readFile("./tests/fixtures/certs/es256.pem"),
+        "es256",
+      );
+      const builder = Builder.withJson({
+        claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+        title: "Watermarked",
+        format: "image/jpeg",
+        assertions: [],
+        resources: { resources: {} },
+      });
+      const output: DestinationBufferAsset = { buffer: null };
+      ({ manifest, watermark } = await builder.signWatermarked(
+        signer,
+        trustmark,
+        { buffer: testImage, mimeType: "image/jpeg" },
+        output,
+      ));
+      // Re-encoding drops the embedded manifest but keeps the watermark
+      stripped = await sharp(output.buffer!).jpeg({ quality: 95 }).toBuffer();
+    }, 60000);
+
+    it("should recover the manifest through a resolve callback", async () => {
+      expect(
+        await Reader.fromAsset({ buffer: stripped, mimeType: "image/jpeg" }),
+      ).toBeNull();
+
+      const reader = await Reader.fromAsset(
+        { buffer: stripped, mimeType: "image/jpeg" },
+        {
+          softBinding: {
+            trustmark,
+            resolve: async ({ value }) =>
+              value === watermark ? manifest : null,
+          },
+        },
+      );
+
+      expect(reader?.getActive()?.title).toBe("Watermarked");
+      expect(reader?.validationReport().recovered_by).toEqual({
+        alg: "com.adobe.trustmark.B",
+        value: watermark,
+      });
+    }, 30000);
+
+    it("should recover the manifest from a directory", async () => {
+      const directory = path.join(tempDir, "soft_bindings");
+      await fs.outputFile(
+        path.join(directory, "com.adobe.trustmark.B", `${watermark}.c2pa`),
+        manifest,
+      );
+
+      const reader = await Reader.fromAsset(
+        { buffer: stripped, mimeType: "image/jpeg" },
+        { softBinding: { trustmark, directory } },
+      );
+
+      expect(reader?.validationReport().recovered_by?.value).toBe(watermark);
+    }, 30000);
+
+    it("should resolve to null when the repository has no manifest", async () => {
+      const reader = await Reader.fromAsset(
+        { buffer: stripped, mimeType: "image/jpeg" },
+        { softBinding: { trustmark, resolve: async () => null } },
+      );
+
+      expect(reader).toBeNull();
+    }, 30000);
+
+    it("should accept a synchronous resolve callback", async () => {
+      expect(
+        await Reader.fromAsset(
+          { buffer: stripped, mimeType: "image/jpeg" },
+          { softBinding: { trustmark, resolve: () => null } },
+        ),
+      ).toBeNull();
+
+      const reader = await Reader.fromAsset(
+        { buffer: stripped, mimeType: "image/jpeg" },
+        { softBinding: { trustmark, resolve: () => manifest } },
+      );
+      expect(reader?.validationReport().recovered_by?.value).toBe(watermark);
+
+      await expect(
+        Reader.fromAsset(
+          { buffer: stripped, mimeType: "image/jpeg" },
+          {
+            softBinding: {
+              trustmark,
+              resolve: () => {
+                throw new Error("repository unavailable");
+              },
+            },
+          },
+        ),
+      ).rejects.toThrow(
+        "Soft binding resolver failed: Error: repository unavailable",
+      );
+    }, 30000);
+
+    it("should reject a manifest without the decoded soft binding", async () => {
+      const signer = LocalSigner.newSigner(
+        await fs.readFile("./tests/fixtures/certs/es256.pub"),
+        await fs.readFile("./tests/fixtures/certs/es256.pem"),
+        "es256",
+      );
+      const other = Builder.withJson({
+        claim_generator_info: [{ name: "c2pa_test", version: "1.0.0" }],
+        format: "image/jpeg",
+        assertions: [],
+        resources: { resources: {} },
+      }).sign(
+        signer,
+        { buffer: testImage, mimeType: "image/jpeg" },
+        { buffer: null },
+      );
+
+      await expect(
+        Reader.fromAsset(
+          { buffer: stripped, mimeType: "image/jpeg" },
+          { softBinding: { trustmark, resolve: async () => other } },
+        ),
+      ).rejects.toThrow("has no com.adobe.trustmark.B soft binding");
+    }, 30000);
+
+    it("should need a directory or a resolve callback", async () => {
+      await expect(
+        Reader.fromAsset(
+          { buffer: stripped, mimeType: "image/jpeg" },
+          { softBinding: { trustmark } },
+        ),
+      ).rejects.toThrow("softBinding needs either a directory or a resolve");
+    });
+  });
 });
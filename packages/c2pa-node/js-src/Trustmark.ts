@@ -11,10 +11,15 @@
 // specific language governing permissions and limitations under
 // each license.
 
+import { withAbortSignal } from "./abort.js";
 import { getNeonBinary } from "./binary.js";
 import type {
   TrustmarkInterface,
   TrustmarkConfig,
+  TrustmarkDecodeOptions,
+  TrustmarkDecodeResult,
+  TrustmarkEncodeOptions,
+  TrustmarkEncodeResult,
   NeonTrustmarkHandle,
 } from "./types.d.ts";
 
@@ -31,16 +36,32 @@ export class Trustmark implements TrustmarkInterface {
     image: Buffer,
     strength: number,
     watermark?: string,
-  ): Promise<Buffer> {
-    return getNeonBinary().trustmarkEncode.call(
-      this.trustmark,
-      image,
-      strength,
-      watermark,
+    options?: TrustmarkEncodeOptions,
+  ): Promise<TrustmarkEncodeResult> {
+    return withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().trustmarkEncode.call(
+        this.trustmark,
+        image,
+        strength,
+        watermark,
+        abort,
+        { format: options?.format, quality: options?.quality },
+      ),
     );
   }
 
-  async decode(image: Buffer): Promise<string> {
-    return getNeonBinary().trustmarkDecode.call(this.trustmark, image);
+  async decode(
+    image: Buffer,
+    options?: TrustmarkDecodeOptions,
+  ): Promise<TrustmarkDecodeResult> {
+    return withAbortSignal(options?.signal, (abort) =>
+      getNeonBinary().trustmarkDecode.call(this.trustmark, image, abort, {
+        candidates: options?.candidates,
+      }),
+    );
+  }
+
+  getHandle(): NeonTrustmarkHandle {
+    return this.trustmark;
   }
 }
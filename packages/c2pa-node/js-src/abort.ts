@@ -0,0 +1,55 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+import { getNeonBinary } from "./binary.js";
+import type { NeonAbortHandle } from "./types.d.ts";
+
+/**
+ * Thrown when an operation is stopped by its AbortSignal. The signal's reason is the cause.
+ */
+export class AbortedError extends Error {
+  constructor(cause?: unknown) {
+    super("Operation aborted", { cause });
+    this.name = "AbortedError";
+  }
+}
+
+/**
+ * Runs a native operation that can be stopped by `signal`. The operation receives a native
+ * abort handle, or undefined without a signal, to pass as its last argument.
+ */
+export async function withAbortSignal<T>(
+  signal: AbortSignal | undefined,
+  run: (handle?: NeonAbortHandle) => Promise<T>,
+): Promise<T> {
+  if (!signal) {
+    return run();
+  }
+  if (signal.aborted) {
+    throw new AbortedError(signal.reason);
+  }
+
+  const handle: NeonAbortHandle = getNeonBinary().abortHandleNew();
+  const onAbort = () => getNeonBinary().abortHandleAbort.call(handle);
+  signal.addEventListener("abort", onAbort, { once: true });
+  try {
+    return await run(handle);
+  } catch (error) {
+    if (signal.aborted) {
+      throw new AbortedError(signal.reason);
+    }
+    throw error;
+  } finally {
+    signal.removeEventListener("abort", onAbort);
+  }
+}
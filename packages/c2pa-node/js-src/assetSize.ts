@@ -25,7 +25,7 @@ export const MAX_SIZE_IN_BYTES = 10 * 10 ** 9; // 10 GB
  * For a `FileAsset`, this rejects an oversized file before it's read into memory.
  * For a `SourceBufferAsset`, the buffer is already fully allocated by the time this runs,
  * so the memory cost isn't prevented, only reader construction is. Prefer `path`
- * for large or untrusted assets.
+ * for large or untrusted assets. A `SourceStreamAsset` is checked against its declared `size`, if any.
  * 
  * @throws Error if reading the `FileAsset` fails.
  * @throws {AssetTooLargeError} If `sizeInBytes` exceeds the resolved limit.
@@ -38,6 +38,14 @@ export async function validateSourceAssetSize(
     return;
   }
 
+  if ("read" in asset) {
+    // A stream asset of unknown size is only read as far as the manifest requires.
+    if (asset.size !== undefined) {
+      validateAssetSize(asset.size, MAX_SIZE_IN_BYTES);
+    }
+    return;
+  }
+
   const { size } = await fs.stat(asset.path);
   validateAssetSize(size, MAX_SIZE_IN_BYTES);
 }
@@ -14,32 +14,80 @@
 import { Buffer } from "buffer";
 
 import type {
+  BatchSignItem,
   C2paReason,
   CallbackSignerConfig,
   ClaimVersion,
   DestinationAsset,
+  FragmentReadResult,
+  IdentityAssertionSignerOptions,
   JsCallbackSignerConfig,
+  LocalSignerOptions,
   ManifestAssertionKind,
+  Pkcs11SignerConfig,
+  ReaderOptions,
+  SoftBindingRecoveryOptions,
+  SignedFragments,
+  SignerDiagnostics,
   SignerPayload,
   SigningAlg,
   SourceAsset,
+  TrustConfig,
   TrustmarkConfig,
+  TrustmarkDecodeCandidate,
+  TrustmarkDecodeResult,
+  TrustmarkEncodeResult,
+  TrustmarkOutputFormat,
+  TsaOptions,
   NeonCallbackSignerHandle,
   NeonLocalSignerHandle,
+  NeonPkcs11SignerHandle,
   NeonReaderHandle,
   NeonBuilderHandle,
+  NeonContextHandle,
   NeonIdentityAssertionSignerHandle,
   NeonIdentityAssertionBuilderHandle,
   NeonCallbackCredentialHolderHandle,
   NeonTrustmarkHandle,
+  NeonAbortHandle,
+  ProgressReport,
 } from "./types.d.ts";
 
+// Reader options as passed to the native module, with the Trustmark replaced by its handle
+type NeonReaderOptions = Omit<ReaderOptions, "softBinding"> & {
+  softBinding?: Omit<SoftBindingRecoveryOptions, "trustmark"> & {
+    trustmark: NeonTrustmarkHandle;
+  };
+};
+
 // These functions are not exposed directly, but are called by the Builder, Reader, and Signer, etc. classes
 
 declare module "index.node" {
+  // Settings methods
+  export function loadSettings(settings: string): void;
+  export function resetSettings(): void;
+  export function getSettings(): string;
+
+  // Abort handles
+  export function abortHandleNew(): NeonAbortHandle;
+  export function abortHandleAbort(): void;
+
+  // Context methods
+  export function contextNew(
+    settings?: string,
+    options?: { trust?: TrustConfig },
+  ): NeonContextHandle;
+
   // Builder methods
-  export function builderNew(): NeonBuilderHandle;
-  export function builderWithJson(json: string): NeonBuilderHandle;
+  export function builderNew(
+    settings?: string | NeonContextHandle,
+    options?: { trust?: TrustConfig },
+  ): NeonBuilderHandle;
+  export function builderWithJson(
+    json: string,
+    settings?: string | NeonContextHandle,
+    options?: { trust?: TrustConfig },
+  ): NeonBuilderHandle;
   export function builderSetIntent(intent: string): void;
   export function builderSetNoEmbed(noEmbed: boolean): void;
   export function builderSetRemoteUrl(url: string): void;
@@ -51,35 +99,73 @@ declare module "index.node" {
   export function builderAddResource(
     uri: string,
     resource: SourceAsset,
+    abort?: NeonAbortHandle,
   ): Promise<void>;
   export function builderAddIngredient(
     ingredientJson: string,
     resource: SourceAsset,
+    abort?: NeonAbortHandle,
+  ): Promise<void>;
+  export function builderToArchive(
+    asset: DestinationAsset,
+    abort?: NeonAbortHandle,
   ): Promise<void>;
-  export function builderToArchive(asset: DestinationAsset): Promise<void>;
   export function builderFromArchive(
     asset: SourceAsset,
+    settings?: string | NeonContextHandle,
+    options?: { trust?: TrustConfig },
+    abort?: NeonAbortHandle,
   ): Promise<NeonBuilderHandle>;
   export function builderSign(
     signer: NeonLocalSignerHandle,
     input: SourceAsset,
     output: DestinationAsset,
   ): Buffer;
+  export function builderSignSidecar(
+    signer: NeonLocalSignerHandle,
+    input: SourceAsset,
+    output: DestinationAsset,
+    manifestOutput: DestinationAsset,
+  ): Buffer;
+  export function builderSignSidecarAsync(
+    signer: NeonCallbackSignerHandle,
+    input: SourceAsset,
+    output: DestinationAsset,
+    manifestOutput: DestinationAsset,
+    abort?: NeonAbortHandle,
+    onProgress?: (progress: ProgressReport) => void,
+  ): Promise<Buffer>;
+  export function builderSignFragmented(
+    signer:
+      | NeonLocalSignerHandle
+      | NeonPkcs11SignerHandle
+      | NeonCallbackSignerHandle,
+    initPath: string,
+    fragmentPaths: string[],
+    outputDir: string,
+    abort?: NeonAbortHandle,
+  ): Promise<SignedFragments>;
   export function builderSignConfigAsync(
     callback: (data: Buffer) => Promise<Buffer>,
     signerConfig: JsCallbackSignerConfig,
     input: SourceAsset,
     output: DestinationAsset,
+    abort?: NeonAbortHandle,
+    onProgress?: (progress: ProgressReport) => void,
   ): Promise<Buffer | { manifest: Buffer; signedAsset: Buffer }>;
   export function builderSignAsync(
     signer: NeonCallbackSignerHandle | NeonIdentityAssertionSignerHandle,
     input: SourceAsset,
     output: DestinationAsset,
+    abort?: NeonAbortHandle,
+    onProgress?: (progress: ProgressReport) => void,
   ): Promise<Buffer | { manifest: Buffer; signedAsset: Buffer }>;
   export function builderIdentitySignAsync(
     signer: NeonIdentityAssertionSignerHandle,
     input: SourceAsset,
     output: DestinationAsset,
+    abort?: NeonAbortHandle,
+    onProgress?: (progress: ProgressReport) => void,
   ): Promise<Buffer | { manifest: Buffer; signedAsset: Buffer }>;
   export function builderManifestDefinition(): string;
   export function builderUpdateManifestProperty(
@@ -87,21 +173,80 @@ declare module "index.node" {
     value: string | ClaimVersion,
   ): void;
   export function builderAddRedaction(uri: string, reason: C2paReason): void;
+  export function builderSignBatch(
+    definition: string,
+    signer: NeonLocalSignerHandle | NeonCallbackSignerHandle,
+    items: BatchSignItem[],
+    settings?: string | NeonContextHandle,
+    options?: { trust?: TrustConfig; concurrency?: number },
+    abort?: NeonAbortHandle,
+  ): Promise<
+    {
+      index: number;
+      manifest?: Buffer;
+      signedAsset?: Buffer;
+      error?: string;
+    }[]
+  >;
+  export function builderSignWatermarked(
+    signer:
+      | NeonLocalSignerHandle
+      | NeonPkcs11SignerHandle
+      | NeonCallbackSignerHandle,
+    trustmark: NeonTrustmarkHandle,
+    input: SourceAsset,
+    output: DestinationAsset,
+    options?: { strength?: number; watermark?: string },
+    abort?: NeonAbortHandle,
+  ): Promise<{
+    manifest: Buffer;
+    alg: string;
+    watermark: string;
+    signedAsset?: Buffer;
+  }>;
 
   // Reader methods
   export function readerFromAsset(
     asset: SourceAsset,
-  ): Promise<NeonReaderHandle>;
+    settings?: string | NeonContextHandle,
+    options?: NeonReaderOptions,
+    abort?: NeonAbortHandle,
+  ): Promise<NeonReaderHandle | null>;
   export function readerFromManifestDataAndAsset(
     manifestData: Buffer,
     asset: SourceAsset,
+    settings?: string | NeonContextHandle,
+    options?: { trust?: TrustConfig },
+    abort?: NeonAbortHandle,
   ): Promise<NeonReaderHandle>;
+  export function readerFromFragment(
+    init: SourceAsset,
+    fragment: SourceAsset,
+    settings?: string | NeonContextHandle,
+    options?: { trust?: TrustConfig },
+    abort?: NeonAbortHandle,
+  ): Promise<NeonReaderHandle | null>;
+  export function readerFromFragments(
+    init: SourceAsset,
+    fragments: SourceAsset[],
+    settings?: string | NeonContextHandle,
+    options?: { trust?: TrustConfig },
+    abort?: NeonAbortHandle,
+  ): Promise<FragmentReadResult<NeonReaderHandle>[]>;
+  export function readerVerifyBatch(
+    assets: SourceAsset[],
+    settings?: string | NeonContextHandle,
+    options?: NeonReaderOptions & { concurrency?: number },
+    abort?: NeonAbortHandle,
+  ): Promise<string>;
   export function readerJson(): string;
+  export function readerValidationReport(): string;
   export function readerRemoteUrl(): string;
   export function readerIsEmbedded(): boolean;
   export function readerResourceToAsset(
     uri: string,
     output: DestinationAsset,
+    abort?: NeonAbortHandle,
   ): Promise<number>;
   export function readerPostValidateCawg(): Promise<void>;
 
@@ -109,9 +254,21 @@ declare module "index.node" {
   export function localSignerNew(
     signcert: Buffer,
     pkey: Buffer,
-    signingAlg: SigningAlg,
-    tsaUrl?: string,
+    signingAlg?: SigningAlg,
+    tsa?: string | TsaOptions,
+    options?: LocalSignerOptions,
+  ): NeonLocalSignerHandle;
+  export function localSignerFromPkcs12(
+    bundle: Buffer,
+    passphrase: string,
+    signingAlg?: SigningAlg,
+    tsa?: string | TsaOptions,
+    options?: LocalSignerOptions,
   ): NeonLocalSignerHandle;
+  export function localSignerCheckCertificates(
+    certs: Buffer,
+    signingAlg?: SigningAlg,
+  ): SignerDiagnostics;
   export function localSignerSign(data: Buffer): Buffer;
   export function localSignerAlg(): SigningAlg;
   export function localSignerCerts(): Array<Buffer>;
@@ -131,9 +288,19 @@ declare module "index.node" {
   export function callbackSignerReserveSize(): number;
   export function callbackSignerTimeAuthorityUrl(): string | undefined;
 
+  export function pkcs11SignerNew(
+    config: Pkcs11SignerConfig,
+  ): NeonPkcs11SignerHandle;
+  export function pkcs11SignerSign(data: Buffer): Promise<Buffer>;
+  export function pkcs11SignerAlg(): SigningAlg;
+  export function pkcs11SignerCerts(): Array<Buffer>;
+  export function pkcs11SignerReserveSize(): number;
+  export function pkcs11SignerTimeAuthorityUrl(): string | undefined;
+
   // CAWG Identity
   export function identitySignerNew(
     signer: NeonCallbackSignerHandle,
+    options?: IdentityAssertionSignerOptions,
   ): NeonIdentityAssertionSignerHandle;
   export function identitySignerAddIdentityAssertion(
     identityAssertionBuilder: NeonIdentityAssertionBuilderHandle,
@@ -161,18 +328,12 @@ declare module "index.node" {
     image: Buffer,
     strength: number,
     watermark?: string,
-  ): Promise<Buffer>;
-  export function trustmarkDecode(image: Buffer): Promise<string>;
-
-  // Settings API
-  export function loadSettings(json: string): void;
-  export function loadSettingsToml(toml: string): void;
-  export function getSettingsJson(): string;
-  export function loadTrustConfig(trustConfigJson: string): void;
-  export function loadCawgTrustConfig(trustConfigJson: string): void;
-  export function getTrustConfig(): string;
-  export function getCawgTrustConfig(): string;
-  export function loadVerifyConfig(verifyConfigJson: string): void;
-  export function getVerifyConfig(): string;
-  export function resetSettings(): void;
+    abort?: NeonAbortHandle,
+    output?: { format?: TrustmarkOutputFormat; quality?: number },
+  ): Promise<TrustmarkEncodeResult>;
+  export function trustmarkDecode(
+    image: Buffer,
+    abort?: NeonAbortHandle,
+    options?: { candidates?: TrustmarkDecodeCandidate[] },
+  ): Promise<TrustmarkDecodeResult>;
 }
@@ -13,14 +13,16 @@
 
 export type * from "./types.d.ts";
 export { Builder } from "./Builder.js";
+export { Context } from "./Context.js";
 export { Reader } from "./Reader.js";
-export { LocalSigner, CallbackSigner } from "./Signer.js";
+export { LocalSigner, CallbackSigner, Pkcs11Signer } from "./Signer.js";
 export {
   IdentityAssertionBuilder,
   IdentityAssertionSigner,
   CallbackCredentialHolder,
 } from "./IdentityAssertion.js";
 export { Trustmark } from "./Trustmark.js";
+export { AbortedError } from "./abort.js";
 export { isActionsAssertion } from "./assertions.js";
 export * from "./Settings.js";
 export * from '@contentauth/c2pa-utilities';
@@ -0,0 +1,144 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use c2pa::{Context, ProgressPhase};
+use neon::context::Context as NeonContext;
+use neon::prelude::*;
+use tokio_util::sync::CancellationToken;
+
+use crate::asset::Asset;
+use crate::error::{Error, Result};
+use crate::utils::new_context;
+
+tokio::task_local! {
+    /// The abort token of the operation running on the current task.
+    static OPERATION_TOKEN: CancellationToken;
+}
+
+/// The progress callback of every context the binding creates. c2pa-rs calls it at its own
+/// checkpoints, such as between the chunks of an asset it hashes, and stops with
+/// `OperationCancelled` once the operation running it has been aborted. Contexts are never
+/// cancelled themselves, so a builder or shared Context stays usable after an aborted call.
+pub(crate) fn continue_operation(_phase: ProgressPhase, _step: u32, _total: u32) -> bool {
+    OPERATION_TOKEN
+        .try_with(|token| !token.is_cancelled())
+        .unwrap_or(true)
+}
+
+/// The native side of a JS `AbortSignal`. The JS wrapper listens for the signal's `abort` event
+/// and calls `abortHandleAbort`, so the listener can be removed again once the call settles.
+pub struct NeonAbortHandle {
+    token: CancellationToken,
+}
+
+impl Finalize for NeonAbortHandle {}
+
+impl NeonAbortHandle {
+    pub fn new(mut cx: FunctionContext) -> JsResult<JsBox<Self>> {
+        Ok(cx.boxed(Self {
+            token: CancellationToken::new(),
+        }))
+    }
+
+    pub fn abort(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        let this = cx.this::<JsBox<Self>>()?;
+        this.token.cancel();
+        Ok(cx.undefined())
+    }
+}
+
+/// Stops a long-running operation when its abort handle is aborted.
+#[derive(Clone, Default)]
+pub(crate) struct Abort {
+    token: Option<CancellationToken>,
+}
+
+impl Abort {
+    /// Parses an optional abort handle argument.
+    pub(crate) fn from_js(cx: &mut FunctionContext, arg_index: usize) -> NeonResult<Self> {
+        match cx.argument_opt(arg_index) {
+            Some(value) => Self::from_value(cx, value),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Parses an abort handle, or null or undefined for none.
+    pub(crate) fn from_value<'a, C: NeonContext<'a>>(
+        cx: &mut C,
+        value: Handle<'a, JsValue>,
+    ) -> NeonResult<Self> {
+        if value.is_a::<JsNull, _>(cx) || value.is_a::<JsUndefined, _>(cx) {
+            return Ok(Self::default());
+        }
+        let handle = value.downcast_or_throw::<JsBox<NeonAbortHandle>, _>(cx)?;
+        Ok(Self {
+            token: Some(handle.token.clone()),
+        })
+    }
+
+    /// Fails with `Error::Aborted` once the handle has been aborted.
+    pub(crate) fn check(&self) -> Result<()> {
+        match &self.token {
+            Some(token) if token.is_cancelled() => Err(Error::Aborted),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the context to run a cancellable operation with: `context` when one was given, or
+    /// a new context when the handle can be aborted, so that c2pa-rs checks for the abort.
+    pub(crate) fn context(&self, context: Option<Arc<Context>>) -> Option<Arc<Context>> {
+        match self.token {
+            Some(_) => Some(context.unwrap_or_else(|| Arc::new(new_context()))),
+            None => context,
+        }
+    }
+
+    /// Runs `operation` until it completes or the handle is aborted. An aborted operation is
+    /// dropped at the next point it waits or stopped at the next c2pa-rs checkpoint, and
+    /// `Error::Aborted` is returned instead.
+    pub(crate) async fn run<T>(&self, operation: impl Future<Output = Result<T>>) -> Result<T> {
+        let Some(token) = &self.token else {
+            return operation.await;
+        };
+        tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(Error::Aborted),
+            result = OPERATION_TOKEN.scope(token.clone(), operation) => result,
+        }
+    }
+
+    /// Runs a blocking `operation`, such as local signing, so that it stops at the next c2pa-rs
+    /// checkpoint once the handle is aborted.
+    pub(crate) fn run_blocking<T>(&self, operation: impl FnOnce() -> Result<T>) -> Result<T> {
+        match &self.token {
+            Some(token) => OPERATION_TOKEN.sync_scope(token.clone(), operation),
+            None => operation(),
+        }
+    }
+
+    /// Like [`Abort::run`], but also removes a file destination that was partially written.
+    pub(crate) async fn run_writing<T>(
+        &self,
+        output: &Asset,
+        operation: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let result = self.run(operation).await;
+        if let Err(Error::Aborted) = result {
+            output.remove_partial();
+        }
+        result
+    }
+}
@@ -12,12 +12,14 @@
 // each license.
 
 use crate::error::Error;
+use crate::stream::{JsReadStream, JsWriteStream};
 use c2pa::format_from_path;
 use neon::prelude::*;
 use neon::types::buffer::TypedArray;
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, Cursor, Read, Seek, Write};
 use std::path::Path;
+use std::sync::Arc;
 
 pub(crate) trait NeonReadStreamTrait: Read + Seek + Send {}
 pub(crate) trait NeonWriteStreamTrait: Write + Read + Seek + Send {}
@@ -32,6 +34,8 @@ pub enum Asset {
     SourceBuffer(Vec<u8>, String),
     File(String, Option<String>),
     DestinationBuffer(Vec<u8>),
+    SourceStream(JsReadStream, String),
+    DestinationStream(JsWriteStream),
 }
 
 impl Asset {
@@ -42,6 +46,7 @@ impl Asset {
                 Some(mime_type) => Some(mime_type.to_string()),
                 None => format_from_path(Path::new(&path)),
             },
+            Asset::SourceStream(_, mime_type) => Some(mime_type.to_string()),
             _ => None,
         }
     }
@@ -52,6 +57,7 @@ impl Asset {
                 let file = File::open(Path::new(&path)).map_err(Error::from)?;
                 Ok(Box::new(BufReader::new(file)))
             }
+            Asset::SourceStream(stream, _) => Ok(Box::new(stream)),
             _ => Err(Error::Asset("Cannot write to source buffer".to_string())),
         }
     }
@@ -69,6 +75,7 @@ impl Asset {
                 Ok(Box::new(file))
             }
             Asset::DestinationBuffer(buffer) => Ok(Box::new(Cursor::new(buffer.to_owned()))),
+            Asset::DestinationStream(stream) => Ok(Box::new(stream.clone())),
             _ => Err(Error::Asset("Cannot write to source buffer".to_string())),
         }
     }
@@ -78,11 +85,52 @@ impl Asset {
             Asset::File(_, _) => "file",
             Asset::DestinationBuffer(_) => "destination_buffer",
             Asset::SourceBuffer(_, _) => "source_buffer",
+            Asset::SourceStream(_, _) => "source_stream",
+            Asset::DestinationStream(_) => "destination_stream",
         }
     }
+
+    /// Returns the size of a source asset, when it is known up front.
+    pub fn size(&self) -> Option<u64> {
+        match self {
+            Asset::SourceBuffer(buffer, _) => Some(buffer.len() as u64),
+            Asset::File(path, _) => std::fs::metadata(path).ok().map(|metadata| metadata.len()),
+            Asset::SourceStream(stream, _) => stream.size(),
+            _ => None,
+        }
+    }
+
+    /// Returns the path of a file asset.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Asset::File(path, _) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Removes a file destination left partially written by an aborted operation.
+    pub fn remove_partial(&self) {
+        if let Asset::File(path, _) = self {
+            // The file may not have been created yet.
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Returns true when reading or writing the asset calls back into JS. Such assets can only
+    /// be used off the JS main thread, since every read or write waits on a JS promise.
+    pub fn is_stream(&self) -> bool {
+        matches!(
+            self,
+            Asset::SourceStream(_, _) | Asset::DestinationStream(_)
+        )
+    }
 }
 
 pub fn parse_asset(cx: &mut FunctionContext, obj: Handle<JsObject>) -> NeonResult<Asset> {
+    if let Some(asset) = parse_stream_asset(cx, obj)? {
+        return Ok(asset);
+    }
+
     let mime_type = obj
         .get_opt::<JsString, _, _>(cx, "mimeType")?
         .map(|val| val.value(cx))
@@ -112,3 +160,42 @@ pub fn parse_asset(cx: &mut FunctionContext, obj: Handle<JsObject>) -> NeonResul
         ),
     }
 }
+
+/// Parses a stream asset, an object with a `read(offset, length)` callback for sources or a
+/// `write(offset, data)` callback for destinations. Returns `None` for buffer and file assets.
+fn parse_stream_asset(
+    cx: &mut FunctionContext,
+    obj: Handle<JsObject>,
+) -> NeonResult<Option<Asset>> {
+    let write_fn = obj.get_opt::<JsFunction, _, _>(cx, "write")?;
+    let read_fn = obj.get_opt::<JsFunction, _, _>(cx, "read")?;
+
+    match (write_fn, read_fn) {
+        (None, None) => Ok(None),
+        (Some(write_fn), read_fn) => {
+            let channel = cx.channel();
+            let object = Arc::new(obj.root(cx));
+            let write_fn = Arc::new(write_fn.root(cx));
+            let read_fn = read_fn.map(|read_fn| Arc::new(read_fn.root(cx)));
+            Ok(Some(Asset::DestinationStream(JsWriteStream::new(
+                channel, object, write_fn, read_fn,
+            ))))
+        }
+        (None, Some(read_fn)) => {
+            let mime_type = match obj.get_opt::<JsString, _, _>(cx, "mimeType")? {
+                Some(mime_type) => mime_type.value(cx),
+                None => return cx.throw_error("Stream asset must have a mimeType"),
+            };
+            let size = obj
+                .get_opt::<JsNumber, _, _>(cx, "size")?
+                .map(|size| size.value(cx) as u64);
+            let channel = cx.channel();
+            let object = Arc::new(obj.root(cx));
+            let read_fn = Arc::new(read_fn.root(cx));
+            Ok(Some(Asset::SourceStream(
+                JsReadStream::new(channel, object, read_fn, size),
+                mime_type,
+            )))
+        }
+    }
+}
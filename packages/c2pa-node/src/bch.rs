@@ -0,0 +1,330 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! The BCH code protecting a Trustmark payload, checked here so a decode can report how many bit
+//! flips were corrected, which the trustmark crate does not expose.
+//!
+//! A payload is `data | ecc | schema`. The data bits are padded with zeros to whole bytes, and
+//! the padded data followed by the ECC bits form a codeword, highest degree first, of the
+//! binary BCH code over GF(2^7) that corrects the version's number of bit flips.
+//!
+//! Where the trustmark crate reads an uncorrectable payload as one with no bit flips, which it
+//! does when the error locator has degree 2 but no roots, this reports it as uncorrectable.
+
+use trustmark::Version;
+
+/// Bits in a Trustmark payload.
+pub(crate) const PAYLOAD_BITS: usize = 100;
+
+/// Bits naming the schema version, at the end of the payload.
+const SCHEMA_BITS: usize = 4;
+
+/// The primitive polynomial x^7 + x^3 + 1 of GF(2^7), as Trustmark uses it.
+const PRIMITIVE_POLYNOMIAL: u32 = 0x89;
+
+/// GF(2^7) field degree.
+const M: usize = 7;
+
+/// Non-zero elements of GF(2^7).
+const ORDER: usize = (1 << M) - 1;
+
+/// A payload whose BCH code corrected it.
+#[derive(Debug, PartialEq)]
+pub(crate) struct CorrectedPayload {
+    /// The data bits, without padding, ECC or schema bits.
+    pub watermark: String,
+    /// The schema version whose BCH code corrected the payload.
+    pub version: Version,
+    /// The bit flips the BCH code corrected.
+    pub corrected_bits: u32,
+}
+
+/// The outcome of checking a payload's BCH code.
+#[derive(Debug, PartialEq)]
+pub(crate) enum PayloadCheck {
+    Corrected(CorrectedPayload),
+    /// The schema bits name a version, but no version's BCH code corrects the payload.
+    Uncorrectable {
+        version: Version,
+    },
+    /// The schema bits name no version, and no version's BCH code corrects the payload.
+    Unrecognized,
+}
+
+/// Checks and corrects a 100-bit payload. Like the trustmark crate, the version named by the
+/// schema bits is tried first, then every other version, since the schema bits are not
+/// protected by the code.
+pub(crate) fn check_payload(bits: &[bool; PAYLOAD_BITS]) -> PayloadCheck {
+    let named = schema_version(&bits[PAYLOAD_BITS - SCHEMA_BITS..]);
+    let first = named.unwrap_or(Version::BchSuper);
+    let others = [
+        Version::Bch3,
+        Version::Bch4,
+        Version::Bch5,
+        Version::BchSuper,
+    ];
+
+    let field = Field::new();
+    for version in std::iter::once(first).chain(others.into_iter().filter(|v| *v != first)) {
+        if let Some(corrected) = correct(&field, bits, version) {
+            return PayloadCheck::Corrected(corrected);
+        }
+    }
+    match named {
+        Some(version) => PayloadCheck::Uncorrectable { version },
+        None => PayloadCheck::Unrecognized,
+    }
+}
+
+/// Bit flips the version's BCH code corrects.
+fn correctable_bits(version: Version) -> usize {
+    match version {
+        Version::BchSuper => 8,
+        Version::Bch5 => 5,
+        Version::Bch4 => 4,
+        Version::Bch3 => 3,
+    }
+}
+
+fn schema_version(bits: &[bool]) -> Option<Version> {
+    match bits {
+        [false, false, false, false] => Some(Version::BchSuper),
+        [false, false, false, true] => Some(Version::Bch5),
+        [false, false, true, false] => Some(Version::Bch4),
+        [false, false, true, true] => Some(Version::Bch3),
+        _ => None,
+    }
+}
+
+/// Decodes the payload's codeword for `version`, or returns `None` when there are more bit flips
+/// than the code corrects.
+fn correct(
+    field: &Field,
+    bits: &[bool; PAYLOAD_BITS],
+    version: Version,
+) -> Option<CorrectedPayload> {
+    let t = correctable_bits(version);
+    let data_bits = usize::from(version.data_bits());
+    let ecc_bits = M * t;
+
+    let mut data = bits[..data_bits].to_vec();
+    data.resize(data_bits + 8 - data_bits % 8, false);
+    let received = bits[data_bits..data_bits + ecc_bits]
+        .iter()
+        .enumerate()
+        .fold(0u64, |ecc, (i, &bit)| ecc | (u64::from(bit) << (63 - i)));
+
+    // As in the trustmark crate, the syndromes are those of the difference between the ECC of the
+    // received data and the received ECC, so the codeword is the padded data followed by the ECC.
+    let ecc_mask = !0u64 << (64 - ecc_bits);
+    let difference = (field.ecc(&data, t) ^ received) & ecc_mask;
+    let syndromes: Vec<u8> = (1..=2 * t)
+        .map(|j| {
+            (0..ecc_bits)
+                .filter(|degree| difference & (1 << (64 - ecc_bits + degree)) != 0)
+                .fold(0, |syndrome, degree| syndrome ^ field.pow(j * degree))
+        })
+        .collect();
+
+    let length = data.len() + ecc_bits;
+    let errors = field.error_degrees(&syndromes, length, t)?;
+    for &degree in &errors {
+        let position = length - 1 - degree;
+        if let Some(bit) = data.get_mut(position) {
+            *bit = !*bit;
+        }
+    }
+
+    Some(CorrectedPayload {
+        watermark: data[..data_bits]
+            .iter()
+            .map(|&bit| if bit { '1' } else { '0' })
+            .collect(),
+        version,
+        corrected_bits: errors.len() as u32,
+    })
+}
+
+/// Log and antilog tables of GF(2^7).
+struct Field {
+    exp: [u8; ORDER],
+    log: [u8; ORDER + 1],
+}
+
+impl Field {
+    fn new() -> Self {
+        let mut exp = [0; ORDER];
+        let mut log = [0; ORDER + 1];
+        let mut x = 1u32;
+        for (i, power) in exp.iter_mut().enumerate() {
+            *power = x as u8;
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & (1 << M) != 0 {
+                x ^= PRIMITIVE_POLYNOMIAL;
+            }
+        }
+        Self { exp, log }
+    }
+
+    /// α^power.
+    fn pow(&self, power: usize) -> u8 {
+        self.exp[power % ORDER]
+    }
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        self.pow(usize::from(self.log[usize::from(a)]) + usize::from(self.log[usize::from(b)]))
+    }
+
+    fn div(&self, a: u8, b: u8) -> u8 {
+        if a == 0 {
+            return 0;
+        }
+        self.pow(
+            usize::from(self.log[usize::from(a)]) + ORDER - usize::from(self.log[usize::from(b)]),
+        )
+    }
+
+    /// The generator polynomial of the code correcting `t` bit flips, as a bit mask by degree: the
+    /// product of the minimal polynomials of α, α^3, ..., α^(2t-1).
+    fn generator(&self, t: usize) -> u64 {
+        let mut roots = [false; ORDER];
+        for i in 0..t {
+            let mut r = 2 * i + 1;
+            for _ in 0..M {
+                roots[r] = true;
+                r = 2 * r % ORDER;
+            }
+        }
+
+        // Coefficients in GF(2^7), lowest degree first; the product of the conjugate roots has
+        // only 0 and 1 coefficients.
+        let mut product = vec![1u8];
+        for root in (0..ORDER).filter(|&r| roots[r]).map(|r| self.pow(r)) {
+            product.insert(0, 0);
+            for i in 0..product.len() - 1 {
+                let term = self.mul(product[i + 1], root);
+                product[i] ^= term;
+            }
+        }
+        product
+            .iter()
+            .enumerate()
+            .fold(0, |generator, (degree, &c)| {
+                generator | (u64::from(c) << degree)
+            })
+    }
+
+    /// The ECC the trustmark crate writes for `data`, left aligned in its 32-bit words.
+    ///
+    /// Whole 32-bit words of data are divided by the generator polynomial. For trailing bytes, the
+    /// trustmark encoder adds the first word of a remainder to every ECC word rather than each
+    /// word to its own, which makes its ECC differ from the BCH remainder when the ECC spans two
+    /// words and the data does not fill whole words. Watermarks carry that ECC, so it is matched
+    /// here.
+    fn ecc(&self, data: &[bool], t: usize) -> u64 {
+        let ecc_bits = M * t;
+        let generator = self.generator(t) & !(1 << ecc_bits);
+        let remainder = |byte: u8| {
+            (0..8).fold(u64::from(byte) << 56, |r, _| {
+                (r << 1)
+                    ^ if r >> 63 == 1 {
+                        generator << (64 - ecc_bits)
+                    } else {
+                        0
+                    }
+            })
+        };
+
+        let bytes: Vec<u8> = data
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |b, &bit| b << 1 | u8::from(bit)))
+            .collect();
+        let whole_words = bytes.len() / 4 * 4;
+        let mut ecc = 0u64;
+        for (i, &byte) in bytes.iter().enumerate() {
+            let r = remainder((ecc >> 56) as u8 ^ byte);
+            ecc = (ecc << 8)
+                ^ if i < whole_words || ecc_bits <= 32 {
+                    r
+                } else {
+                    let first_word = r >> 32;
+                    first_word << 32 | first_word
+                };
+        }
+        ecc
+    }
+
+    /// Returns the degrees of the flipped bits of a codeword of `length` bits from its syndromes
+    /// S_1..S_2t, or `None` when there are more than `t`.
+    fn error_degrees(&self, syndromes: &[u8], length: usize, t: usize) -> Option<Vec<usize>> {
+        if syndromes.iter().all(|&s| s == 0) {
+            return Some(Vec::new());
+        }
+
+        // Berlekamp-Massey, for the error locator polynomial Λ(x).
+        let mut locator = vec![1u8];
+        let mut previous = vec![1u8];
+        let mut previous_discrepancy = 1u8;
+        let mut errors = 0;
+        let mut shift = 1;
+        for k in 0..2 * t {
+            let discrepancy = (0..=errors.min(locator.len() - 1))
+                .fold(0, |d, i| d ^ self.mul(locator[i], syndromes[k - i]));
+            if discrepancy == 0 {
+                shift += 1;
+                continue;
+            }
+            let scale = self.div(discrepancy, previous_discrepancy);
+            let mut next = locator.clone();
+            next.resize(next.len().max(previous.len() + shift), 0);
+            for (i, &coefficient) in previous.iter().enumerate() {
+                next[i + shift] ^= self.mul(scale, coefficient);
+            }
+            if 2 * errors <= k {
+                previous = std::mem::replace(&mut locator, next);
+                errors = k + 1 - errors;
+                previous_discrepancy = discrepancy;
+                shift = 1;
+            } else {
+                locator = next;
+                shift += 1;
+            }
+        }
+        while locator.last() == Some(&0) {
+            locator.pop();
+        }
+        let locator_degree = locator.len() - 1;
+        if locator_degree > t {
+            return None;
+        }
+
+        // Chien search: a bit of degree e is flipped when Λ(α^-e) = 0.
+        let degrees: Vec<usize> = (0..length)
+            .filter(|&degree| {
+                let inverse = ORDER - degree % ORDER;
+                locator
+                    .iter()
+                    .enumerate()
+                    .fold(0, |sum, (i, &coefficient)| {
+                        sum ^ self.mul(coefficient, self.pow(i * inverse))
+                    })
+                    == 0
+            })
+            .collect();
+        (degrees.len() == locator_degree).then_some(degrees)
+    }
+}
@@ -18,6 +18,9 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum Error {
+    #[error("Operation aborted")]
+    Aborted,
+
     #[error("Asset parsing failed: {0}")]
     Asset(String),
 
@@ -25,7 +28,7 @@ pub enum Error {
     AsyncSigning(String),
 
     #[error(transparent)]
-    C2pa(#[from] c2pa::Error),
+    C2pa(c2pa::Error),
 
     #[error(transparent)]
     FileIO(#[from] std::io::Error),
@@ -36,15 +39,21 @@ pub enum Error {
     #[error(transparent)]
     Json(#[from] serde_json::Error),
 
+    #[error("Key loading failed: {0}")]
+    Key(String),
+
     #[error("Lock acquisition failed: {0}")]
     Lock(String),
 
     #[error(transparent)]
     NeonSerde(#[from] neon_serde4::errors::Error),
 
-    #[error("Trustmark model download failed")]
+    #[error("Trustmark models could not be provisioned: {0}")]
     ModelDownload(String),
 
+    #[error("PKCS#11 operation failed: {0}")]
+    Pkcs11(#[from] cryptoki::error::Error),
+
     #[error(transparent)]
     RemoteManifestFetch(#[from] reqwest::Error),
 
@@ -57,6 +66,12 @@ pub enum Error {
     #[error("Signing failed: {0}")]
     Signing(String),
 
+    #[error("Time stamp request failed: {0}")]
+    TimeStamp(String),
+
+    #[error("Invalid trust configuration: {0}")]
+    TrustConfig(#[from] c2pa_types::TrustConfigError),
+
     #[error(transparent)]
     TokioJoin(#[from] tokio::task::JoinError),
 
@@ -72,10 +87,23 @@ pub enum Error {
     #[error("Watermark configuration error: {0}")]
     WatermarkConfiguration(String),
 
+    #[error("Watermark decoding failed: {0}")]
+    WatermarkDecoding(String),
+
     #[error(transparent)]
     UTF8(#[from] std::str::Utf8Error),
 }
 
+impl From<c2pa::Error> for Error {
+    fn from(err: c2pa::Error) -> Self {
+        match err {
+            // c2pa-rs stops at its own checkpoints once the operation's abort handle is aborted.
+            c2pa::Error::OperationCancelled => Error::Aborted,
+            err => Error::C2pa(err),
+        }
+    }
+}
+
 impl<T> From<PoisonError<T>> for Error {
     fn from(err: PoisonError<T>) -> Self {
         Error::Lock(err.to_string())
@@ -0,0 +1,157 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::error::{Error, Result};
+use c2pa::{Builder, Signer};
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory of a staged rendition, named so c2pa-rs writes it to `output/rendition/`.
+const RENDITION_DIR: &str = "rendition";
+
+/// Directory of the staged fragments, next to the staged init segment.
+const FRAGMENTS_DIR: &str = "fragments";
+
+/// An init segment and its fragments staged for c2pa-rs, which signs an init segment together
+/// with the fragments a glob matches next to it, in glob order.
+///
+/// The files are linked into a temporary directory under names that glob in the order they were
+/// given, so exactly those fragments are signed, in that order. The signed files are moved to
+/// `outputDir/<init segment directory name>/` under their original names, and the temporary
+/// directory is removed on drop.
+pub(crate) struct StagedFragments {
+    dir: PathBuf,
+    init: PathBuf,
+    /// Each signed file as c2pa-rs writes it, and where it is moved to. The init segment is
+    /// first, followed by the fragments in order.
+    outputs: Vec<(PathBuf, PathBuf)>,
+}
+
+impl StagedFragments {
+    pub(crate) fn new(
+        init_path: &Path,
+        fragment_paths: &[PathBuf],
+        output_dir: &Path,
+    ) -> Result<Self> {
+        if fragment_paths.is_empty() {
+            return Err(Error::Asset(
+                "At least one fragment is required".to_string(),
+            ));
+        }
+        let init_path = fs::canonicalize(init_path)?;
+        let destination = match init_path.parent().and_then(Path::file_name) {
+            Some(rendition) => output_dir.join(rendition),
+            None => output_dir.to_path_buf(),
+        };
+
+        let dir = std::env::temp_dir().join(format!(
+            "c2pa-fragments-{}-{:016x}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        let input_dir = dir.join("input").join(RENDITION_DIR);
+        let signed_dir = dir.join("output").join(RENDITION_DIR);
+        let mut staged = Self {
+            init: input_dir.join(staged_name("init", &init_path)),
+            dir,
+            outputs: Vec::new(),
+        };
+        fs::create_dir_all(input_dir.join(FRAGMENTS_DIR))?;
+
+        let mut names = HashSet::new();
+        let init_name = file_name(&init_path)?;
+        link(&init_path, &staged.init)?;
+        staged.outputs.push((
+            signed_dir.join(file_name(&staged.init)?),
+            destination.join(&init_name),
+        ));
+        names.insert(init_name);
+
+        for (index, path) in fragment_paths.iter().enumerate() {
+            let name = file_name(path)?;
+            if !names.insert(name.clone()) {
+                return Err(Error::Asset(format!(
+                    "More than one segment is named {name:?}"
+                )));
+            }
+            let staged_name = staged_name(&format!("{index:08}"), path);
+            link(path, &input_dir.join(FRAGMENTS_DIR).join(&staged_name))?;
+            staged
+                .outputs
+                .push((signed_dir.join(staged_name), destination.join(name)));
+        }
+
+        if let Some((_, target)) = staged.outputs.iter().find(|(_, target)| target.exists()) {
+            return Err(Error::Asset(format!("{target:?} already exists")));
+        }
+        Ok(staged)
+    }
+
+    /// Signs the staged rendition with `builder`.
+    pub(crate) fn sign(&self, builder: &mut Builder, signer: &dyn Signer) -> Result<()> {
+        // c2pa-rs matches the init segment path as a glob too.
+        let init = PathBuf::from(glob::Pattern::escape(&self.init.to_string_lossy()));
+        let fragments = Path::new(FRAGMENTS_DIR).join("*");
+        builder.sign_fragmented_files(signer, init, fragments, self.dir.join("output"))?;
+        Ok(())
+    }
+
+    /// Moves the signed files into place, returning their paths with the init segment first.
+    pub(crate) fn commit(&self) -> Result<Vec<PathBuf>> {
+        if let Some(parent) = self.outputs.first().and_then(|(_, target)| target.parent()) {
+            fs::create_dir_all(parent)?;
+        }
+        for (signed, target) in &self.outputs {
+            // The temporary directory may be on another file system.
+            if fs::rename(signed, target).is_err() {
+                fs::copy(signed, target)?;
+            }
+        }
+        Ok(self
+            .outputs
+            .iter()
+            .map(|(_, target)| target.clone())
+            .collect())
+    }
+}
+
+impl Drop for StagedFragments {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn file_name(path: &Path) -> Result<OsString> {
+    path.file_name()
+        .map(OsString::from)
+        .ok_or_else(|| Error::Asset(format!("{path:?} is not a file")))
+}
+
+/// Names a staged file `stem`, keeping the extension c2pa-rs detects the format from.
+fn staged_name(stem: &str, path: &Path) -> PathBuf {
+    let name = PathBuf::from(stem);
+    match path.extension() {
+        Some(extension) => name.with_extension(extension),
+        None => name,
+    }
+}
+
+/// Links `source` to `staged`, copying it when the two are on different file systems.
+fn link(source: &Path, staged: &Path) -> Result<()> {
+    if fs::hard_link(source, staged).is_err() {
+        fs::copy(source, staged)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use c2pa::SigningAlg;
+use p12_keystore::KeyStore;
+use pem::Pem;
+use pkcs8::der::Decode;
+use pkcs8::{EncryptedPrivateKeyInfo, PrivateKeyInfo};
+
+use crate::error::{Error, Result};
+
+/// Returns `pkey` as the PEM that c2pa-rs loads keys from, decrypting an
+/// `ENCRYPTED PRIVATE KEY` with `passphrase`. Other keys are returned unchanged.
+pub(crate) fn private_key_pem(pkey: &[u8], passphrase: Option<&str>) -> Result<Vec<u8>> {
+    let Ok(parsed) = pem::parse(pkey) else {
+        return Ok(pkey.to_vec());
+    };
+    if parsed.tag() != "ENCRYPTED PRIVATE KEY" {
+        return Ok(pkey.to_vec());
+    }
+    let Some(passphrase) = passphrase else {
+        return Err(Error::Key(
+            "the private key is encrypted, but no passphrase was given".to_string(),
+        ));
+    };
+    let document = EncryptedPrivateKeyInfo::from_der(parsed.contents())
+        .map_err(pkcs8::Error::from)
+        .and_then(|info| info.decrypt(passphrase))
+        .map_err(|_| {
+            Error::Key("the private key could not be decrypted with the passphrase".to_string())
+        })?;
+    Ok(pem::encode(&Pem::new("PRIVATE KEY", document.as_bytes())).into_bytes())
+}
+
+/// Picks the signing algorithm for a PKCS#8 `PRIVATE KEY` from its key type. RSA keys sign with
+/// PS256, since C2PA does not allow PKCS#1 v1.5 signatures.
+pub(crate) fn signing_alg(pkey_pem: &[u8]) -> Result<SigningAlg> {
+    let parsed =
+        pem::parse(pkey_pem).map_err(|err| Error::Key(format!("invalid private key: {err}")))?;
+    if parsed.tag() != "PRIVATE KEY" {
+        return Err(Error::Key(format!(
+            "the signing algorithm cannot be derived from a {} key; pass it explicitly",
+            parsed.tag()
+        )));
+    }
+    let info = PrivateKeyInfo::from_der(parsed.contents())
+        .map_err(|err| Error::Key(format!("invalid private key: {err}")))?;
+    let curve = info
+        .algorithm
+        .parameters_oid()
+        .ok()
+        .map(|oid| oid.to_string());
+    c2pa_types::key_signing_alg(&info.algorithm.oid.to_string(), curve.as_deref())
+        .map_err(|err| Error::Key(err.to_string()))
+}
+
+/// Reads the private key and its certificate chain from a PKCS#12 (PFX) bundle, returning the
+/// chain and the key as PEM.
+pub(crate) fn from_pkcs12(bundle: &[u8], passphrase: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+    let keystore = KeyStore::from_pkcs12(bundle, passphrase)
+        .map_err(|err| Error::Key(format!("the PKCS#12 bundle could not be read: {err}")))?;
+    let (_, chain) = keystore
+        .private_key_chain()
+        .ok_or_else(|| Error::Key("the PKCS#12 bundle has no private key".to_string()))?;
+    if chain.chain().is_empty() {
+        return Err(Error::Key(
+            "the PKCS#12 bundle has no certificate for its private key".to_string(),
+        ));
+    }
+
+    let certs = chain
+        .chain()
+        .iter()
+        .map(|cert| pem::encode(&Pem::new("CERTIFICATE", cert.as_der())))
+        .collect::<String>();
+    let pkey = pem::encode(&Pem::new("PRIVATE KEY", chain.key()));
+    Ok((certs.into_bytes(), pkey.into_bytes()))
+}
@@ -13,21 +13,48 @@
 
 use neon::prelude::*;
 
+mod abort;
 mod asset;
+mod bch;
 mod error;
+mod fragmented;
+mod keys;
+mod ocsp;
+mod progress;
+mod remote_manifest;
 mod runtime;
+mod settings;
+mod sidecar;
+mod soft_binding;
+mod stream;
+mod trustmark_decoder;
+mod tsa;
 mod utils;
 
 pub mod neon_builder;
+pub mod neon_context;
 pub mod neon_credential_holder;
 pub mod neon_identity_assertion_builder;
 pub mod neon_identity_assertion_signer;
+pub mod neon_pkcs11_signer;
 pub mod neon_reader;
 pub mod neon_signer;
 pub mod neon_trustmark;
 
 #[neon::main]
 fn main(mut cx: ModuleContext) -> NeonResult<()> {
+    // Abort handles
+    cx.export_function("abortHandleNew", abort::NeonAbortHandle::new)?;
+    cx.export_function("abortHandleAbort", abort::NeonAbortHandle::abort)?;
+
+    // Settings
+    cx.export_function("loadSettings", settings::load_settings)?;
+    cx.export_function("resetSettings", settings::reset_settings)?;
+    cx.export_function("getSettings", settings::get_settings)?;
+
+    // Context
+    cx.export_function("contextNew", neon_context::NeonContext::new)?;
+
     // Builder
     cx.export_function("builderNew", neon_builder::NeonBuilder::new)?;
     cx.export_function("builderWithJson", neon_builder::NeonBuilder::with_json)?;
@@ -64,11 +91,28 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
         neon_builder::NeonBuilder::from_archive,
     )?;
     cx.export_function("builderSign", neon_builder::NeonBuilder::sign)?;
+    cx.export_function(
+        "builderSignSidecar",
+        neon_builder::NeonBuilder::sign_sidecar,
+    )?;
+    cx.export_function(
+        "builderSignSidecarAsync",
+        neon_builder::NeonBuilder::sign_sidecar_async,
+    )?;
+    cx.export_function(
+        "builderSignFragmented",
+        neon_builder::NeonBuilder::sign_fragmented,
+    )?;
     cx.export_function(
         "builderSignConfigAsync",
         neon_builder::NeonBuilder::sign_config_async,
     )?;
     cx.export_function("builderSignAsync", neon_builder::NeonBuilder::sign_async)?;
+    cx.export_function("builderSignBatch", neon_builder::NeonBuilder::sign_batch)?;
+    cx.export_function(
+        "builderSignWatermarked",
+        neon_builder::NeonBuilder::sign_watermarked,
+    )?;
     cx.export_function(
         "builderIdentitySignAsync",
         neon_builder::NeonBuilder::identity_sign_async,
@@ -113,7 +157,17 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
         "readerFromManifestDataAndFile",
         neon_reader::NeonReader::from_manifest_data_and_asset,
     )?;
+    cx.export_function("readerFromFragment", neon_reader::NeonReader::from_fragment)?;
+    cx.export_function(
+        "readerFromFragments",
+        neon_reader::NeonReader::from_fragments,
+    )?;
+    cx.export_function("readerVerifyBatch", neon_reader::NeonReader::verify_batch)?;
     cx.export_function("readerJson", neon_reader::NeonReader::json)?;
+    cx.export_function(
+        "readerValidationReport",
+        neon_reader::NeonReader::validation_report,
+    )?;
     cx.export_function("readerRemoteUrl", neon_reader::NeonReader::remote_url)?;
     cx.export_function("readerIsEmbedded", neon_reader::NeonReader::is_embedded)?;
     cx.export_function(
@@ -123,6 +177,14 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
 
     // Signers
     cx.export_function("localSignerNew", neon_signer::NeonLocalSigner::new)?;
+    cx.export_function(
+        "localSignerFromPkcs12",
+        neon_signer::NeonLocalSigner::from_pkcs12,
+    )?;
+    cx.export_function(
+        "localSignerCheckCertificates",
+        neon_signer::NeonLocalSigner::check_certificates,
+    )?;
     cx.export_function("localSignerSign", neon_signer::NeonLocalSigner::sign)?;
     cx.export_function("localSignerAlg", neon_signer::NeonLocalSigner::alg)?;
     cx.export_function("localSignerCerts", neon_signer::NeonLocalSigner::certs)?;
@@ -160,6 +222,24 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
         "callbackSignerDirectCoseHandling",
         neon_signer::NeonCallbackSigner::direct_cose_handling,
     )?;
+    cx.export_function("pkcs11SignerNew", neon_pkcs11_signer::NeonPkcs11Signer::new)?;
+    cx.export_function(
+        "pkcs11SignerSign",
+        neon_pkcs11_signer::NeonPkcs11Signer::sign,
+    )?;
+    cx.export_function("pkcs11SignerAlg", neon_pkcs11_signer::NeonPkcs11Signer::alg)?;
+    cx.export_function(
+        "pkcs11SignerCerts",
+        neon_pkcs11_signer::NeonPkcs11Signer::certs,
+    )?;
+    cx.export_function(
+        "pkcs11SignerReserveSize",
+        neon_pkcs11_signer::NeonPkcs11Signer::reserve_size,
+    )?;
+    cx.export_function(
+        "pkcs11SignerTimeAuthorityUrl",
+        neon_pkcs11_signer::NeonPkcs11Signer::time_authority_url,
+    )?;
 
     // Identity Assertions
     cx.export_function(
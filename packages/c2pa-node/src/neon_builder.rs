@@ -11,22 +11,33 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use crate::asset::parse_asset;
+use crate::abort::Abort;
+use crate::asset::{parse_asset, Asset, NeonReadStreamTrait, NeonWriteStreamTrait};
 use crate::error::{as_js_error, Error};
+use crate::fragmented::StagedFragments;
 use crate::neon_identity_assertion_signer::NeonIdentityAssertionSigner;
+use crate::neon_pkcs11_signer::NeonPkcs11Signer;
 use crate::neon_reader::NeonReader;
-use crate::neon_signer::{CallbackSignerConfig, NeonCallbackSigner, NeonLocalSigner};
+use crate::neon_signer::{
+    BlockingCallbackSigner, CallbackSignerConfig, LocalSignerKeys, NeonCallbackSigner,
+    NeonLocalSigner,
+};
+use crate::neon_trustmark::{NeonTrustmark, OutputOptions, SoftBinding};
+use crate::progress::Progress;
 use crate::runtime::runtime;
-use crate::utils::parse_settings;
+use crate::sidecar::SidecarDestination;
+use crate::utils::{new_context, parse_settings, parse_trust_config};
 use c2pa::{assertions::{Action, Actions}, Builder, BuilderIntent, Ingredient, Reader};
+use futures::stream::{self, StreamExt};
 use neon::context::Context as NeonContext;
 use neon::prelude::*;
 use neon_serde4;
 use serde_json;
 use std::io::Cursor;
-use std::ops::Deref;
+use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 pub struct NeonBuilder {
     builder: Arc<Mutex<Builder>>,
@@ -34,15 +45,13 @@ pub struct NeonBuilder {
 
 impl NeonBuilder {
     pub fn new(mut cx: FunctionContext) -> JsResult<JsBox<Self>> {
-        // Parse optional settings parameter (argument 0)
-        let context_opt =
-            parse_settings(&mut cx, 0, "Builder").or_else(|err| cx.throw_error(err.to_string()))?;
+        // Parse optional settings (argument 0) and the trust config of the options (argument 1)
+        let trust = parse_trust_config(&mut cx, 1, "Builder")?;
+        let context = parse_settings(&mut cx, 0, trust.as_ref(), "Builder")
+            .or_else(|err| cx.throw_error(err.to_string()))?
+            .unwrap_or_else(|| Arc::new(new_context()));
 
-        let builder = if let Some(context) = context_opt {
-            Builder::from_context(context)
-        } else {
-            Builder::default()
-        };
+        let builder = Builder::from_shared_context(&context);
 
         Ok(cx.boxed(Self {
             builder: Arc::new(Mutex::new(builder)),
@@ -52,19 +61,15 @@ impl NeonBuilder {
     pub fn with_json(mut cx: FunctionContext) -> JsResult<JsBox<Self>> {
         let json = cx.argument::<JsString>(0)?.value(&mut cx);
 
-        // Parse optional settings parameter (argument 1)
-        let context_opt =
-            parse_settings(&mut cx, 1, "Builder").or_else(|err| cx.throw_error(err.to_string()))?;
+        // Parse optional settings (argument 1) and the trust config of the options (argument 2)
+        let trust = parse_trust_config(&mut cx, 2, "Builder")?;
+        let context = parse_settings(&mut cx, 1, trust.as_ref(), "Builder")
+            .or_else(|err| cx.throw_error(err.to_string()))?
+            .unwrap_or_else(|| Arc::new(new_context()));
 
-        let builder = if let Some(context) = context_opt {
-            Builder::from_context(context)
-                .with_definition(json.as_str())
-                .or_else(|err| cx.throw_error(err.to_string()))?
-        } else {
-            Builder::default()
-                .with_definition(&json)
-                .or_else(|err| cx.throw_error(err.to_string()))?
-        };
+        let builder = Builder::from_shared_context(&context)
+            .with_definition(json.as_str())
+            .or_else(|err| cx.throw_error(err.to_string()))?;
 
         Ok(cx.boxed(Self {
             builder: Arc::new(Mutex::new(builder)),
@@ -153,6 +158,7 @@ impl NeonBuilder {
         let resource = cx
             .argument::<JsObject>(1)
             .and_then(|obj| parse_asset(&mut cx, obj))?;
+        let abort = Abort::from_js(&mut cx, 2)?;
         let builder = Arc::clone(&this.builder);
 
         let promise = cx
@@ -160,6 +166,7 @@ impl NeonBuilder {
                 // Block on acquiring the async mutex lock
                 let rt = runtime();
                 let mut builder = rt.block_on(async { builder.lock().await });
+                abort.check()?;
 
                 resource.into_read_stream().and_then(|mut resource_stream| {
                     builder.add_resource(&uri, &mut resource_stream)?;
@@ -214,6 +221,7 @@ impl NeonBuilder {
         let ingredient = cx
             .argument::<JsObject>(1)
             .and_then(|obj| parse_asset(&mut cx, obj))?;
+        let abort = Abort::from_js(&mut cx, 2)?;
 
         let builder = Arc::clone(&this.builder);
 
@@ -223,24 +231,25 @@ impl NeonBuilder {
         rt.spawn(async move {
             let mut builder = builder.lock().await;
 
-            let result = async {
-                let format = ingredient
-                    .mime_type()
-                    .ok_or_else(|| {
-                        Error::Signing("Ingredient asset must have a mime type".to_string())
-                    })?
-                    .to_owned();
-                let mut ingredient_stream = ingredient.into_read_stream()?;
-                builder
-                    .add_ingredient_from_stream_async(
-                        &ingredient_json,
-                        &format,
-                        &mut ingredient_stream,
-                    )
-                    .await?;
-                Ok(())
-            }
-            .await;
+            let result = abort
+                .run(async {
+                    let format = ingredient
+                        .mime_type()
+                        .ok_or_else(|| {
+                            Error::Signing("Ingredient asset must have a mime type".to_string())
+                        })?
+                        .to_owned();
+                    let mut ingredient_stream = ingredient.into_read_stream()?;
+                    builder
+                        .add_ingredient_from_stream_async(
+                            &ingredient_json,
+                            &format,
+                            &mut ingredient_stream,
+                        )
+                        .await?;
+                    Ok(())
+                })
+                .await;
 
             deferred.settle_with(&channel, move |mut cx| match result {
                 Ok(_) => Ok(cx.undefined()),
@@ -270,6 +279,7 @@ impl NeonBuilder {
         let this = cx.this::<JsBox<Self>>()?;
         let dest_obj = cx.argument::<JsObject>(0)?;
         let dest = parse_asset(&mut cx, dest_obj)?;
+        let abort = Abort::from_js(&mut cx, 1)?;
         let is_buffer = dest.name() == "destination_buffer";
         let builder = Arc::clone(&this.builder);
         let dest_obj_root: Arc<Root<JsObject>> = Arc::new(Root::new(&mut cx, &dest_obj));
@@ -280,9 +290,18 @@ impl NeonBuilder {
                 // Settings are automatically applied when runtime() is called
                 let rt = runtime();
                 let builder = rt.block_on(async { builder.lock().await });
+                abort.check()?;
 
                 dest.write_stream().and_then(|mut dest_stream| {
                     builder.to_archive(&mut dest_stream)?;
+                    dest_stream.flush()?;
+                    // Writing the archive cannot be interrupted, so an abort that arrived
+                    // meanwhile discards it instead.
+                    if let Err(err) = abort.check() {
+                        drop(dest_stream);
+                        dest.remove_partial();
+                        return Err(err);
+                    }
                     if is_buffer {
                         let mut archive_data = Vec::new();
                         dest_stream
@@ -318,17 +337,18 @@ impl NeonBuilder {
             .argument::<JsObject>(0)
             .and_then(|obj| parse_asset(&mut cx, obj))?;
 
-        let context_opt =
-            parse_settings(&mut cx, 1, "Builder").or_else(|err| cx.throw_error(err.to_string()))?;
+        let trust = parse_trust_config(&mut cx, 2, "Builder")?;
+        let context = parse_settings(&mut cx, 1, trust.as_ref(), "Builder")
+            .or_else(|err| cx.throw_error(err.to_string()))?
+            .unwrap_or_else(|| Arc::new(new_context()));
+        let abort = Abort::from_js(&mut cx, 3)?;
 
         let promise = cx
             .task(move || {
+                abort.check()?;
                 let source_stream = source.into_read_stream()?;
-                let mut builder = if let Some(context) = context_opt {
-                    Builder::from_context(context).with_archive(source_stream)?
-                } else {
-                    Builder::default().with_archive(source_stream)?
-                };
+                let mut builder =
+                    Builder::from_shared_context(&context).with_archive(source_stream)?;
                 // The ARCHIVE_METADATA assertion is merely working-store bookkeeping
                 // used to reconstruct a builder from an archive.
                 builder
@@ -351,12 +371,17 @@ impl NeonBuilder {
     pub fn sign(mut cx: FunctionContext) -> JsResult<JsBuffer> {
         let rt = runtime();
         let this = cx.this::<JsBox<Self>>()?;
-        let signer = cx.argument::<JsBox<NeonLocalSigner>>(0)?;
+        let signer = SyncSigner::from_js(&mut cx, 0)?;
         let input = cx
             .argument::<JsObject>(1)
             .and_then(|obj| parse_asset(&mut cx, obj))?;
         let output_obj = cx.argument::<JsObject>(2)?;
         let output = parse_asset(&mut cx, output_obj)?;
+        // Stream assets wait on JS promises for every read and write, which can never settle
+        // while this call is holding the JS thread.
+        if input.is_stream() || output.is_stream() {
+            return cx.throw_error("Stream assets are only supported by signAsync");
+        }
         let mut builder = rt.block_on(async { this.builder.lock().await });
         let signer = signer.signer();
         let format = match input.mime_type() {
@@ -370,7 +395,7 @@ impl NeonBuilder {
             .write_stream()
             .or_else(|err| cx.throw_error(err.to_string()))?;
         let bytes = builder
-            .sign(&**signer, &format, &mut input_stream, &mut output_stream)
+            .sign(signer, &format, &mut input_stream, &mut output_stream)
             .or_else(|err| cx.throw_error(err.to_string()))?;
 
         // If the output is a buffer, write the signed asset to it
@@ -392,6 +417,199 @@ impl NeonBuilder {
         Ok(buffer)
     }
 
+    /// Signs an asset without embedding the manifest, writing the manifest store to a second
+    /// destination (for instance `image.jpg.c2pa`) alongside the asset.
+    pub fn sign_sidecar(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+        let rt = runtime();
+        let this = cx.this::<JsBox<Self>>()?;
+        let signer = SyncSigner::from_js(&mut cx, 0)?;
+        let input = cx
+            .argument::<JsObject>(1)
+            .and_then(|obj| parse_asset(&mut cx, obj))?;
+        let output_obj = cx.argument::<JsObject>(2)?;
+        let output = parse_asset(&mut cx, output_obj)?;
+        let manifest_obj = cx.argument::<JsObject>(3)?;
+        let manifest_output = parse_asset(&mut cx, manifest_obj)?;
+        if input.is_stream() || output.is_stream() || manifest_output.is_stream() {
+            return cx.throw_error("Stream assets are only supported by signSidecarAsync");
+        }
+        let format = match input.mime_type() {
+            Some(mime_type) => mime_type.to_owned(),
+            None => return cx.throw_error("Input asset must have a mime type"),
+        };
+        let mut input_stream = input
+            .into_read_stream()
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        let destination = SidecarDestination::new(output, manifest_output);
+        let signer = signer.signer();
+        let mut builder = rt.block_on(async { this.builder.lock().await });
+        let result = destination.asset_stream().and_then(|mut output_stream| {
+            let no_embed = std::mem::replace(&mut builder.no_embed, true);
+            let signed = builder.sign(signer, &format, &mut input_stream, &mut output_stream);
+            builder.no_embed = no_embed;
+            let manifest = match signed {
+                Ok(manifest) => manifest,
+                Err(err) => {
+                    drop(output_stream);
+                    destination.discard();
+                    return Err(err.into());
+                }
+            };
+            let buffers = destination.finish(output_stream, &manifest)?;
+            Ok((manifest, buffers))
+        });
+        let (manifest, buffers) = result.or_else(|err| cx.throw_error(err.to_string()))?;
+
+        if let Some(asset) = buffers.asset {
+            let buffer = JsBuffer::from_slice(&mut cx, &asset)?;
+            output_obj.set(&mut cx, "buffer", buffer)?;
+        }
+        if let Some(manifest) = buffers.manifest {
+            let buffer = JsBuffer::from_slice(&mut cx, &manifest)?;
+            manifest_obj.set(&mut cx, "buffer", buffer)?;
+        }
+        JsBuffer::from_slice(&mut cx, &manifest)
+    }
+
+    /// Async counterpart of [`Self::sign_sidecar`], using a `CallbackSigner`.
+    pub fn sign_sidecar_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+        let rt = runtime();
+        let channel = cx.channel();
+
+        let this = cx.this::<JsBox<Self>>()?;
+        let signer = cx.argument::<JsBox<NeonCallbackSigner>>(0)?;
+        let signer_ref: &NeonCallbackSigner = signer.deref();
+        let signer = signer_ref.clone();
+        let input = cx
+            .argument::<JsObject>(1)
+            .and_then(|obj| parse_asset(&mut cx, obj))?;
+        let output_obj = cx.argument::<JsObject>(2)?;
+        let output = parse_asset(&mut cx, output_obj)?;
+        let manifest_obj = cx.argument::<JsObject>(3)?;
+        let manifest_output = parse_asset(&mut cx, manifest_obj)?;
+        let abort = Abort::from_js(&mut cx, 4)?;
+        let progress = Progress::from_js(&mut cx, 5)?;
+        let signer = signer.with_progress(progress.clone());
+        let format = match input.mime_type() {
+            Some(mime_type) => mime_type.to_owned(),
+            None => return cx.throw_error("Input asset must have a mime type"),
+        };
+        let input_size = input.size();
+        let mut input_stream = input
+            .into_read_stream()
+            .map(|stream| progress.read_stream(stream, input_size))
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+        let output_obj_root: Arc<Root<JsObject>> = Arc::new(Root::new(&mut cx, &output_obj));
+        let manifest_obj_root: Arc<Root<JsObject>> = Arc::new(Root::new(&mut cx, &manifest_obj));
+
+        let destination = SidecarDestination::new(output, manifest_output);
+        let builder = Arc::clone(&this.builder);
+        let (deferred, promise) = cx.promise();
+        rt.spawn(async move {
+            let result: Result<_, Error> = async {
+                let mut output_stream = progress.write_stream(destination.asset_stream()?);
+                let mut builder = builder.lock().await;
+                let no_embed = std::mem::replace(&mut builder.no_embed, true);
+                let signed = abort
+                    .run(async {
+                        Ok(builder
+                            .sign_async(&signer, &format, &mut input_stream, &mut output_stream)
+                            .await?)
+                    })
+                    .await;
+                builder.no_embed = no_embed;
+                let manifest = match signed {
+                    Ok(manifest) => manifest,
+                    Err(err) => {
+                        drop(output_stream);
+                        destination.discard();
+                        return Err(err);
+                    }
+                };
+                let buffers = destination.finish(output_stream, &manifest)?;
+                Ok((manifest, buffers))
+            }
+            .await;
+
+            deferred.settle_with(&channel, move |mut cx| match result {
+                Ok((manifest, buffers)) => {
+                    if let Some(asset) = buffers.asset {
+                        let buffer = JsBuffer::from_slice(&mut cx, &asset)?;
+                        let output_obj = output_obj_root.to_inner(&mut cx);
+                        output_obj.set(&mut cx, "buffer", buffer)?;
+                    }
+                    if let Some(manifest) = buffers.manifest {
+                        let buffer = JsBuffer::from_slice(&mut cx, &manifest)?;
+                        let manifest_obj = manifest_obj_root.to_inner(&mut cx);
+                        manifest_obj.set(&mut cx, "buffer", buffer)?;
+                    }
+                    JsBuffer::from_slice(&mut cx, &manifest)
+                }
+                Err(err) => as_js_error(&mut cx, err).and_then(|err| cx.throw(err)),
+            });
+        });
+        Ok(promise)
+    }
+
+    /// Signs a fragmented BMFF asset on disk: an init segment and its fragments, in order,
+    /// resolving to the paths of the signed files. Like c2pa-rs, the signed files are written to
+    /// `outputDir/<init segment directory name>/` under their original file names.
+    pub fn sign_fragmented(mut cx: FunctionContext) -> JsResult<JsPromise> {
+        let rt = runtime();
+        let channel = cx.channel();
+
+        let this = cx.this::<JsBox<Self>>()?;
+        let signer = BatchSigner::from_js(&mut cx, 0)?;
+        let init_path = PathBuf::from(cx.argument::<JsString>(1)?.value(&mut cx));
+        let fragment_paths = cx
+            .argument::<JsArray>(2)?
+            .to_vec(&mut cx)?
+            .into_iter()
+            .map(|value| {
+                let path = value.downcast_or_throw::<JsString, _>(&mut cx)?;
+                Ok(PathBuf::from(path.value(&mut cx)))
+            })
+            .collect::<NeonResult<Vec<_>>>()?;
+        let output_dir = PathBuf::from(cx.argument::<JsString>(3)?.value(&mut cx));
+        let abort = Abort::from_js(&mut cx, 4)?;
+
+        let builder = Arc::clone(&this.builder);
+        let (deferred, promise) = cx.promise();
+        rt.spawn(async move {
+            let result = abort
+                .run(async {
+                    let builder = builder.lock_owned().await;
+                    signer
+                        .sign_fragmented(builder, init_path, fragment_paths, output_dir, &abort)
+                        .await
+                })
+                .await;
+
+            deferred.settle_with(&channel, move |mut cx| match result {
+                Ok(paths) => {
+                    let result = cx.empty_object();
+                    let mut paths = paths
+                        .into_iter()
+                        .map(|path| path.to_string_lossy().into_owned());
+                    if let Some(init) = paths.next() {
+                        let init = cx.string(init);
+                        result.set(&mut cx, "init", init)?;
+                    }
+                    let fragments = cx.empty_array();
+                    for (i, path) in paths.enumerate() {
+                        let path = cx.string(path);
+                        fragments.set(&mut cx, i as u32, path)?;
+                    }
+                    result.set(&mut cx, "fragments", fragments)?;
+                    Ok(result)
+                }
+                Err(err) => as_js_error(&mut cx, err).and_then(|err| cx.throw(err)),
+            });
+        });
+        Ok(promise)
+    }
+
     // TODO: This mimics the previous c2pa-node iteration's arguments.
     // It is probably redundant with sign_async.
     pub fn sign_config_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
@@ -410,26 +628,37 @@ impl NeonBuilder {
             .and_then(|obj| parse_asset(&mut cx, obj))?;
         let output_obj = cx.argument::<JsObject>(3)?;
         let output = parse_asset(&mut cx, output_obj)?;
+        let abort = Abort::from_js(&mut cx, 4)?;
+        let progress = Progress::from_js(&mut cx, 5)?;
+        let signer = signer.with_progress(progress.clone());
         let format = match input.mime_type() {
             Some(mime_type) => mime_type.to_owned(),
             None => return cx.throw_error("Input asset must have a mime type"),
         };
+        let input_size = input.size();
         let mut input_stream = input
             .into_read_stream()
+            .map(|stream| progress.read_stream(stream, input_size))
             .or_else(|err| cx.throw_error(err.to_string()))?;
         let mut output_stream = output
             .write_stream()
+            .map(|stream| progress.write_stream(stream))
             .or_else(|err| cx.throw_error(err.to_string()))?;
 
         let builder = Arc::clone(&this.builder);
         let (deferred, promise) = cx.promise();
         rt.spawn(async move {
-            let result = builder
-                .lock()
-                .await
-                .sign_async(&signer, &format, &mut input_stream, &mut output_stream)
-                .await
-                .map(|sign_result| (sign_result, output_stream));
+            let result = abort
+                .run_writing(&output, async {
+                    let mut builder = builder.lock().await;
+                    let sign_result = builder
+                        .sign_async(&signer, &format, &mut input_stream, &mut output_stream)
+                        .await?;
+                    // Stream assets buffer writes, so push the tail to JS before resolving.
+                    output_stream.flush()?;
+                    Ok((sign_result, output_stream))
+                })
+                .await;
 
             deferred.settle_with(&channel, move |mut cx| match result {
                 Ok((signed_bytes, mut output_stream)) => {
@@ -462,6 +691,9 @@ impl NeonBuilder {
                         Ok(result_buffer.upcast::<JsValue>())
                     }
                 }
+                Err(Error::Aborted) => {
+                    as_js_error(&mut cx, Error::Aborted).and_then(|err| cx.throw(err))
+                }
                 Err(err) => cx.throw_error(err.to_string()),
             });
         });
@@ -473,34 +705,52 @@ impl NeonBuilder {
         let channel = cx.channel();
 
         let this = cx.this::<JsBox<Self>>()?;
-        let signer = cx.argument::<JsBox<NeonCallbackSigner>>(0)?;
-        let signer_ref: &NeonCallbackSigner = signer.deref();
-        let signer = signer_ref.clone();
+        let signer = cx.argument::<JsValue>(0)?;
         let input = cx
             .argument::<JsObject>(1)
             .and_then(|obj| parse_asset(&mut cx, obj))?;
         let output_obj = cx.argument::<JsObject>(2)?;
         let output = parse_asset(&mut cx, output_obj)?;
+        let abort = Abort::from_js(&mut cx, 3)?;
+        let progress = Progress::from_js(&mut cx, 4)?;
+        // A PKCS#11 signer signs on a blocking thread, so it can share this path.
+        let signer: Box<dyn c2pa::AsyncSigner + Send + Sync> = match signer
+            .downcast::<JsBox<NeonPkcs11Signer>, _>(&mut cx)
+        {
+            Ok(signer) => Box::new(NeonPkcs11Signer::clone(&signer)),
+            Err(_) => {
+                let signer = signer.downcast_or_throw::<JsBox<NeonCallbackSigner>, _>(&mut cx)?;
+                Box::new(signer.with_progress(progress.clone()))
+            }
+        };
         let format = match input.mime_type() {
             Some(mime_type) => mime_type.to_owned(),
             None => return cx.throw_error("Input asset must have a mime type"),
         };
+        let input_size = input.size();
         let mut input_stream = input
             .into_read_stream()
+            .map(|stream| progress.read_stream(stream, input_size))
             .or_else(|err| cx.throw_error(err.to_string()))?;
         let mut output_stream = output
             .write_stream()
+            .map(|stream| progress.write_stream(stream))
             .or_else(|err| cx.throw_error(err.to_string()))?;
 
         let builder = Arc::clone(&this.builder);
         let (deferred, promise) = cx.promise();
         rt.spawn(async move {
-            let result = builder
-                .lock()
-                .await
-                .sign_async(&signer, &format, &mut input_stream, &mut output_stream)
-                .await
-                .map(|sign_result| (sign_result, output_stream));
+            let result = abort
+                .run_writing(&output, async {
+                    let mut builder = builder.lock().await;
+                    let sign_result = builder
+                        .sign_async(&*signer, &format, &mut input_stream, &mut output_stream)
+                        .await?;
+                    // Stream assets buffer writes, so push the tail to JS before resolving.
+                    output_stream.flush()?;
+                    Ok((sign_result, output_stream))
+                })
+                .await;
 
             deferred.settle_with(&channel, move |mut cx| match result {
                 Ok((signed_bytes, mut output_stream)) => {
@@ -533,12 +783,205 @@ impl NeonBuilder {
                         Ok(result_buffer.upcast::<JsValue>())
                     }
                 }
+                Err(Error::Aborted) => {
+                    as_js_error(&mut cx, Error::Aborted).and_then(|err| cx.throw(err))
+                }
                 Err(err) => cx.throw_error(err.to_string()),
             });
         });
         Ok(promise)
     }
 
+    /// Signs many assets concurrently with one manifest definition template and one signer,
+    /// resolving to one result per asset, in order, with the manifest bytes or the error.
+    pub fn sign_batch(mut cx: FunctionContext) -> JsResult<JsPromise> {
+        let rt = runtime();
+        let channel = cx.channel();
+
+        let template = cx.argument::<JsString>(0)?.value(&mut cx);
+        let template: serde_json::Value =
+            serde_json::from_str(&template).or_else(|err| cx.throw_error(err.to_string()))?;
+        let signer = BatchSigner::from_js(&mut cx, 1)?;
+        let items = cx
+            .argument::<JsArray>(2)?
+            .to_vec(&mut cx)?
+            .into_iter()
+            .map(|value| {
+                let obj = value.downcast_or_throw::<JsObject, _>(&mut cx)?;
+                BatchSignItem::from_js(&mut cx, obj, &template)
+            })
+            .collect::<NeonResult<Vec<_>>>()?;
+
+        // Parse optional settings (argument 3) and options (argument 4) with the trust config
+        let trust = parse_trust_config(&mut cx, 4, "Builder")?;
+        let context_opt = parse_settings(&mut cx, 3, trust.as_ref(), "Builder")
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+        let concurrency = match cx
+            .argument_opt(4)
+            .and_then(|value| value.downcast::<JsObject, _>(&mut cx).ok())
+        {
+            Some(obj) => obj
+                .get_opt::<JsNumber, _, _>(&mut cx, "concurrency")?
+                .map(|concurrency| (concurrency.value(&mut cx) as usize).max(1)),
+            None => None,
+        }
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+        let abort = Abort::from_js(&mut cx, 5)?;
+        let context_opt = abort.context(context_opt);
+
+        let (deferred, promise) = cx.promise();
+        let handle = rt.clone();
+        rt.spawn(async move {
+            // Each asset is signed by its own builder on its own task, with at most
+            // `concurrency` assets in flight at once.
+            let mut results: Vec<(usize, Result<BatchSigned, Error>)> =
+                stream::iter(items.into_iter().enumerate())
+                    .map(|(index, item)| {
+                        let builder = match &context_opt {
+                            Some(context) => Builder::from_shared_context(context),
+                            None => Builder::default(),
+                        };
+                        let task = handle.spawn(item.sign(builder, signer.clone(), abort.clone()));
+                        async move {
+                            let result = task.await.map_err(Error::from).and_then(|result| result);
+                            (index, result)
+                        }
+                    })
+                    .buffer_unordered(concurrency)
+                    .collect()
+                    .await;
+            results.sort_by_key(|(index, _)| *index);
+
+            deferred.settle_with(&channel, move |mut cx| {
+                let js_results = JsArray::new(&mut cx, results.len());
+                for (index, result) in results {
+                    let js_result = cx.empty_object();
+                    let js_index = cx.number(index as f64);
+                    js_result.set(&mut cx, "index", js_index)?;
+                    match result {
+                        Ok(signed) => {
+                            let manifest = JsBuffer::from_slice(&mut cx, &signed.manifest)?;
+                            js_result.set(&mut cx, "manifest", manifest)?;
+                            if let Some(signed_asset) = signed.signed_asset {
+                                let signed_asset = JsBuffer::from_slice(&mut cx, &signed_asset)?;
+                                js_result.set(&mut cx, "signedAsset", signed_asset)?;
+                            }
+                        }
+                        Err(err) => {
+                            let error = cx.string(err.to_string());
+                            js_result.set(&mut cx, "error", error)?;
+                        }
+                    }
+                    js_results.set(&mut cx, index as u32, js_result)?;
+                }
+                Ok(js_results)
+            });
+        });
+        Ok(promise)
+    }
+
+    /// Watermarks an image with a Trustmark, binds the watermark to the manifest with a
+    /// `c2pa.soft-binding` assertion and signs the watermarked image, resolving to the manifest
+    /// bytes and the watermark.
+    pub fn sign_watermarked(mut cx: FunctionContext) -> JsResult<JsPromise> {
+        let rt = runtime();
+        let channel = cx.channel();
+
+        let this = cx.this::<JsBox<Self>>()?;
+        let signer = BatchSigner::from_js(&mut cx, 0)?;
+        let trustmark = cx.argument::<JsBox<NeonTrustmark>>(1)?;
+        let trustmark = NeonTrustmark::clone(&trustmark);
+        let input = cx
+            .argument::<JsObject>(2)
+            .and_then(|obj| parse_asset(&mut cx, obj))?;
+        let output_obj = cx.argument::<JsObject>(3)?;
+        let output = parse_asset(&mut cx, output_obj)?;
+        let (strength, watermark) = match cx
+            .argument_opt(4)
+            .and_then(|value| value.downcast::<JsObject, _>(&mut cx).ok())
+        {
+            Some(obj) => (
+                obj.get_opt::<JsNumber, _, _>(&mut cx, "strength")?
+                    .map(|strength| strength.value(&mut cx) as f32),
+                obj.get_opt::<JsString, _, _>(&mut cx, "watermark")?
+                    .map(|watermark| watermark.value(&mut cx)),
+            ),
+            None => (None, None),
+        };
+        let strength = strength.unwrap_or(DEFAULT_WATERMARK_STRENGTH);
+        let abort = Abort::from_js(&mut cx, 5)?;
+        let format = match input.mime_type() {
+            Some(mime_type) => mime_type.to_owned(),
+            None => return cx.throw_error("Input asset must have a mime type"),
+        };
+
+        let builder = Arc::clone(&this.builder);
+        let (deferred, promise) = cx.promise();
+        rt.spawn(async move {
+            let result = async {
+                let image_format = format.clone();
+                let (image, soft_binding) = tokio::task::spawn_blocking(move || {
+                    let mut image = Vec::new();
+                    input.into_read_stream()?.read_to_end(&mut image)?;
+                    let output = OutputOptions::for_mime_type(&image_format)?;
+                    trustmark.watermark_encoded(&image, strength, watermark, &output)
+                })
+                .await??;
+                abort.check()?;
+
+                // Hold the lock until the assertion is removed again, so no other call sees it.
+                let builder = SoftBindingBuilder::new(builder.lock_owned().await, &soft_binding)?;
+                let (manifest, mut output_stream) = signer
+                    .sign(
+                        builder,
+                        format,
+                        Box::new(Cursor::new(image.bytes)),
+                        output.write_stream()?,
+                        &abort,
+                    )
+                    .await?;
+                // Local signing cannot be interrupted, so check for an abort that arrived
+                // meanwhile before resolving.
+                abort.check()?;
+                // Stream assets buffer writes, so push the tail to JS before resolving.
+                output_stream.flush()?;
+
+                let signed_asset = if output.name() == "destination_buffer" {
+                    let mut buffer = Vec::new();
+                    output_stream.rewind()?;
+                    output_stream.read_to_end(&mut buffer)?;
+                    Some(buffer)
+                } else {
+                    None
+                };
+                Ok::<_, Error>((manifest, signed_asset, soft_binding))
+            }
+            .await;
+            if let Err(Error::Aborted) = result {
+                output.remove_partial();
+            }
+
+            deferred.settle_with(&channel, move |mut cx| match result {
+                Ok((manifest, signed_asset, soft_binding)) => {
+                    let result = cx.empty_object();
+                    let manifest = JsBuffer::from_slice(&mut cx, &manifest)?;
+                    result.set(&mut cx, "manifest", manifest)?;
+                    let alg = cx.string(soft_binding.alg);
+                    result.set(&mut cx, "alg", alg)?;
+                    let watermark = cx.string(soft_binding.value);
+                    result.set(&mut cx, "watermark", watermark)?;
+                    if let Some(signed_asset) = signed_asset {
+                        let signed_asset = JsBuffer::from_slice(&mut cx, &signed_asset)?;
+                        result.set(&mut cx, "signedAsset", signed_asset)?;
+                    }
+                    Ok(result)
+                }
+                Err(err) => as_js_error(&mut cx, err).and_then(|err| cx.throw(err)),
+            });
+        });
+        Ok(promise)
+    }
+
     pub fn identity_sign_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
         let rt = runtime();
         let channel = cx.channel();
@@ -552,27 +995,37 @@ impl NeonBuilder {
             .and_then(|obj| parse_asset(&mut cx, obj))?;
         let output_obj = cx.argument::<JsObject>(2)?;
         let output = parse_asset(&mut cx, output_obj)?;
+        let abort = Abort::from_js(&mut cx, 3)?;
+        let progress = Progress::from_js(&mut cx, 4)?;
         let format = match input.mime_type() {
             Some(mime_type) => mime_type.to_owned(),
             None => return cx.throw_error("Input asset must have a mime type"),
         };
+        let input_size = input.size();
         let mut input_stream = input
             .into_read_stream()
+            .map(|stream| progress.read_stream(stream, input_size))
             .or_else(|err| cx.throw_error(err.to_string()))?;
         let mut output_stream = output
             .write_stream()
+            .map(|stream| progress.write_stream(stream))
             .or_else(|err| cx.throw_error(err.to_string()))?;
 
         let builder = Arc::clone(&this.builder);
         let (deferred, promise) = cx.promise();
 
         rt.spawn(async move {
-            let result = builder
-                .lock()
-                .await
-                .sign_async(&signer, &format, &mut input_stream, &mut output_stream)
-                .await
-                .map(|sign_result| (sign_result, output_stream));
+            let result = abort
+                .run_writing(&output, async {
+                    let mut builder = builder.lock().await;
+                    let sign_result = builder
+                        .sign_async(&signer, &format, &mut input_stream, &mut output_stream)
+                        .await?;
+                    // Stream assets buffer writes, so push the tail to JS before resolving.
+                    output_stream.flush()?;
+                    Ok((sign_result, output_stream))
+                })
+                .await;
 
             deferred.settle_with(&channel, move |mut cx| match result {
                 Ok((signed_bytes, mut output_stream)) => {
@@ -605,6 +1058,9 @@ impl NeonBuilder {
                         Ok(result_buffer.upcast::<JsValue>())
                     }
                 }
+                Err(Error::Aborted) => {
+                    as_js_error(&mut cx, Error::Aborted).and_then(|err| cx.throw(err))
+                }
                 Err(err) => cx.throw_error(err.to_string()),
             });
         });
@@ -987,6 +1443,300 @@ impl NeonBuilder {
 
 impl Finalize for NeonBuilder {}
 
+/// How strongly `signWatermarked` applies the watermark when no strength is given.
+const DEFAULT_WATERMARK_STRENGTH: f32 = 1.0;
+
+/// Assets signed at once by `signBatch` when no concurrency is given.
+const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// The signer of a `signWatermarked` call, or the one shared by every asset of a `signBatch`
+/// call.
+#[derive(Clone)]
+enum BatchSigner {
+    /// Each asset creates its own signer from the keys, since a local signer is not `Send`.
+    Local(Arc<LocalSignerKeys>),
+    /// Signs on a blocking thread like a local signer, sharing one session on the token.
+    Pkcs11(NeonPkcs11Signer),
+    Callback(Box<NeonCallbackSigner>),
+}
+
+impl BatchSigner {
+    fn from_js(cx: &mut FunctionContext, arg_index: usize) -> NeonResult<Self> {
+        let value = cx.argument::<JsValue>(arg_index)?;
+        if let Ok(signer) = value.downcast::<JsBox<NeonLocalSigner>, _>(cx) {
+            return Ok(Self::Local(signer.keys()));
+        }
+        if let Ok(signer) = value.downcast::<JsBox<NeonPkcs11Signer>, _>(cx) {
+            return Ok(Self::Pkcs11(NeonPkcs11Signer::clone(&signer)));
+        }
+        match value.downcast::<JsBox<NeonCallbackSigner>, _>(cx) {
+            Ok(signer) => Ok(Self::Callback(Box::new(NeonCallbackSigner::clone(&signer)))),
+            Err(_) => cx.throw_type_error("Expected a LocalSigner, Pkcs11Signer or CallbackSigner"),
+        }
+    }
+
+    /// Signs an asset with `builder`, on a blocking thread for the signers that block.
+    async fn sign<B>(
+        self,
+        mut builder: B,
+        format: String,
+        mut input_stream: Box<dyn NeonReadStreamTrait>,
+        mut output_stream: Box<dyn NeonWriteStreamTrait>,
+        abort: &Abort,
+    ) -> Result<(Vec<u8>, Box<dyn NeonWriteStreamTrait>), Error>
+    where
+        B: DerefMut<Target = Builder> + Send + 'static,
+    {
+        let abort = abort.clone();
+        match self {
+            BatchSigner::Local(keys) => {
+                // Local signing blocks, including on any time stamp request.
+                tokio::task::spawn_blocking(move || {
+                    abort.run_blocking(|| {
+                        let signer = keys.signer()?;
+                        let manifest = builder.sign(
+                            &*signer,
+                            &format,
+                            &mut input_stream,
+                            &mut output_stream,
+                        )?;
+                        Ok((manifest, output_stream))
+                    })
+                })
+                .await
+                .map_err(Error::from)
+                .and_then(|signed| signed)
+            }
+            BatchSigner::Pkcs11(signer) => tokio::task::spawn_blocking(move || {
+                abort.run_blocking(|| {
+                    let manifest =
+                        builder.sign(&signer, &format, &mut input_stream, &mut output_stream)?;
+                    Ok((manifest, output_stream))
+                })
+            })
+            .await
+            .map_err(Error::from)
+            .and_then(|signed| signed),
+            BatchSigner::Callback(signer) => {
+                abort
+                    .run(async {
+                        let manifest = builder
+                            .sign_async(&*signer, &format, &mut input_stream, &mut output_stream)
+                            .await?;
+                        Ok((manifest, output_stream))
+                    })
+                    .await
+            }
+        }
+    }
+
+    /// Signs a fragmented asset with `builder` on a blocking thread, since c2pa-rs only signs
+    /// fragmented files synchronously, and moves the signed files into place.
+    async fn sign_fragmented(
+        self,
+        mut builder: OwnedMutexGuard<Builder>,
+        init_path: PathBuf,
+        fragment_paths: Vec<PathBuf>,
+        output_dir: PathBuf,
+        abort: &Abort,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let abort = abort.clone();
+        // A callback signer blocks on the runtime for each call, off the JS thread.
+        let runtime = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || {
+            abort.run_blocking(|| {
+                let staged = StagedFragments::new(&init_path, &fragment_paths, &output_dir)?;
+                match self {
+                    BatchSigner::Local(keys) => staged.sign(&mut builder, &*keys.signer()?)?,
+                    BatchSigner::Pkcs11(signer) => staged.sign(&mut builder, &signer)?,
+                    BatchSigner::Callback(signer) => {
+                        staged.sign(&mut builder, &BlockingCallbackSigner::new(*signer, runtime))?
+                    }
+                }
+                staged.commit()
+            })
+        })
+        .await
+        .map_err(Error::from)
+        .and_then(|signed| signed)
+    }
+}
+
+/// A locked builder with a `c2pa.soft-binding` assertion added for one `signWatermarked` call.
+/// Dropping it removes the assertion, so the builder is left as it was whether or not signing
+/// succeeded.
+struct SoftBindingBuilder {
+    builder: OwnedMutexGuard<Builder>,
+    assertion_count: usize,
+}
+
+impl SoftBindingBuilder {
+    fn new(
+        mut builder: OwnedMutexGuard<Builder>,
+        soft_binding: &SoftBinding,
+    ) -> Result<Self, Error> {
+        let assertion_count = builder.definition.assertions.len();
+        builder.add_assertion(SoftBinding::LABEL, &soft_binding.assertion())?;
+        Ok(Self {
+            builder,
+            assertion_count,
+        })
+    }
+}
+
+impl Deref for SoftBindingBuilder {
+    type Target = Builder;
+
+    fn deref(&self) -> &Builder {
+        &self.builder
+    }
+}
+
+impl DerefMut for SoftBindingBuilder {
+    fn deref_mut(&mut self) -> &mut Builder {
+        &mut self.builder
+    }
+}
+
+impl Drop for SoftBindingBuilder {
+    fn drop(&mut self) {
+        let assertion_count = self.assertion_count;
+        self.builder.definition.assertions.truncate(assertion_count);
+    }
+}
+
+/// The signer argument of the synchronous signing calls.
+enum SyncSigner<'a> {
+    Local(Handle<'a, JsBox<NeonLocalSigner>>),
+    Pkcs11(Handle<'a, JsBox<NeonPkcs11Signer>>),
+}
+
+impl<'a> SyncSigner<'a> {
+    fn from_js(cx: &mut FunctionContext<'a>, arg_index: usize) -> NeonResult<Self> {
+        let value = cx.argument::<JsValue>(arg_index)?;
+        if let Ok(signer) = value.downcast::<JsBox<NeonPkcs11Signer>, _>(cx) {
+            return Ok(Self::Pkcs11(signer));
+        }
+        match value.downcast::<JsBox<NeonLocalSigner>, _>(cx) {
+            Ok(signer) => Ok(Self::Local(signer)),
+            Err(_) => cx.throw_type_error("Expected a LocalSigner or Pkcs11Signer"),
+        }
+    }
+
+    fn signer(&self) -> &dyn c2pa::Signer {
+        match self {
+            Self::Local(signer) => &**signer.signer(),
+            Self::Pkcs11(signer) => &***signer,
+        }
+    }
+}
+
+/// One input/output pair of a `signBatch` call, with the template definition and its
+/// per-asset overrides applied.
+struct BatchSignItem {
+    definition: String,
+    format: String,
+    input: Asset,
+    output: Asset,
+}
+
+/// The manifest store of a signed asset, and the asset itself for buffer destinations.
+struct BatchSigned {
+    manifest: Vec<u8>,
+    signed_asset: Option<Vec<u8>>,
+}
+
+impl BatchSignItem {
+    /// Parses `{ input, output, title?, instanceId?, format? }`.
+    fn from_js(
+        cx: &mut FunctionContext,
+        obj: Handle<JsObject>,
+        template: &serde_json::Value,
+    ) -> NeonResult<Self> {
+        let input = obj
+            .get::<JsObject, _, _>(cx, "input")
+            .and_then(|input| parse_asset(cx, input))?;
+        let output = obj
+            .get::<JsObject, _, _>(cx, "output")
+            .and_then(|output| parse_asset(cx, output))?;
+        let Some(format) = input.mime_type() else {
+            return cx.throw_error("Input asset must have a mime type");
+        };
+
+        let mut definition = template.clone();
+        if let Some(definition) = definition.as_object_mut() {
+            for (key, field) in [
+                ("title", "title"),
+                ("instanceId", "instance_id"),
+                ("format", "format"),
+            ] {
+                if let Some(value) = obj.get_opt::<JsString, _, _>(cx, key)? {
+                    definition.insert(field.to_owned(), value.value(cx).into());
+                }
+            }
+        }
+
+        Ok(Self {
+            definition: definition.to_string(),
+            format,
+            input,
+            output,
+        })
+    }
+
+    async fn sign(
+        self,
+        builder: Builder,
+        signer: BatchSigner,
+        abort: Abort,
+    ) -> Result<BatchSigned, Error> {
+        abort.check()?;
+        let builder = builder.with_definition(self.definition.as_str())?;
+        let input_stream = self.input.into_read_stream()?;
+        let output_stream = self.output.write_stream()?;
+
+        let signed = signer
+            .sign(
+                Box::new(builder),
+                self.format,
+                input_stream,
+                output_stream,
+                &abort,
+            )
+            .await;
+        // Local signing is only interrupted at c2pa-rs's own checkpoints, so an abort that arrived
+        // after the last one discards the signed asset instead.
+        let (manifest, mut output_stream) = match signed.and_then(|signed| {
+            abort.check()?;
+            Ok(signed)
+        }) {
+            Ok(signed) => signed,
+            Err(err) => {
+                if let Error::Aborted = err {
+                    self.output.remove_partial();
+                }
+                return Err(err);
+            }
+        };
+        // Stream assets buffer writes, so push the tail to JS before resolving.
+        output_stream.flush()?;
+
+        let signed_asset = if self.output.name() == "destination_buffer" {
+            let mut buffer = Vec::new();
+            output_stream.rewind()?;
+            output_stream.read_to_end(&mut buffer)?;
+            Some(buffer)
+        } else {
+            None
+        };
+
+        Ok(BatchSigned {
+            manifest,
+            signed_asset,
+        })
+    }
+}
+
 /// Synchronously calls a JS predicate with a single argument and returns its boolean result.
 /// Propagates the pending JS exception if the predicate throws, and raises a `TypeError` if it
 /// returns a non-boolean value.
@@ -0,0 +1,59 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::utils::{
+    context_from_settings, new_context, parse_settings_string, parse_trust_config,
+    settings_with_trust,
+};
+use c2pa::Context;
+use neon::context::Context as _;
+use neon::prelude::*;
+use std::sync::Arc;
+
+/// A `c2pa::Context` parsed once from settings and shared by every builder and reader it is
+/// passed to in place of a settings string.
+pub struct NeonContext {
+    context: Arc<Context>,
+}
+
+impl Finalize for NeonContext {}
+
+impl NeonContext {
+    pub fn new(mut cx: FunctionContext) -> JsResult<JsBox<Self>> {
+        // Parse optional settings (argument 0) and the trust config of the options (argument 1)
+        let trust = parse_trust_config(&mut cx, 1, "Context")?;
+        let settings = parse_settings_string(&mut cx, 0, "Context")
+            .and_then(|settings| settings_with_trust(settings, trust.as_ref()))
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+        let context = match settings {
+            Some(settings) => context_from_settings(&settings, "Context")
+                .or_else(|err| cx.throw_error(err.to_string()))?,
+            None => new_context(),
+        };
+
+        Ok(cx.boxed(Self {
+            context: Arc::new(context),
+        }))
+    }
+
+    /// Returns the shared context when `value` is a context handle.
+    pub(crate) fn from_js(
+        cx: &mut FunctionContext,
+        value: Handle<JsValue>,
+    ) -> Option<Arc<Context>> {
+        value
+            .downcast::<JsBox<Self>, _>(cx)
+            .ok()
+            .map(|this| Arc::clone(&this.context))
+    }
+}
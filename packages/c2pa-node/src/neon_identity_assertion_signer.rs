@@ -13,6 +13,7 @@
 
 use crate::{
     neon_identity_assertion_builder::NeonIdentityAssertionBuilder, neon_signer::NeonCallbackSigner,
+    ocsp::OcspSource,
 };
 use async_trait::async_trait;
 use c2pa::{
@@ -37,8 +38,21 @@ impl NeonIdentityAssertionSigner {
     pub fn new(mut cx: FunctionContext) -> JsResult<JsBox<Self>> {
         let signer_handle = cx.argument::<JsBox<NeonCallbackSigner>>(0)?;
         let signer_ref: &NeonCallbackSigner = signer_handle.deref();
+        // `{ ocspResponse }` overrides the OCSP response the callback signer staples.
+        let ocsp = match cx.argument_opt(1) {
+            Some(js_value) if js_value.is_a::<JsObject, _>(&mut cx) => {
+                let options = js_value.downcast_or_throw::<JsObject, _>(&mut cx)?;
+                let ocsp_response = options.get_value(&mut cx, "ocspResponse")?;
+                OcspSource::from_js(&mut cx, ocsp_response)?
+            }
+            _ => None,
+        };
+        let signer = match ocsp {
+            Some(ocsp) => signer_ref.with_ocsp(ocsp),
+            None => signer_ref.clone(),
+        };
         Ok(cx.boxed(Self {
-            signer: RwLock::new(signer_ref.clone()),
+            signer: RwLock::new(signer),
             identity_assertions: RwLock::new(vec![]),
         }))
     }
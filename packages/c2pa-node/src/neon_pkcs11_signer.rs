@@ -0,0 +1,339 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use async_trait::async_trait;
+use c2pa::{AsyncSigner, Error::OtherError, Signer, SigningAlg};
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::error::RvError;
+use cryptoki::mechanism::rsa::{PkcsMgfType, PkcsPssParams};
+use cryptoki::mechanism::{Mechanism, MechanismType};
+use cryptoki::object::{Attribute, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use neon::prelude::*;
+use neon::types::buffer::TypedArray;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::error::{as_js_error_fn, Error};
+use crate::runtime::runtime;
+
+/// Reserved for the signature when the config does not give a size.
+const DEFAULT_RESERVE_SIZE: usize = 10000;
+
+/// A PKCS#11 module may only be initialized once per process, so every signer using the same
+/// module shares its context.
+fn module(path: &str) -> Result<Pkcs11, Error> {
+    static MODULES: OnceLock<Mutex<HashMap<String, Pkcs11>>> = OnceLock::new();
+    let mut modules = MODULES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    if let Some(pkcs11) = modules.get(path) {
+        return Ok(pkcs11.clone());
+    }
+    let pkcs11 = Pkcs11::new(path)?;
+    pkcs11.initialize(CInitializeArgs::OsThreads)?;
+    modules.insert(path.to_owned(), pkcs11.clone());
+    Ok(pkcs11)
+}
+
+/// A private key in a PKCS#11 token, and the logged-in session used to sign with it. Sessions
+/// are not safe to use from two threads at once, so signatures are made one at a time.
+struct Pkcs11Key {
+    session: Mutex<Session>,
+    handle: ObjectHandle,
+}
+
+/// Signs with a private key held in an HSM or other PKCS#11 token, such as SoftHSM.
+#[derive(Clone)]
+pub struct NeonPkcs11Signer {
+    key: Arc<Pkcs11Key>,
+    alg: SigningAlg,
+    certs: Vec<Vec<u8>>,
+    reserve_size: usize,
+    tsa_url: Option<String>,
+}
+
+impl NeonPkcs11Signer {
+    /// Parses `{ modulePath, pin, alg, certs, tokenLabel?, keyLabel?, keyId?, reserveSize?,
+    /// tsaUrl? }`, then opens a session on the token and finds the key.
+    pub fn new(mut cx: FunctionContext) -> JsResult<JsBox<Self>> {
+        let config = cx.argument::<JsObject>(0)?;
+        let module_path = config
+            .get::<JsString, _, _>(&mut cx, "modulePath")?
+            .value(&mut cx);
+        let pin = config.get::<JsString, _, _>(&mut cx, "pin")?.value(&mut cx);
+        let alg_str = config.get::<JsString, _, _>(&mut cx, "alg")?.value(&mut cx);
+        let alg = SigningAlg::from_str(&alg_str).or_else(|err| cx.throw_error(err.to_string()))?;
+
+        let mut certs = Vec::new();
+        for cert in config
+            .get::<JsArray, _, _>(&mut cx, "certs")?
+            .to_vec(&mut cx)?
+        {
+            let cert = cert.downcast_or_throw::<JsBuffer, _>(&mut cx)?;
+            let pems = pem::parse_many(cert.as_slice(&cx))
+                .or_else(|err| cx.throw_error(format!("Invalid certificate: {err}")))?;
+            certs.extend(pems.into_iter().map(|pem| pem.into_contents()));
+        }
+        if certs.is_empty() {
+            return cx.throw_error("A PKCS#11 signer needs the certificate chain of its key");
+        }
+
+        let token_label = config
+            .get_opt::<JsString, _, _>(&mut cx, "tokenLabel")?
+            .map(|label| label.value(&mut cx));
+        let key_label = config
+            .get_opt::<JsString, _, _>(&mut cx, "keyLabel")?
+            .map(|label| label.value(&mut cx));
+        let key_id = config
+            .get_opt::<JsBuffer, _, _>(&mut cx, "keyId")?
+            .map(|id| id.as_slice(&cx).to_vec());
+        if key_label.is_none() && key_id.is_none() {
+            return cx.throw_error("A PKCS#11 signer needs a keyLabel or keyId");
+        }
+        let reserve_size = config
+            .get_opt::<JsNumber, _, _>(&mut cx, "reserveSize")?
+            .map(|size| size.value(&mut cx) as usize)
+            .unwrap_or(DEFAULT_RESERVE_SIZE);
+        let tsa_url = config
+            .get_opt::<JsString, _, _>(&mut cx, "tsaUrl")?
+            .map(|url| url.value(&mut cx));
+
+        let key = Self::open_key(
+            &module_path,
+            token_label.as_deref(),
+            &pin,
+            key_label.as_deref(),
+            key_id,
+        )
+        .or_else(|err| as_js_error_fn(&mut cx, err).and_then(|err| cx.throw(err)))?;
+
+        Ok(cx.boxed(Self {
+            key: Arc::new(key),
+            alg,
+            certs,
+            reserve_size,
+            tsa_url,
+        }))
+    }
+
+    /// Logs in to the token labelled `token_label`, or the first token without a label, and
+    /// finds the one private key matching `key_label` and `key_id`.
+    fn open_key(
+        module_path: &str,
+        token_label: Option<&str>,
+        pin: &str,
+        key_label: Option<&str>,
+        key_id: Option<Vec<u8>>,
+    ) -> Result<Pkcs11Key, Error> {
+        let pkcs11 = module(module_path)?;
+        let mut slot = None;
+        for candidate in pkcs11.get_slots_with_token()? {
+            let label = pkcs11.get_token_info(candidate)?.label().trim().to_owned();
+            if token_label.is_none_or(|token_label| token_label == label) {
+                slot = Some(candidate);
+                break;
+            }
+        }
+        let slot = slot.ok_or_else(|| match token_label {
+            Some(label) => Error::Signing(format!("No PKCS#11 token labelled {label:?}")),
+            None => Error::Signing("No PKCS#11 token found".to_string()),
+        })?;
+
+        let session = pkcs11.open_ro_session(slot)?;
+        // Another signer on the same token may have logged in already.
+        match session.login(UserType::User, Some(&AuthPin::new(pin.to_owned()))) {
+            Ok(()) | Err(cryptoki::error::Error::Pkcs11(RvError::UserAlreadyLoggedIn, ..)) => (),
+            Err(err) => return Err(err.into()),
+        }
+
+        let mut template = vec![Attribute::Class(ObjectClass::PRIVATE_KEY)];
+        if let Some(label) = key_label {
+            template.push(Attribute::Label(label.as_bytes().to_vec()));
+        }
+        if let Some(id) = key_id {
+            template.push(Attribute::Id(id));
+        }
+        let handle = match session.find_objects(&template)?.as_slice() {
+            [handle] => *handle,
+            [] => {
+                return Err(Error::Signing(
+                    "No matching PKCS#11 private key".to_string(),
+                ))
+            }
+            _ => {
+                return Err(Error::Signing(
+                    "More than one PKCS#11 private key matches; give both keyLabel and keyId"
+                        .to_string(),
+                ))
+            }
+        };
+
+        Ok(Pkcs11Key {
+            session: Mutex::new(session),
+            handle,
+        })
+    }
+
+    /// Signs `data` on the token, blocking until it responds. ECDSA keys sign a digest the
+    /// signer computes, since not every token supports hashing ECDSA mechanisms.
+    fn sign_blocking(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let pss = |hash_alg, mgf, s_len: u64| PkcsPssParams {
+            hash_alg,
+            mgf,
+            s_len: s_len.into(),
+        };
+        let (mechanism, message) = match self.alg {
+            SigningAlg::Es256 => (Mechanism::Ecdsa, Sha256::digest(data).to_vec()),
+            SigningAlg::Es384 => (Mechanism::Ecdsa, Sha384::digest(data).to_vec()),
+            SigningAlg::Es512 => (Mechanism::Ecdsa, Sha512::digest(data).to_vec()),
+            SigningAlg::Ps256 => (
+                Mechanism::Sha256RsaPkcsPss(pss(
+                    MechanismType::SHA256,
+                    PkcsMgfType::MGF1_SHA256,
+                    32,
+                )),
+                data.to_vec(),
+            ),
+            SigningAlg::Ps384 => (
+                Mechanism::Sha384RsaPkcsPss(pss(
+                    MechanismType::SHA384,
+                    PkcsMgfType::MGF1_SHA384,
+                    48,
+                )),
+                data.to_vec(),
+            ),
+            SigningAlg::Ps512 => (
+                Mechanism::Sha512RsaPkcsPss(pss(
+                    MechanismType::SHA512,
+                    PkcsMgfType::MGF1_SHA512,
+                    64,
+                )),
+                data.to_vec(),
+            ),
+            SigningAlg::Ed25519 => (Mechanism::Eddsa, data.to_vec()),
+        };
+        let session = self
+            .key
+            .session
+            .lock()
+            .map_err(|err| Error::Lock(err.to_string()))?;
+        Ok(session.sign(&mechanism, self.key.handle, &message)?)
+    }
+
+    pub fn alg(mut cx: FunctionContext) -> JsResult<JsString> {
+        let this = cx.this::<JsBox<Self>>()?;
+        let alg = this.alg.to_string();
+        Ok(cx.string(alg))
+    }
+
+    pub fn certs(mut cx: FunctionContext) -> JsResult<JsArray> {
+        let this = cx.this::<JsBox<Self>>()?;
+        let js_array = JsArray::new(&mut cx, this.certs.len());
+        for (i, cert) in this.certs.iter().enumerate() {
+            let js_buffer = JsBuffer::from_slice(&mut cx, cert.as_slice())?;
+            js_array.set(&mut cx, i as u32, js_buffer)?;
+        }
+        Ok(js_array)
+    }
+
+    pub fn reserve_size(mut cx: FunctionContext) -> JsResult<JsNumber> {
+        let this = cx.this::<JsBox<Self>>()?;
+        Ok(cx.number(this.reserve_size as f64))
+    }
+
+    pub fn time_authority_url(mut cx: FunctionContext) -> JsResult<JsValue> {
+        let this = cx.this::<JsBox<Self>>()?;
+        match this.tsa_url.clone() {
+            Some(url) => Ok(cx.string(url).upcast()),
+            None => Ok(cx.undefined().upcast()),
+        }
+    }
+
+    /// Signs `data` off the JS thread, since tokens can take a while to respond.
+    pub fn sign(mut cx: FunctionContext) -> JsResult<JsPromise> {
+        let rt = runtime();
+        let this = cx.this::<JsBox<Self>>()?;
+        let signer = (**this).clone();
+        let data = cx.argument::<JsBuffer>(0)?.as_slice(&cx).to_vec();
+        let channel = cx.channel();
+        let (deferred, promise) = cx.promise();
+
+        rt.spawn(async move {
+            let result = AsyncSigner::sign(&signer, data).await;
+            deferred.settle_with(&channel, move |mut cx| match result {
+                Ok(signature) => JsBuffer::from_slice(&mut cx, &signature),
+                Err(err) => cx.throw_error(err.to_string()),
+            });
+        });
+        Ok(promise)
+    }
+}
+
+impl Signer for NeonPkcs11Signer {
+    fn sign(&self, data: &[u8]) -> c2pa::Result<Vec<u8>> {
+        self.sign_blocking(data)
+            .map_err(|err| OtherError(Box::new(err)))
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.alg
+    }
+
+    fn certs(&self) -> c2pa::Result<Vec<Vec<u8>>> {
+        Ok(self.certs.clone())
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.reserve_size
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.tsa_url.clone()
+    }
+}
+
+#[async_trait]
+impl AsyncSigner for NeonPkcs11Signer {
+    async fn sign(&self, data: Vec<u8>) -> c2pa::Result<Vec<u8>> {
+        let signer = self.clone();
+        tokio::task::spawn_blocking(move || signer.sign_blocking(&data))
+            .await
+            .map_err(Error::from)
+            .and_then(|signature| signature)
+            .map_err(|err| OtherError(Box::new(err)))
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.alg
+    }
+
+    fn certs(&self) -> c2pa::Result<Vec<Vec<u8>>> {
+        Ok(self.certs.clone())
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.reserve_size
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.tsa_url.clone()
+    }
+}
+
+impl Finalize for NeonPkcs11Signer {}
@@ -11,20 +11,133 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use crate::asset::parse_asset;
-use crate::error::{as_js_error, Error, Result};
+use crate::abort::Abort;
+use crate::asset::{parse_asset, Asset};
+use crate::error::{as_js_error, as_js_error_fn, Error, Result};
+use crate::progress::Progress;
+use crate::remote_manifest::RemoteManifestOptions;
 use crate::runtime::runtime;
-use crate::utils::parse_settings;
-use c2pa::Reader;
+use crate::sidecar::find_sidecar;
+use crate::soft_binding::{check_recovered, SoftBindingRecovery};
+use crate::utils::{
+    context_from_settings, owned_context, parse_settings, parse_settings_string,
+    parse_shared_context, parse_trust_config, settings_with_trust,
+};
+use c2pa::{Context, Reader};
+use c2pa_types::{RecoveredSoftBinding, TrustConfig, ValidationReport};
+use futures::stream::{self, StreamExt};
 use neon::context::Context as NeonContext;
 use neon::prelude::*;
 use neon::types::buffer::TypedArray;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Cursor;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Reader behavior not covered by c2pa settings, passed as the last argument to
+/// `readerFromAsset`.
+#[derive(Default)]
+struct ReaderOptions {
+    /// Fall back to a neighbouring `.c2pa` sidecar when a file asset has no embedded manifest.
+    sidecar: bool,
+    /// Follow remote manifest references with these limits, or with a JS resolver.
+    remote_manifest: Option<RemoteManifestOptions>,
+    /// Recover the manifest of an asset that has none from its watermark.
+    soft_binding: Option<SoftBindingRecovery>,
+    /// Trust lists applied on top of the reader settings.
+    trust: Option<TrustConfig>,
+    /// Reports how much of the asset has been read.
+    progress: Progress,
+}
+
+impl ReaderOptions {
+    fn from_js(cx: &mut FunctionContext, arg_index: usize) -> NeonResult<Self> {
+        let Some(obj) = cx
+            .argument_opt(arg_index)
+            .and_then(|value| value.downcast::<JsObject, _>(cx).ok())
+        else {
+            return Ok(Self::default());
+        };
+
+        let sidecar = obj
+            .get_opt::<JsBoolean, _, _>(cx, "sidecar")?
+            .is_some_and(|sidecar| sidecar.value(cx));
+        let remote_manifest = match obj.get_opt::<JsObject, _, _>(cx, "remoteManifest")? {
+            Some(remote_manifest) => Some(RemoteManifestOptions::from_js(cx, remote_manifest)?),
+            None => None,
+        };
+        let soft_binding = match obj.get_opt::<JsObject, _, _>(cx, "softBinding")? {
+            Some(soft_binding) => Some(SoftBindingRecovery::from_js(cx, soft_binding)?),
+            None => None,
+        };
+        let trust = parse_trust_config(cx, arg_index, "Reader")?;
+        let progress = match obj.get_opt::<JsFunction, _, _>(cx, "onProgress")? {
+            Some(callback) => Progress::new(cx, callback),
+            None => Progress::default(),
+        };
+        Ok(Self {
+            sidecar,
+            remote_manifest,
+            soft_binding,
+            trust,
+            progress,
+        })
+    }
+}
+
+/// Assets read at once by `verifyBatch` when no concurrency is given.
+const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// The outcome of reading one asset of a `verifyBatch` call.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchVerifyResult {
+    index: usize,
+    /// The manifest store, or `None` when the asset has no C2PA data or could not be read.
+    manifest_store: Option<Value>,
+    validation_report: Option<ValidationReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl BatchVerifyResult {
+    fn new(index: usize, result: Result<(Reader, Option<RecoveredSoftBinding>)>) -> Self {
+        let (reader, recovered_by) = match result {
+            Ok(read) => read,
+            Err(Error::C2pa(c2pa::Error::JumbfNotFound)) => return Self::failed(index, None),
+            Err(err) => return Self::failed(index, Some(err.to_string())),
+        };
+        match serde_json::from_str(&reader.json()) {
+            Ok(manifest_store) => Self {
+                index,
+                manifest_store: Some(manifest_store),
+                validation_report: Some(ValidationReport {
+                    recovered_by,
+                    ..ValidationReport::from_reader(&reader)
+                }),
+                error: None,
+            },
+            Err(err) => Self::failed(index, Some(Error::from(err).to_string())),
+        }
+    }
+
+    fn failed(index: usize, error: Option<String>) -> Self {
+        Self {
+            index,
+            manifest_store: None,
+            validation_report: None,
+            error,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct NeonReader {
     reader: Arc<Mutex<Reader>>,
+    /// The soft binding the manifest store was recovered by, when the asset had none.
+    recovered_by: Option<RecoveredSoftBinding>,
 }
 
 impl Finalize for NeonReader {}
@@ -33,6 +146,7 @@ impl NeonReader {
     pub fn new(mut cx: FunctionContext) -> JsResult<JsBox<NeonReader>> {
         Ok(cx.boxed(Self {
             reader: Arc::new(Mutex::new(Reader::default())),
+            recovered_by: None,
         }))
     }
 
@@ -48,58 +162,385 @@ impl NeonReader {
             .argument::<JsObject>(0)
             .and_then(|obj| parse_asset(&mut cx, obj))?;
 
-        // Parse optional settings parameter (argument 1)
-        let context_opt =
-            parse_settings(&mut cx, 1, "Reader").or_else(|err| cx.throw_error(err.to_string()))?;
+        // Parse optional reader options (argument 2) and settings (argument 1)
+        let mut options = ReaderOptions::from_js(&mut cx, 2)?;
+        let abort = Abort::from_js(&mut cx, 3)?;
+        let context_opt = Self::reader_context(&mut cx, 1, &mut options, &abort)?;
+
+        // Only file assets have a neighbouring sidecar to look for.
+        let sidecar = source
+            .path()
+            .filter(|_| options.sidecar)
+            .and_then(find_sidecar);
+        let soft_binding = options.soft_binding;
+        let progress = options.progress;
 
         let (deferred, promise) = cx.promise();
         rt.spawn(async move {
-            let result: Result<Reader> = async {
-                let format = source
-                    .mime_type()
-                    .ok_or_else(|| {
-                        Error::Reading("Source asset must have a mime type".to_string())
-                    })?
-                    .to_owned();
-
-                let stream = source.into_read_stream()?;
-
-                // Create reader with or without context
-                let reader = if let Some(context) = context_opt {
-                    Reader::from_context(context)
-                        .with_stream_async(&format, stream)
-                        .await?
-                } else {
-                    Reader::default().with_stream_async(&format, stream).await?
-                };
+            let result = abort
+                .run(Self::read_asset(
+                    source,
+                    context_opt,
+                    sidecar,
+                    soft_binding.as_ref(),
+                    progress,
+                ))
+                .await;
 
-                Ok(reader)
-            }
-            .await;
+            deferred.settle_with(&channel, move |mut cx| {
+                Self::reader_or_null(&mut cx, result)
+            });
+        });
+        Ok(promise)
+    }
 
-            deferred.settle_with(&channel, move |mut cx| match result {
-                Ok(reader) => {
-                    let boxed_reader = cx.boxed(Self {
-                        reader: Arc::new(Mutex::new(reader)),
+    /// Reads many assets concurrently, resolving to a JSON array with the manifest store and
+    /// validation report, or the error, of each asset in the order given.
+    pub fn verify_batch(mut cx: FunctionContext) -> JsResult<JsPromise> {
+        let rt = runtime();
+        let channel = cx.channel();
+        let sources = cx
+            .argument::<JsArray>(0)?
+            .to_vec(&mut cx)?
+            .into_iter()
+            .map(|value| {
+                let obj = value.downcast_or_throw::<JsObject, _>(&mut cx)?;
+                parse_asset(&mut cx, obj)
+            })
+            .collect::<NeonResult<Vec<_>>>()?;
+
+        // Parse optional batch options (argument 2) and settings (argument 1)
+        let mut options = ReaderOptions::from_js(&mut cx, 2)?;
+        let abort = Abort::from_js(&mut cx, 3)?;
+        let context_opt = Self::reader_context(&mut cx, 1, &mut options, &abort)?;
+        let concurrency = match cx
+            .argument_opt(2)
+            .and_then(|value| value.downcast::<JsObject, _>(&mut cx).ok())
+        {
+            Some(obj) => obj
+                .get_opt::<JsNumber, _, _>(&mut cx, "concurrency")?
+                .map(|concurrency| (concurrency.value(&mut cx) as usize).max(1)),
+            None => None,
+        }
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+        let sidecar = options.sidecar;
+        let soft_binding = options.soft_binding.map(Arc::new);
+
+        let (deferred, promise) = cx.promise();
+        let handle = rt.clone();
+        rt.spawn(async move {
+            // Each asset is read on its own task so the batch is spread over the runtime's
+            // worker threads, with at most `concurrency` assets in flight at once.
+            let mut results: Vec<BatchVerifyResult> = stream::iter(sources.into_iter().enumerate())
+                .map(|(index, source)| {
+                    let context_opt = context_opt.clone();
+                    let soft_binding = soft_binding.clone();
+                    let sidecar = source.path().filter(|_| sidecar).and_then(find_sidecar);
+                    let abort = abort.clone();
+                    let task = handle.spawn(async move {
+                        abort
+                            .run(Self::read_asset(
+                                source,
+                                context_opt,
+                                sidecar,
+                                soft_binding.as_deref(),
+                                Progress::default(),
+                            ))
+                            .await
                     });
-                    Ok(boxed_reader.upcast::<JsValue>())
+                    async move {
+                        let result = task.await.map_err(Error::from).and_then(|result| result);
+                        BatchVerifyResult::new(index, result)
+                    }
+                })
+                .buffer_unordered(concurrency)
+                .collect()
+                .await;
+            results.sort_by_key(|result| result.index);
+
+            deferred.settle_with(&channel, move |mut cx| {
+                match serde_json::to_string(&results) {
+                    Ok(json) => Ok(cx.string(json)),
+                    Err(err) => {
+                        as_js_error(&mut cx, Error::from(err)).and_then(|err| cx.throw(err))
+                    }
+                }
+            });
+        });
+        Ok(promise)
+    }
+
+    /// Builds the context for a reader from its settings argument, which may also be a shared
+    /// Context, and the trust and remote manifest reader options.
+    fn reader_context(
+        cx: &mut FunctionContext,
+        arg_index: usize,
+        options: &mut ReaderOptions,
+        abort: &Abort,
+    ) -> NeonResult<Option<Arc<Context>>> {
+        let context = match parse_shared_context(cx, arg_index) {
+            Some(_) if options.trust.is_some() => cx.throw_error(
+                "Reader: A trust config cannot be combined with a shared Context; \
+                 pass it to Context.new instead",
+            )?,
+            Some(context) => Some(context),
+            None => parse_settings_string(cx, arg_index, "Reader")
+                .and_then(|settings| settings_with_trust(settings, options.trust.as_ref()))
+                .and_then(|settings| {
+                    settings
+                        .map(|settings| context_from_settings(&settings, "Reader").map(Arc::new))
+                        .transpose()
+                })
+                .or_else(|err| cx.throw_error(err.to_string()))?,
+        };
+
+        // c2pa-rs fetches remote manifests itself; the options only decide how, through a
+        // resolver on a context of the reader's own.
+        let Some(remote_manifest) = options.remote_manifest.take() else {
+            return Ok(abort.context(context));
+        };
+        let mut context = owned_context(context).or_else(|err| cx.throw_error(err.to_string()))?;
+        context.settings_mut().verify.remote_manifest_fetch = true;
+        Ok(Some(Arc::new(context.with_resolver_async(remote_manifest))))
+    }
+
+    /// Reads the manifest store embedded in `source`, falling back to a sidecar file or a
+    /// manifest recovered through the asset's watermark when it has none. Returns the soft
+    /// binding of a recovered manifest alongside the reader.
+    async fn read_asset(
+        source: Asset,
+        context_opt: Option<Arc<Context>>,
+        sidecar: Option<PathBuf>,
+        soft_binding: Option<&SoftBindingRecovery>,
+        progress: Progress,
+    ) -> Result<(Reader, Option<RecoveredSoftBinding>)> {
+        let format = source
+            .mime_type()
+            .ok_or_else(|| Error::Reading("Source asset must have a mime type".to_string()))?
+            .to_owned();
+
+        let size = source.size();
+        let mut stream = progress.read_stream(source.into_read_stream()?, size);
+
+        // Create readers with or without context
+        let new_reader = || match &context_opt {
+            Some(context) => Reader::from_shared_context(context),
+            None => Reader::default(),
+        };
+
+        let embedded = new_reader().with_stream_async(&format, &mut stream).await;
+
+        // Without an embedded manifest, fall back to a sidecar or the manifest the asset's
+        // watermark identifies.
+        let mut recovered_by = None;
+        let manifest_data = match (embedded, &sidecar, soft_binding) {
+            (Err(c2pa::Error::JumbfNotFound), Some(sidecar), _) => std::fs::read(sidecar)?,
+            (Err(c2pa::Error::JumbfNotFound), None, Some(soft_binding)) => {
+                stream.rewind()?;
+                let mut asset = Vec::new();
+                stream.read_to_end(&mut asset)?;
+                match soft_binding.recover(asset).await? {
+                    Some((manifest_data, soft_binding)) => {
+                        recovered_by = Some(soft_binding);
+                        manifest_data
+                    }
+                    None => return Err(Error::C2pa(c2pa::Error::JumbfNotFound)),
                 }
-                Err(err) => {
-                    // Check if the error is due to missing C2PA data
-                    // Return null instead of throwing for these specific cases
-                    match &err {
-                        Error::C2pa(c2pa_err) => match c2pa_err {
-                            c2pa::Error::JumbfNotFound => Ok(cx.null().upcast::<JsValue>()),
-                            _ => as_js_error(&mut cx, err).and_then(|err| cx.throw(err)),
-                        },
-                        _ => as_js_error(&mut cx, err).and_then(|err| cx.throw(err)),
+            }
+            (embedded, _, _) => return embedded.map(|reader| (reader, None)).map_err(Error::from),
+        };
+
+        stream.rewind()?;
+        let reader = new_reader()
+            .with_manifest_data_and_stream_async(&manifest_data, &format, stream)
+            .await?;
+        if let Some(recovered_by) = &recovered_by {
+            check_recovered(&reader, recovered_by)?;
+        }
+
+        Ok((reader, recovered_by))
+    }
+
+    pub fn from_fragment(mut cx: FunctionContext) -> JsResult<JsPromise> {
+        let rt = runtime();
+        let channel = cx.channel();
+        let init = cx
+            .argument::<JsObject>(0)
+            .and_then(|obj| parse_asset(&mut cx, obj))?;
+        let fragment = cx
+            .argument::<JsObject>(1)
+            .and_then(|obj| parse_asset(&mut cx, obj))?;
+
+        // Parse optional settings (argument 2) and the trust config of the options (argument 3)
+        let trust = parse_trust_config(&mut cx, 3, "Reader")?;
+        let abort = Abort::from_js(&mut cx, 4)?;
+        let context_opt = parse_settings(&mut cx, 2, trust.as_ref(), "Reader")
+            .map(|context| abort.context(context))
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        let (deferred, promise) = cx.promise();
+        rt.spawn(async move {
+            let result: Result<Reader> = abort
+                .run(async {
+                    let format = init
+                        .mime_type()
+                        .ok_or_else(|| {
+                            Error::Reading("Init segment asset must have a mime type".to_string())
+                        })?
+                        .to_owned();
+
+                    let init_stream = init.into_read_stream()?;
+                    let fragment_stream = fragment.into_read_stream()?;
+
+                    // Create reader with or without context
+                    let reader = if let Some(context) = context_opt {
+                        Reader::from_shared_context(&context)
+                            .with_fragment_async(&format, init_stream, fragment_stream)
+                            .await?
+                    } else {
+                        Reader::default()
+                            .with_fragment_async(&format, init_stream, fragment_stream)
+                            .await?
+                    };
+
+                    Ok(reader)
+                })
+                .await;
+
+            deferred.settle_with(&channel, move |mut cx| {
+                Self::reader_or_null(&mut cx, result.map(|reader| (reader, None)))
+            });
+        });
+        Ok(promise)
+    }
+
+    /// Reads every fragment of a segment list against the same init segment, resolving to an
+    /// array with one `{ index, reader, error? }` result per fragment. A fragment without C2PA
+    /// data gets a `null` reader, and one that cannot be read gets an error rather than failing
+    /// the others.
+    pub fn from_fragments(mut cx: FunctionContext) -> JsResult<JsPromise> {
+        let rt = runtime();
+        let channel = cx.channel();
+        let init = cx
+            .argument::<JsObject>(0)
+            .and_then(|obj| parse_asset(&mut cx, obj))?;
+        let fragments = cx
+            .argument::<JsArray>(1)?
+            .to_vec(&mut cx)?
+            .into_iter()
+            .map(|value| {
+                value
+                    .downcast_or_throw::<JsObject, _>(&mut cx)
+                    .and_then(|obj| parse_asset(&mut cx, obj))
+            })
+            .collect::<NeonResult<Vec<_>>>()?;
+
+        // Parse optional settings (argument 2) and the trust config of the options (argument 3)
+        let trust = parse_trust_config(&mut cx, 3, "Reader")?;
+        let abort = Abort::from_js(&mut cx, 4)?;
+        let context_opt = parse_settings(&mut cx, 2, trust.as_ref(), "Reader")
+            .map(|context| abort.context(context))
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+
+        let (deferred, promise) = cx.promise();
+        rt.spawn(async move {
+            let result: Result<Vec<Result<Reader>>> = abort
+                .run(async {
+                    let format = init
+                        .mime_type()
+                        .ok_or_else(|| {
+                            Error::Reading("Init segment asset must have a mime type".to_string())
+                        })?
+                        .to_owned();
+
+                    // Init segments are small, so hold one in memory rather than re-reading the
+                    // asset for every fragment.
+                    let mut init_bytes = Vec::new();
+                    init.into_read_stream()?.read_to_end(&mut init_bytes)?;
+                    // Every fragment gets its own reader, so share one context between them.
+                    let context = context_opt.unwrap_or_else(|| Arc::new(Context::new()));
+
+                    let mut readers = Vec::with_capacity(fragments.len());
+                    for fragment in fragments {
+                        let reader = match fragment.into_read_stream() {
+                            Ok(fragment_stream) => Reader::from_shared_context(&context)
+                                .with_fragment_async(
+                                    &format,
+                                    Cursor::new(&init_bytes),
+                                    fragment_stream,
+                                )
+                                .await
+                                .map_err(Error::from),
+                            Err(err) => Err(err),
+                        };
+                        readers.push(reader);
                     }
+                    Ok(readers)
+                })
+                .await;
+
+            deferred.settle_with(&channel, move |mut cx| {
+                let readers = match result {
+                    Ok(readers) => readers,
+                    Err(err) => return as_js_error(&mut cx, err).and_then(|err| cx.throw(err)),
+                };
+                let array = cx.empty_array();
+                for (index, reader) in readers.into_iter().enumerate() {
+                    let value = Self::fragment_result(&mut cx, index, reader)?;
+                    array.set(&mut cx, index as u32, value)?;
                 }
+                Ok(array)
             });
         });
         Ok(promise)
     }
 
+    /// Boxes a successfully read reader. Missing C2PA data resolves to `null` instead of
+    /// throwing, so callers can tell unsigned assets apart from invalid ones.
+    fn reader_or_null<'a>(
+        cx: &mut TaskContext<'a>,
+        result: Result<(Reader, Option<RecoveredSoftBinding>)>,
+    ) -> JsResult<'a, JsValue> {
+        match result {
+            Ok((reader, recovered_by)) => {
+                let boxed_reader = cx.boxed(Self {
+                    reader: Arc::new(Mutex::new(reader)),
+                    recovered_by,
+                });
+                Ok(boxed_reader.upcast::<JsValue>())
+            }
+            Err(Error::C2pa(c2pa::Error::JumbfNotFound)) => Ok(cx.null().upcast::<JsValue>()),
+            Err(err) => as_js_error(cx, err).and_then(|err| cx.throw(err)),
+        }
+    }
+
+    /// Converts the outcome of reading one fragment to a `{ index, reader, error? }` object.
+    fn fragment_result<'a>(
+        cx: &mut TaskContext<'a>,
+        index: usize,
+        result: Result<Reader>,
+    ) -> JsResult<'a, JsObject> {
+        let obj = cx.empty_object();
+        let js_index = cx.number(index as f64);
+        obj.set(cx, "index", js_index)?;
+        let reader = match result {
+            Ok(reader) => cx
+                .boxed(Self {
+                    reader: Arc::new(Mutex::new(reader)),
+                    recovered_by: None,
+                })
+                .upcast::<JsValue>(),
+            Err(Error::C2pa(c2pa::Error::JumbfNotFound)) => cx.null().upcast(),
+            Err(err) => {
+                let error = cx.string(err.to_string());
+                obj.set(cx, "error", error)?;
+                cx.null().upcast()
+            }
+        };
+        obj.set(cx, "reader", reader)?;
+        Ok(obj)
+    }
+
     pub fn from_manifest_data_and_asset(mut cx: FunctionContext) -> JsResult<JsPromise> {
         let rt = runtime();
         let channel = cx.channel();
@@ -108,41 +549,45 @@ impl NeonReader {
             .argument::<JsObject>(1)
             .and_then(|obj| parse_asset(&mut cx, obj))?;
 
-        // Parse optional settings parameter (argument 2) - note: settings are not currently used
-        // for from_manifest_data_and_asset as the c2pa-rs API doesn't support context for this method yet
-        let context_opt =
-            parse_settings(&mut cx, 2, "Reader").or_else(|err| cx.throw_error(err.to_string()))?;
+        // Parse optional settings (argument 2) and the trust config of the options (argument 3)
+        let trust = parse_trust_config(&mut cx, 3, "Reader")?;
+        let abort = Abort::from_js(&mut cx, 4)?;
+        let context_opt = parse_settings(&mut cx, 2, trust.as_ref(), "Reader")
+            .map(|context| abort.context(context))
+            .or_else(|err| cx.throw_error(err.to_string()))?;
 
         let c2pa_data = manifest_data.as_slice(&cx).to_vec();
         let (deferred, promise) = cx.promise();
         rt.spawn(async move {
-            let result = async {
-                let format = asset
-                    .mime_type()
-                    .ok_or_else(|| {
-                        Error::Reading("Source asset must have a mime type".to_string())
-                    })?
-                    .to_owned();
-                let stream = asset.into_read_stream()?;
-
-                let reader = if let Some(context) = context_opt {
-                    Reader::from_context(context)
-                        .with_manifest_data_and_stream_async(&c2pa_data, &format, stream)
-                        .await?
-                } else {
-                    Reader::default()
-                        .with_manifest_data_and_stream_async(&c2pa_data, &format, stream)
-                        .await?
-                };
+            let result = abort
+                .run(async {
+                    let format = asset
+                        .mime_type()
+                        .ok_or_else(|| {
+                            Error::Reading("Source asset must have a mime type".to_string())
+                        })?
+                        .to_owned();
+                    let stream = asset.into_read_stream()?;
 
-                Ok(reader)
-            }
-            .await;
+                    let reader = if let Some(context) = context_opt {
+                        Reader::from_shared_context(&context)
+                            .with_manifest_data_and_stream_async(&c2pa_data, &format, stream)
+                            .await?
+                    } else {
+                        Reader::default()
+                            .with_manifest_data_and_stream_async(&c2pa_data, &format, stream)
+                            .await?
+                    };
+
+                    Ok(reader)
+                })
+                .await;
 
             deferred.settle_with(&channel, move |mut cx| match result {
                 Ok(reader) => {
                     let boxed_reader = cx.boxed(Self {
                         reader: Arc::new(Mutex::new(reader)),
+                        recovered_by: None,
                     });
                     Ok(boxed_reader.upcast::<JsValue>())
                 }
@@ -160,6 +605,20 @@ impl NeonReader {
         Ok(cx.string(json).upcast())
     }
 
+    pub fn validation_report(mut cx: FunctionContext) -> JsResult<JsValue> {
+        let rt = runtime();
+        let this = cx.this::<JsBox<Self>>()?;
+        let reader = rt.block_on(async { this.reader.lock().await });
+        let report = ValidationReport {
+            recovered_by: this.recovered_by.clone(),
+            ..ValidationReport::from_reader(&reader)
+        };
+        match serde_json::to_string(&report) {
+            Ok(json) => Ok(cx.string(json).upcast()),
+            Err(err) => as_js_error_fn(&mut cx, Error::from(err)).and_then(|err| cx.throw(err)),
+        }
+    }
+
     pub fn remote_url(mut cx: FunctionContext) -> JsResult<JsValue> {
         let rt = runtime();
         let this = cx.this::<JsBox<Self>>()?;
@@ -183,6 +642,7 @@ impl NeonReader {
         let output = cx
             .argument::<JsObject>(1)
             .and_then(|obj| parse_asset(&mut cx, obj))?;
+        let abort = Abort::from_js(&mut cx, 2)?;
         let mut output_stream = output
             .write_stream()
             .or_else(|err| cx.throw_error(err.to_string()))?;
@@ -192,12 +652,17 @@ impl NeonReader {
 
         let (deferred, promise) = cx.promise();
         rt.spawn(async move {
-            let result = reader
-                .lock()
-                .await
-                .resource_to_stream(&uri, &mut output_stream)
-                .map(|bytes_written| (bytes_written, output_stream))
-                .map_err(Error::from);
+            let result = abort
+                .run_writing(&output, async {
+                    let bytes_written = reader
+                        .lock()
+                        .await
+                        .resource_to_stream(&uri, &mut output_stream)?;
+                    // Stream assets buffer writes, so push the tail to JS before resolving.
+                    output_stream.flush()?;
+                    Ok((bytes_written, output_stream))
+                })
+                .await;
 
             deferred.settle_with(&channel, move |mut cx| match result {
                 Ok((bytes_written, mut output_stream)) => {
@@ -23,13 +23,97 @@ use c2pa::{
     Error::OtherError,
     Signer, SigningAlg,
 };
+use c2pa_types::SignerDiagnostics;
 use neon::prelude::*;
 use neon::types::buffer::TypedArray;
 use std::ops::Deref;
-use std::{boxed::Box, str::FromStr, sync::Arc};
-use tokio::sync::oneshot;
+use std::{
+    boxed::Box,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crate::error::Error;
+use crate::keys;
+use crate::ocsp::{OcspSource, StapledSigner};
+use crate::progress::{Phase, Progress};
+use crate::stream::await_js;
+use crate::tsa::{TsaOptions, TsaSigner};
+
+/// The delay before the first retry of a failed signing callback, when none is configured.
+const DEFAULT_SIGN_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// How long to wait on a JS signing callback, and how often to call it again when it fails.
+#[derive(Debug, Clone)]
+pub struct SignRetryPolicy {
+    /// Gives up on a call to the callback after this long. Without one, a call is waited on
+    /// for as long as it takes.
+    pub timeout: Option<Duration>,
+    /// The number of times a timed out call, or one whose error the callback marks as
+    /// retryable, is retried.
+    pub retries: u32,
+    /// The delay before the first retry, doubled before each one after.
+    pub retry_delay: Duration,
+}
+
+impl Default for SignRetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            retries: 0,
+            retry_delay: DEFAULT_SIGN_RETRY_DELAY,
+        }
+    }
+}
+
+impl SignRetryPolicy {
+    fn from_js_config(cx: &mut FunctionContext, js_config: Handle<JsObject>) -> NeonResult<Self> {
+        let mut policy = Self::default();
+        if let Some(timeout_ms) = Self::whole_number(cx, js_config, "signTimeoutMs")? {
+            policy.timeout = Some(Duration::from_millis(timeout_ms));
+        }
+        if let Some(retries) = Self::whole_number(cx, js_config, "signRetries")? {
+            policy.retries = u32::try_from(retries).unwrap_or(u32::MAX);
+        }
+        if let Some(delay_ms) = Self::whole_number(cx, js_config, "signRetryDelayMs")? {
+            policy.retry_delay = Duration::from_millis(delay_ms);
+        }
+        Ok(policy)
+    }
+
+    // Reads an optional non-negative whole number from the config.
+    fn whole_number(
+        cx: &mut FunctionContext,
+        js_config: Handle<JsObject>,
+        key: &str,
+    ) -> NeonResult<Option<u64>> {
+        match js_config.get_opt::<JsNumber, _, _>(cx, key)? {
+            Some(number) => {
+                let value = number.value(cx);
+                if !value.is_finite() || value < 0.0 || value.fract() != 0.0 {
+                    return cx.throw_range_error(format!(
+                        "{key} must be a non-negative integer, got {value}"
+                    ));
+                }
+                Ok(Some(value as u64))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The delay before retrying after `attempt` failed calls.
+    fn delay(&self, attempt: u32) -> Duration {
+        self.retry_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
+/// A failed call to a JS signing callback, and whether the retry policy may call it again.
+struct SignAttemptError {
+    error: Box<Error>,
+    retryable: bool,
+}
 
 #[derive(Debug, Clone)]
 pub struct CallbackSignerConfig {
@@ -40,6 +124,8 @@ pub struct CallbackSignerConfig {
     pub tsa_headers: Option<Vec<(String, String)>>,
     pub tsa_body: Option<Vec<u8>>,
     pub direct_cose_handling: bool,
+    pub sign_retry_policy: SignRetryPolicy,
+    pub ocsp: Option<OcspSource>,
 }
 
 impl CallbackSignerConfig {
@@ -60,6 +146,8 @@ impl CallbackSignerConfig {
             tsa_headers,
             tsa_body,
             direct_cose_handling,
+            sign_retry_policy: SignRetryPolicy::default(),
+            ocsp: None,
         }
     }
 
@@ -120,16 +208,23 @@ impl CallbackSignerConfig {
         let tsa_body = js_config
             .get_opt::<JsBuffer, _, _>(cx, "tsaBody")?
             .map(|js_buffer| js_buffer.as_slice(cx).to_vec());
-
-        Ok(cx.boxed(Self::new(
-            alg,
-            certs,
-            reserve_size,
-            tsa_url,
-            tsa_headers,
-            tsa_body,
-            direct_cose_handling,
-        )))
+        let sign_retry_policy = SignRetryPolicy::from_js_config(cx, js_config)?;
+        let ocsp_response = js_config.get_value(cx, "ocspResponse")?;
+        let ocsp = OcspSource::from_js(cx, ocsp_response)?;
+
+        Ok(cx.boxed(Self {
+            sign_retry_policy,
+            ocsp,
+            ..Self::new(
+                alg,
+                certs,
+                reserve_size,
+                tsa_url,
+                tsa_headers,
+                tsa_body,
+                direct_cose_handling,
+            )
+        }))
     }
 }
 
@@ -147,6 +242,7 @@ pub struct NeonCallbackSigner {
     // JsFunction must be of the form (data: Buffer) => Promise<Buffer>
     callback: Arc<Root<JsFunction>>,
     config: CallbackSignerConfig,
+    progress: Progress,
 }
 
 impl NeonCallbackSigner {
@@ -159,6 +255,126 @@ impl NeonCallbackSigner {
             channel,
             callback,
             config,
+            progress: Progress::default(),
+        }
+    }
+
+    /// Returns a copy of this signer that reports the signing and timestamping phases.
+    pub(crate) fn with_progress(&self, progress: Progress) -> Self {
+        Self {
+            progress,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of this signer that staples OCSP responses from `ocsp` instead of the ones
+    /// it was configured with.
+    pub(crate) fn with_ocsp(&self, ocsp: OcspSource) -> Self {
+        let mut signer = self.clone();
+        signer.config.ocsp = Some(ocsp);
+        signer
+    }
+
+    /// The configured reserve size plus room for the OCSP response this signer staples.
+    fn total_reserve_size(&self) -> usize {
+        self.config.reserve_size
+            + self
+                .config
+                .ocsp
+                .as_ref()
+                .map_or(0, OcspSource::reserve_size)
+    }
+
+    async fn fetch_ocsp_response(&self) -> Option<Vec<u8>> {
+        match &self.config.ocsp {
+            Some(ocsp) => ocsp.fetch().await,
+            None => None,
+        }
+    }
+
+    /// Calls the JS callback once, failing with `Error::AsyncSigning` when it throws or its
+    /// promise rejects. The failure can be retried when the callback marks its error with
+    /// `retryable: true`.
+    async fn call_callback(&self, data: &[u8]) -> Result<Vec<u8>, SignAttemptError> {
+        let sign_fn = self.callback.clone();
+        let data = data.to_vec();
+
+        await_js(
+            &self.channel,
+            move |cx| {
+                let to_be_signed = JsBuffer::from_slice(cx, &data)?;
+                sign_fn
+                    .to_inner(cx)
+                    .call_with(cx)
+                    .arg(to_be_signed)
+                    .apply(cx)
+            },
+            |mut cx, result| match result {
+                Ok(value) => {
+                    let buffer = value.downcast_or_throw::<JsBuffer, _>(&mut cx)?;
+                    Ok(Ok(buffer.as_slice(&cx).to_vec()))
+                }
+                Err(err) => {
+                    let retryable = match err.downcast::<JsObject, _>(&mut cx) {
+                        Ok(obj) => obj
+                            .get_value(&mut cx, "retryable")?
+                            .downcast::<JsBoolean, _>(&mut cx)
+                            .is_ok_and(|retryable| retryable.value(&mut cx)),
+                        Err(_) => false,
+                    };
+                    let err_string = err.to_string(&mut cx)?.value(&mut cx);
+                    Ok(Err(SignAttemptError {
+                        error: Box::new(Error::AsyncSigning(err_string)),
+                        retryable,
+                    }))
+                }
+            },
+        )
+        .await
+        .unwrap_or_else(|err| {
+            Err(SignAttemptError {
+                error: Box::new(Error::AsyncSigning(err)),
+                retryable: false,
+            })
+        })
+    }
+
+    /// Calls the JS callback under the configured retry policy. An attempt that outlives the
+    /// timeout fails with `Error::TokioTimeout` and is retried, like a rejection the callback
+    /// marks as retryable; other failures are returned at once.
+    ///
+    /// A timed out call is not cancelled, since a JS promise cannot be: the callback keeps
+    /// running, and whatever it settles to is ignored. A retry can therefore overlap it.
+    async fn sign_with_retries(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.progress.phase(Phase::Signing);
+        let policy = &self.config.sign_retry_policy;
+        let mut attempt = 0;
+        loop {
+            let result = match policy.timeout {
+                Some(timeout) => tokio::time::timeout(timeout, self.call_callback(data))
+                    .await
+                    .unwrap_or_else(|elapsed| {
+                        Err(SignAttemptError {
+                            error: Box::new(elapsed.into()),
+                            retryable: true,
+                        })
+                    }),
+                None => self.call_callback(data).await,
+            };
+            match result {
+                Ok(signature) => {
+                    // c2pa-rs requests the time stamp once the signature is back.
+                    if self.config.tsa_url.is_some() {
+                        self.progress.phase(Phase::Timestamping);
+                    }
+                    return Ok(signature);
+                }
+                Err(err) if err.retryable && attempt < policy.retries => {
+                    attempt += 1;
+                    tokio::time::sleep(policy.delay(attempt)).await;
+                }
+                Err(err) => return Err(*err.error),
+            }
         }
     }
 
@@ -189,7 +405,7 @@ impl NeonCallbackSigner {
 
     pub fn reserve_size(mut cx: FunctionContext) -> JsResult<JsNumber> {
         let this = cx.this::<JsBox<Self>>()?;
-        let reserve_size = this.config.reserve_size;
+        let reserve_size = this.total_reserve_size();
         Ok(cx.number(reserve_size as f64))
     }
 
@@ -266,50 +482,9 @@ unsafe impl Sync for NeonCallbackSigner {}
 #[async_trait]
 impl AsyncSigner for NeonCallbackSigner {
     async fn sign(&self, data: Vec<u8>) -> Result<Vec<u8>, c2pa::Error> {
-        let (tx, rx) = oneshot::channel();
-        let sign_fn = self.callback.clone();
-        let data = data.to_vec();
-
-        self.channel
-            .try_send(move |mut cx| {
-                let to_be_signed = JsBuffer::from_slice(&mut cx, &data)?;
-                let sign_fn = sign_fn.to_inner(&mut cx);
-
-                let sign_fut = sign_fn
-                    .call_with(&cx)
-                    .arg(to_be_signed)
-                    .apply::<JsPromise, _>(&mut cx)?
-                    .to_future(&mut cx, |mut cx, result| match result {
-                        Ok(value) => Ok(Ok(value
-                            .downcast_or_throw::<JsBuffer, _>(&mut cx)?
-                            .as_slice(&cx)
-                            .to_vec())),
-                        Err(err) => {
-                            let js_string_result = err.to_string(&mut cx);
-                            match js_string_result {
-                                Ok(js_string) => {
-                                    let err_string: String = js_string.value(&mut cx);
-                                    Ok(Err(OtherError(Box::new(Error::AsyncSigning(err_string)))))
-                                }
-                                Err(throw) => Err(throw),
-                            }
-                        }
-                    })?;
-
-                let _ = tx.send(sign_fut);
-
-                Ok(())
-            })
-            .map_err(|err| OtherError(Box::new(err)))?;
-
-        let sign_fut = rx.await.map_err(|err| OtherError(Box::new(err)))?;
-
-        let sign_result = sign_fut
+        self.sign_with_retries(&data)
             .await
-            .map_err(|err| OtherError(Box::new(err)))?
-            .map_err(|err| OtherError(Box::new(err)))?;
-
-        Ok(sign_result)
+            .map_err(|err| OtherError(Box::new(err)))
     }
 
     fn alg(&self) -> SigningAlg {
@@ -322,13 +497,17 @@ impl AsyncSigner for NeonCallbackSigner {
     }
 
     fn reserve_size(&self) -> usize {
-        self.config.reserve_size
+        self.total_reserve_size()
     }
 
     fn direct_cose_handling(&self) -> bool {
         self.config.direct_cose_handling
     }
 
+    async fn ocsp_val(&self) -> Option<Vec<u8>> {
+        self.fetch_ocsp_response().await
+    }
+
     fn async_raw_signer(&self) -> Option<Box<&dyn AsyncRawSigner>> {
         Some(Box::new(self))
     }
@@ -355,48 +534,12 @@ impl AsyncTimeStampProvider for NeonCallbackSigner {
 #[async_trait]
 impl AsyncRawSigner for NeonCallbackSigner {
     async fn sign(&self, data: Vec<u8>) -> Result<Vec<u8>, RawSignerError> {
-        let (tx, rx) = oneshot::channel();
-        let sign_fn = self.callback.clone();
-        let data = data.to_vec();
-
-        // Send the signing request to the JavaScript side
-        self.channel
-            .try_send(move |mut cx| {
-                let to_be_signed = JsBuffer::from_slice(&mut cx, &data)?;
-                let sign_fn = sign_fn.to_inner(&mut cx);
-
-                let sign_fut = sign_fn
-                    .call_with(&cx)
-                    .arg(to_be_signed)
-                    .apply::<JsPromise, _>(&mut cx)?
-                    .to_future(&mut cx, |mut cx, result| match result {
-                        Ok(value) => {
-                            let buffer = value.downcast_or_throw::<JsBuffer, _>(&mut cx)?;
-                            Ok(Ok(buffer.as_slice(&cx).to_vec()))
-                        }
-                        Err(err) => {
-                            let err_string = match err.to_string(&mut cx) {
-                                Ok(js_string) => js_string.value(&mut cx),
-                                Err(e) => return Err(e),
-                            };
-                            Ok(Err(RawSignerError::CryptoLibraryError(err_string)))
-                        }
-                    })?;
-
-                let _ = tx.send(sign_fut);
-                Ok(())
-            })
-            .map_err(|err| RawSignerError::CryptoLibraryError(err.to_string()))?;
-
-        // Wait for the JavaScript promise to resolve
-        let sign_fut = rx
-            .await
-            .map_err(|err| RawSignerError::CryptoLibraryError(err.to_string()))?;
-
-        // Get the result from the future
-        sign_fut
+        self.sign_with_retries(&data)
             .await
-            .map_err(|err| RawSignerError::CryptoLibraryError(err.to_string()))?
+            .map_err(|err| match err {
+                Error::AsyncSigning(message) => RawSignerError::CryptoLibraryError(message),
+                err => RawSignerError::CryptoLibraryError(err.to_string()),
+            })
     }
 
     fn alg(&self) -> SigningAlg {
@@ -410,11 +553,11 @@ impl AsyncRawSigner for NeonCallbackSigner {
     }
 
     fn reserve_size(&self) -> usize {
-        self.config.reserve_size
+        self.total_reserve_size()
     }
 
     async fn ocsp_response(&self) -> Option<Vec<u8>> {
-        None
+        self.fetch_ocsp_response().await
     }
 }
 
@@ -439,25 +582,203 @@ impl RawSigner for NeonCallbackSigner {
     }
 }
 
+/// A callback signer for the c2pa-rs APIs that only take a synchronous `Signer`. Each call blocks
+/// on the runtime until the JS callback settles, so it must only be used on a blocking thread,
+/// never on the JS thread or in an async task.
+pub(crate) struct BlockingCallbackSigner {
+    signer: NeonCallbackSigner,
+    runtime: tokio::runtime::Handle,
+}
+
+impl BlockingCallbackSigner {
+    pub(crate) fn new(signer: NeonCallbackSigner, runtime: tokio::runtime::Handle) -> Self {
+        Self { signer, runtime }
+    }
+}
+
+impl Signer for BlockingCallbackSigner {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, c2pa::Error> {
+        self.runtime
+            .block_on(AsyncSigner::sign(&self.signer, data.to_vec()))
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.signer.config.alg
+    }
+
+    fn certs(&self) -> Result<Vec<Vec<u8>>, c2pa::Error> {
+        AsyncSigner::certs(&self.signer)
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.signer.total_reserve_size()
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.signer.config.tsa_url.clone()
+    }
+
+    fn timestamp_request_headers(&self) -> Option<Vec<(String, String)>> {
+        self.signer.config.tsa_headers.clone()
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        self.runtime.block_on(self.signer.fetch_ocsp_response())
+    }
+
+    fn direct_cose_handling(&self) -> bool {
+        self.signer.config.direct_cose_handling
+    }
+}
+
 pub struct NeonLocalSigner {
     signer: Box<dyn Signer>,
+    keys: Arc<LocalSignerKeys>,
+}
+
+/// The key material a `NeonLocalSigner` was created from, so other threads can create their
+/// own signer from it.
+pub(crate) struct LocalSignerKeys {
+    signcert: Vec<u8>,
+    pkey: Vec<u8>,
+    alg: SigningAlg,
+    tsa_url: Option<String>,
+    tsa: Option<TsaOptions>,
+    ocsp_response: Option<Vec<u8>>,
+}
+
+impl LocalSignerKeys {
+    pub(crate) fn signer(&self) -> Result<Box<dyn Signer>, Error> {
+        let signer =
+            create_signer::from_keys(&self.signcert, &self.pkey, self.alg, self.tsa_url.clone())?;
+        let signer: Box<dyn Signer> = match &self.tsa {
+            Some(options) => Box::new(TsaSigner::new(signer, options.clone())),
+            None => signer,
+        };
+        Ok(match &self.ocsp_response {
+            Some(response) => Box::new(StapledSigner::new(signer, response.clone())),
+            None => signer,
+        })
+    }
 }
 
 impl NeonLocalSigner {
     pub fn new(mut cx: FunctionContext) -> JsResult<JsBox<Self>> {
         let signcert = cx.argument::<JsBuffer>(0)?.as_slice(&cx).to_vec();
         let pkey = cx.argument::<JsBuffer>(1)?.as_slice(&cx).to_vec();
-        let alg_str = cx.argument::<JsString>(2)?.value(&mut cx);
-        let alg = SigningAlg::from_str(&alg_str).or_else(|err| cx.throw_error(err.to_string()))?;
-        let tsa_url = cx.argument_opt(3).and_then(|js_value| {
-            js_value
-                .downcast::<JsString, _>(&mut cx)
-                .ok()
-                .map(|js_string| js_string.value(&mut cx))
-        });
-        let signer = create_signer::from_keys(&signcert, &pkey, alg, tsa_url)
+        Self::from_keys(cx, signcert, pkey)
+    }
+
+    /// Creates a signer from the key and certificate chain in a PKCS#12 (PFX) bundle.
+    pub fn from_pkcs12(mut cx: FunctionContext) -> JsResult<JsBox<Self>> {
+        let bundle = cx.argument::<JsBuffer>(0)?.as_slice(&cx).to_vec();
+        let passphrase = cx.argument::<JsString>(1)?.value(&mut cx);
+        let (signcert, pkey) = keys::from_pkcs12(&bundle, &passphrase)
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+        Self::from_keys(cx, signcert, pkey)
+    }
+
+    /// Reads the arguments that follow the keys: `alg?`, `tsa?` and
+    /// `{ ocspResponse?, passphrase? }`.
+    fn from_keys(
+        mut cx: FunctionContext,
+        signcert: Vec<u8>,
+        pkey: Vec<u8>,
+    ) -> JsResult<JsBox<Self>> {
+        let alg = match cx.argument_opt(2) {
+            Some(js_value) if js_value.is_a::<JsString, _>(&mut cx) => {
+                let alg_str = js_value
+                    .downcast_or_throw::<JsString, _>(&mut cx)?
+                    .value(&mut cx);
+                Some(
+                    SigningAlg::from_str(&alg_str)
+                        .or_else(|err| cx.throw_error(err.to_string()))?,
+                )
+            }
+            _ => None,
+        };
+        // The fourth argument is either a time stamp authority URL or time stamp options.
+        let (tsa_url, tsa) = match cx.argument_opt(3) {
+            Some(js_value) if js_value.is_a::<JsString, _>(&mut cx) => {
+                let url = js_value.downcast_or_throw::<JsString, _>(&mut cx)?;
+                (Some(url.value(&mut cx)), None)
+            }
+            Some(js_value) if js_value.is_a::<JsObject, _>(&mut cx) => {
+                let obj = js_value.downcast_or_throw::<JsObject, _>(&mut cx)?;
+                let options = TsaOptions::from_js(&mut cx, obj)?;
+                (Some(options.primary_url().to_owned()), Some(options))
+            }
+            _ => (None, None),
+        };
+        let (ocsp_response, passphrase, check_certificates) = match cx.argument_opt(4) {
+            Some(js_value) if js_value.is_a::<JsObject, _>(&mut cx) => {
+                let options = js_value.downcast_or_throw::<JsObject, _>(&mut cx)?;
+                let ocsp_response = options.get_value(&mut cx, "ocspResponse")?;
+                let ocsp_response = match OcspSource::from_js(&mut cx, ocsp_response)? {
+                    Some(OcspSource::Response(response)) => Some(response),
+                    // Local signers sign on the calling thread, so they cannot wait on a promise.
+                    Some(OcspSource::Callback { .. }) => {
+                        return cx.throw_type_error(
+                            "Local signers take an OCSP response as a Buffer, not a callback",
+                        )
+                    }
+                    None => None,
+                };
+                let passphrase = options
+                    .get_opt::<JsString, _, _>(&mut cx, "passphrase")?
+                    .map(|passphrase| passphrase.value(&mut cx));
+                let check_certificates = options
+                    .get_opt::<JsBoolean, _, _>(&mut cx, "checkCertificates")?
+                    .is_none_or(|check| check.value(&mut cx));
+                (ocsp_response, passphrase, check_certificates)
+            }
+            _ => (None, None, true),
+        };
+        let pkey = keys::private_key_pem(&pkey, passphrase.as_deref())
+            .or_else(|err| cx.throw_error(err.to_string()))?;
+        let alg = match alg {
+            Some(alg) => alg,
+            None => keys::signing_alg(&pkey).or_else(|err| cx.throw_error(err.to_string()))?,
+        };
+        if check_certificates {
+            let diagnostics = SignerDiagnostics::check_pem(&signcert, Some(alg), unix_now());
+            throw_if_invalid(&mut cx, &diagnostics)?;
+        }
+        let keys = LocalSignerKeys {
+            signcert,
+            pkey,
+            alg,
+            tsa_url,
+            tsa,
+            ocsp_response,
+        };
+        let signer = keys
+            .signer()
             .or_else(|err| cx.throw_error(format!("Failed to create signer from keys: {err}")))?;
-        Ok(cx.boxed(Self { signer }))
+        Ok(cx.boxed(Self {
+            signer,
+            keys: Arc::new(keys),
+        }))
+    }
+
+    /// Checks a PEM certificate chain, and optionally the algorithm it will sign with, without
+    /// creating a signer. Returns the `SignerDiagnostics` report.
+    pub fn check_certificates(mut cx: FunctionContext) -> JsResult<JsValue> {
+        let certs = cx.argument::<JsBuffer>(0)?.as_slice(&cx).to_vec();
+        let alg = match cx.argument_opt(1) {
+            Some(js_value) if js_value.is_a::<JsString, _>(&mut cx) => {
+                let alg_str = js_value
+                    .downcast_or_throw::<JsString, _>(&mut cx)?
+                    .value(&mut cx);
+                Some(
+                    SigningAlg::from_str(&alg_str)
+                        .or_else(|err| cx.throw_type_error(err.to_string()))?,
+                )
+            }
+            _ => None,
+        };
+        let diagnostics = SignerDiagnostics::check_pem(&certs, alg, unix_now());
+        neon_serde4::to_value(&mut cx, &diagnostics).or_else(|err| cx.throw_error(err.to_string()))
     }
 
     #[allow(clippy::borrowed_box)]
@@ -465,6 +786,10 @@ impl NeonLocalSigner {
         &self.signer
     }
 
+    pub(crate) fn keys(&self) -> Arc<LocalSignerKeys> {
+        Arc::clone(&self.keys)
+    }
+
     pub fn sign(mut cx: FunctionContext) -> JsResult<JsBuffer> {
         let this = cx.this::<JsBox<Self>>()?;
         let data = cx.argument::<JsBuffer>(0)?.as_slice(&cx).to_vec();
@@ -509,6 +834,30 @@ impl NeonLocalSigner {
     }
 }
 
+/// Throws when `diagnostics` found a problem. The report is attached to the error as
+/// `diagnostics`, so callers can tell the issues apart without parsing the message.
+fn throw_if_invalid<'a>(
+    cx: &mut FunctionContext<'a>,
+    diagnostics: &SignerDiagnostics,
+) -> NeonResult<()> {
+    if diagnostics.valid {
+        return Ok(());
+    }
+    let js_err = cx.error(format!(
+        "The signing certificates cannot be used with this signer:\n{diagnostics}"
+    ))?;
+    let js_diagnostics =
+        neon_serde4::to_value(cx, diagnostics).or_else(|err| cx.throw_error(err.to_string()))?;
+    js_err.set(cx, "diagnostics", js_diagnostics)?;
+    cx.throw(js_err)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
 impl Finalize for NeonLocalSigner {}
 impl Finalize for CallbackSignerConfig {}
 impl Finalize for NeonCallbackSigner {}
@@ -11,17 +11,24 @@
 // specific language governing permissions and limitations under
 // each license.
 
+use crate::abort::Abort;
+use crate::bch::{CorrectedPayload, PayloadCheck};
 use crate::error::{as_js_error, as_js_error_fn, Error, Result};
 use crate::runtime::runtime;
+use crate::trustmark_decoder::PayloadDecoder;
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageFormat};
+use img_parts::{Bytes, DynImage, ImageEXIF, ImageICC};
 use neon::prelude::*;
 use neon::result::{JsResult, NeonResult};
 use neon::types::{buffer::TypedArray, Finalize, JsObject};
 use rand::{distributions::Standard, prelude::Distribution as _};
 use reqwest::Client;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::{
     fs::OpenOptions,
-    io::Write,
-    path::PathBuf,
+    io::{Cursor, Write},
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     time::Duration,
 };
@@ -32,14 +39,68 @@ pub struct WatermarkConfig {
     variant: Variant,
     version: Version,
     model_path: PathBuf,
+    /// Never download models; they must be in `model_path` or passed as `models`.
+    offline: bool,
+    /// Model files passed in memory, used instead of `model_path`.
+    models: Option<ModelPair<Vec<u8>>>,
+    /// SHA-256 digests of the variant's model files, as lowercase hex, overriding the digests
+    /// pinned in the crate.
+    checksums: Option<ModelPair<String>>,
 }
 
+/// The encoder and decoder model of a variant.
+#[derive(Clone)]
+pub struct ModelPair<T> {
+    encoder: T,
+    decoder: T,
+}
+
+impl<T> ModelPair<T> {
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        [&self.encoder, &self.decoder].into_iter()
+    }
+}
+
+#[derive(Clone)]
 pub struct NeonTrustmark {
     // All Trustmark calls are synchronous, so the standard Mutex works.
     trustmark: Arc<Mutex<Trustmark>>,
+    /// Decodes payloads, reporting how their BCH code corrected them.
+    decoder: Arc<PayloadDecoder>,
+    variant: Variant,
     version: Version,
 }
 
+/// A watermark embedded in an image, as recorded in a `c2pa.soft-binding` assertion.
+pub(crate) struct SoftBinding {
+    /// The soft binding algorithm, named after the Trustmark variant.
+    pub alg: String,
+    /// The embedded watermark bitstring.
+    pub value: String,
+}
+
+/// The watermark decoded from an image.
+pub(crate) enum DecodedWatermark {
+    Decoded(SoftBinding),
+    /// The payload names a schema version but has more bit flips than its BCH code corrects.
+    Corrupt,
+    /// The payload names no schema version and no BCH code corrects it.
+    Absent,
+}
+
+impl SoftBinding {
+    pub(crate) const LABEL: &'static str = "c2pa.soft-binding";
+
+    /// Returns the assertion binding the watermark to the whole image.
+    pub(crate) fn assertion(&self) -> serde_json::Value {
+        serde_json::json!({
+            "alg": self.alg,
+            "blocks": [{ "scope": {}, "value": self.value }],
+            "pad": [],
+        })
+    }
+}
+
 impl Finalize for NeonTrustmark {}
 
 pub fn parse_watermark_config(
@@ -77,10 +138,65 @@ pub fn parse_watermark_config(
         .map(|val| std::path::PathBuf::from(val.value(cx)))
         .unwrap_or_else(|| PathBuf::from("./models"));
 
+    let offline = obj
+        .get_opt::<JsBoolean, _, _>(cx, "offline")?
+        .is_some_and(|val| val.value(cx));
+
+    let models = match obj.get_opt::<JsObject, _, _>(cx, "models")? {
+        Some(models) => {
+            let mut model = |name: &str| -> NeonResult<Vec<u8>> {
+                match models.get_opt::<JsBuffer, _, _>(cx, name)? {
+                    Some(buffer) => Ok(buffer.as_slice(cx).to_vec()),
+                    None => cx.throw_error(format!(
+                        "Watermark configuration error: models.{name} must be a Buffer"
+                    )),
+                }
+            };
+            Some(ModelPair {
+                encoder: model("encoder")?,
+                decoder: model("decoder")?,
+            })
+        }
+        None => None,
+    };
+
+    // Checksums are keyed by variant, so one pinned set can be shared by every configuration.
+    let checksums = match obj
+        .get_opt::<JsObject, _, _>(cx, "checksums")?
+        .map(|checksums| checksums.get_opt::<JsObject, _, _>(cx, variant_str.as_str()))
+        .transpose()?
+        .flatten()
+    {
+        Some(pinned) => {
+            let mut digest = |name: &str| -> NeonResult<String> {
+                let digest = pinned
+                    .get_opt::<JsString, _, _>(cx, name)?
+                    .map(|val| val.value(cx).to_ascii_lowercase())
+                    .filter(|digest| {
+                        digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit())
+                    });
+                match digest {
+                    Some(digest) => Ok(digest),
+                    None => cx.throw_error(format!(
+                        "Watermark configuration error: checksums.{variant_str}.{name} must be a hex SHA-256 digest"
+                    )),
+                }
+            };
+            Some(ModelPair {
+                encoder: digest("encoder")?,
+                decoder: digest("decoder")?,
+            })
+        }
+        None => None,
+    };
+
     Ok(WatermarkConfig {
         variant,
         version,
         model_path,
+        offline,
+        models,
+        checksums,
     })
 }
 
@@ -99,25 +215,102 @@ impl NeonTrustmark {
         let config = cx
             .argument::<JsObject>(0)
             .and_then(|obj| parse_watermark_config(&mut cx, obj))?;
-        let model_path = match fetch_model(config.variant, &config.model_path) {
-            Ok(path) => path,
+        let (trustmark, decoder) = match load_models(&config) {
+            Ok(models) => models,
             Err(err) => return as_js_error_fn(&mut cx, err).and_then(|err| cx.throw(err)),
         };
-        let trustmark = match Trustmark::new(model_path, config.variant, config.version) {
-            Ok(trustmark) => trustmark,
-            Err(err) => return as_js_error_fn(&mut cx, err.into()).and_then(|err| cx.throw(err)),
-        };
 
         Ok(cx.boxed(Self {
             trustmark: Arc::new(Mutex::new(trustmark)),
+            decoder: Arc::new(decoder),
+            variant: config.variant,
             version: config.version,
         }))
     }
 
+    /// Watermarks an encoded image and encodes the result as `output` describes, keeping the
+    /// alpha channel, colour profile and EXIF metadata of the source image. Returns the
+    /// watermarked image and the soft binding identifying the watermark.
+    pub(crate) fn watermark_encoded(
+        &self,
+        image_bytes: &[u8],
+        strength: f32,
+        watermark: Option<String>,
+        output: &OutputOptions,
+    ) -> Result<(EncodedImage, SoftBinding)> {
+        check_strength(strength)?;
+        let source_format = image::guess_format(image_bytes)?;
+        let image = image::load_from_memory_with_format(image_bytes, source_format)?;
+        let format = output
+            .format
+            .or_else(|| {
+                OUTPUT_FORMATS
+                    .contains(&source_format)
+                    .then_some(source_format)
+            })
+            .unwrap_or(ImageFormat::Png);
+        // Trustmark works on RGB, so the alpha channel is put back afterwards.
+        let alpha =
+            (image.color().has_alpha() && format != ImageFormat::Jpeg).then(|| image.to_rgba8());
+        let watermark = watermark.unwrap_or_else(|| gen_watermark(self.version));
+        let watermarked_image = self
+            .trustmark
+            .lock()?
+            .encode(watermark.clone(), image, strength)
+            .map_err(Error::Watermark)?;
+
+        let watermarked_image = match alpha {
+            Some(alpha) => {
+                let mut rgba = watermarked_image.to_rgba8();
+                for (pixel, source) in rgba.pixels_mut().zip(alpha.pixels()) {
+                    pixel[3] = source[3];
+                }
+                DynamicImage::ImageRgba8(rgba)
+            }
+            None => DynamicImage::ImageRgb8(watermarked_image.to_rgb8()),
+        };
+        let mut encoded = Cursor::new(Vec::new());
+        match format {
+            ImageFormat::Jpeg => watermarked_image
+                .write_with_encoder(JpegEncoder::new_with_quality(&mut encoded, output.quality))?,
+            _ => watermarked_image.write_to(&mut encoded, format)?,
+        }
+        let bytes = copy_metadata(image_bytes, encoded.into_inner())?;
+
+        Ok((
+            EncodedImage {
+                bytes,
+                width: watermarked_image.width(),
+                height: watermarked_image.height(),
+                format,
+            },
+            self.soft_binding(watermark),
+        ))
+    }
+
+    /// Decodes the watermark of a whole encoded image. Failures other than a corrupt or missing
+    /// watermark, such as a model error, are returned.
+    pub(crate) fn decode_soft_binding(&self, image_bytes: &[u8]) -> Result<DecodedWatermark> {
+        let image = image::load_from_memory(image_bytes)?;
+        Ok(match self.decoder.decode(&image)? {
+            PayloadCheck::Corrected(payload) => {
+                DecodedWatermark::Decoded(self.soft_binding(payload.watermark))
+            }
+            PayloadCheck::Uncorrectable { .. } => DecodedWatermark::Corrupt,
+            PayloadCheck::Unrecognized => DecodedWatermark::Absent,
+        })
+    }
+
+    fn soft_binding(&self, watermark: String) -> SoftBinding {
+        SoftBinding {
+            alg: format!("com.adobe.trustmark.{}", self.variant),
+            value: watermark,
+        }
+    }
+
     pub fn encode(mut cx: FunctionContext) -> JsResult<JsPromise> {
         let this = cx.this::<JsBox<Self>>()?;
-        let trustmark = this.trustmark.clone();
-        let version = this.version;
+        let this = NeonTrustmark::clone(&this);
 
         let image_bytes = cx.argument::<JsBuffer>(0)?.as_slice(&cx).to_vec();
         let strength = cx.argument::<JsNumber>(1)?.value(&mut cx) as f32;
@@ -132,47 +325,389 @@ impl NeonTrustmark {
                 .ok()
                 .map(|js_string| js_string.value(&mut cx))
         });
+        let abort = Abort::from_js(&mut cx, 3)?;
+        let output = OutputOptions::from_js(&mut cx, 4)?;
 
         let promise = cx
             .task(move || {
-                let trustmark = trustmark.lock()?;
-                let image = image::load_from_memory(&image_bytes)?;
-                let watermark = watermark.unwrap_or_else(|| gen_watermark(version));
-                let watermarked_image = trustmark
-                    .encode(watermark, image, strength)
-                    .map_err(Error::Watermark)?;
-
-                // Convert to RGB8 format and return raw pixel data
-                let rgb_image = watermarked_image.to_rgb8();
-                let raw_pixel_data = rgb_image.into_raw();
-                Ok(raw_pixel_data)
+                abort.check()?;
+                let (image, _) =
+                    this.watermark_encoded(&image_bytes, strength, watermark, &output)?;
+                abort.check()?;
+                Ok(image)
             })
-            .promise(move |mut cx, result: Result<Vec<u8>>| match result {
-                Ok(raw_pixel_data) => JsBuffer::from_slice(&mut cx, &raw_pixel_data),
+            .promise(move |mut cx, result: Result<EncodedImage>| match result {
+                Ok(image) => {
+                    let result = cx.empty_object();
+                    let bytes = JsBuffer::from_slice(&mut cx, &image.bytes)?;
+                    result.set(&mut cx, "image", bytes)?;
+                    let width = cx.number(image.width);
+                    result.set(&mut cx, "width", width)?;
+                    let height = cx.number(image.height);
+                    result.set(&mut cx, "height", height)?;
+                    let format = cx.string(format_name(image.format));
+                    result.set(&mut cx, "format", format)?;
+                    Ok(result)
+                }
                 Err(err) => as_js_error(&mut cx, err).and_then(|err| cx.throw(err)),
             });
         Ok(promise)
     }
 
+    /// Decodes the watermark of an image from each candidate crop and scale of it, resolving to
+    /// the payload most candidates agree on. Finding no watermark is not an error.
     pub fn decode(mut cx: FunctionContext) -> JsResult<JsPromise> {
         let this = cx.this::<JsBox<Self>>()?;
-        let trustmark = this.trustmark.clone();
+        let this = NeonTrustmark::clone(&this);
 
         let image_bytes = cx.argument::<JsBuffer>(0)?.as_slice(&cx).to_vec();
+        let abort = Abort::from_js(&mut cx, 1)?;
+        let candidates = DecodeCandidate::from_js(&mut cx, 2)?;
 
         let promise = cx
             .task(move || {
-                let trustmark = trustmark.lock()?;
                 let image = image::load_from_memory(&image_bytes)?;
-                let watermark = trustmark.decode(image)?;
-                Ok(watermark)
+                let mut results = Vec::with_capacity(candidates.len());
+                for (index, candidate) in candidates.iter().enumerate() {
+                    let region = candidate.apply(&image, index)?;
+                    abort.check()?;
+                    results.push(CandidateResult::from(this.decoder.decode(&region)?));
+                }
+                abort.check()?;
+                Ok(this.decode_result(results))
             })
-            .promise(move |mut cx, result: Result<String>| match result {
-                Ok(watermark) => Ok(cx.string(watermark)),
+            .promise(move |mut cx, result: Result<DecodeResult>| match result {
+                Ok(result) => neon_serde4::to_value(&mut cx, &result)
+                    .or_else(|err| cx.throw_error(err.to_string())),
                 Err(err) => as_js_error(&mut cx, err).and_then(|err| cx.throw(err)),
             });
         Ok(promise)
     }
+
+    /// Combines the results of every candidate, reporting the payload decoded most often, and
+    /// the first of those tied. When no candidate decoded a payload, the watermark is corrupt if
+    /// any candidate found a corrupt one.
+    fn decode_result(&self, candidates: Vec<CandidateResult>) -> DecodeResult {
+        let mut best: Option<(&CandidateResult, usize)> = None;
+        for candidate in candidates.iter().filter(|c| c.watermark.is_some()) {
+            let count = candidates
+                .iter()
+                .filter(|c| c.watermark == candidate.watermark)
+                .count();
+            if best.is_none_or(|(_, best_count)| count > best_count) {
+                best = Some((candidate, count));
+            }
+        }
+        let reported = best.map(|(candidate, _)| candidate).or_else(|| {
+            candidates
+                .iter()
+                .find(|c| c.status == DecodeStatus::Corrupt)
+        });
+
+        DecodeResult {
+            status: reported.map_or(DecodeStatus::Absent, |c| c.status),
+            detected: best.is_some(),
+            watermark: reported.and_then(|c| c.watermark.clone()),
+            variant: self.variant.to_string(),
+            version: reported.and_then(|c| c.version.clone()),
+            corrected_bits: reported.and_then(|c| c.corrected_bits),
+            candidates,
+        }
+    }
+}
+
+/// Whether a payload was decoded, and if not, whether it was corrupt or missing.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum DecodeStatus {
+    Decoded,
+    /// The payload names a schema version but has more bit flips than its BCH code corrects.
+    Corrupt,
+    /// The payload names no schema version and no BCH code corrects it.
+    Absent,
+}
+
+/// The result of `decode`, serialized to JS as is.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodeResult {
+    status: DecodeStatus,
+    /// Whether any candidate decoded a watermark.
+    detected: bool,
+    /// The payload bitstring most candidates decoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    watermark: Option<String>,
+    /// The Trustmark variant the image was decoded with.
+    variant: String,
+    /// The schema version of the decoded payload, or the one a corrupt payload names.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// Bit flips the BCH code corrected in the first candidate that decoded the payload.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corrected_bits: Option<u32>,
+    candidates: Vec<CandidateResult>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CandidateResult {
+    status: DecodeStatus,
+    detected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    watermark: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corrected_bits: Option<u32>,
+}
+
+impl From<PayloadCheck> for CandidateResult {
+    fn from(check: PayloadCheck) -> Self {
+        let absent = Self {
+            status: DecodeStatus::Absent,
+            detected: false,
+            watermark: None,
+            version: None,
+            corrected_bits: None,
+        };
+        match check {
+            PayloadCheck::Corrected(CorrectedPayload {
+                watermark,
+                version,
+                corrected_bits,
+            }) => Self {
+                status: DecodeStatus::Decoded,
+                detected: true,
+                watermark: Some(watermark),
+                version: Some(version.to_string()),
+                corrected_bits: Some(corrected_bits),
+            },
+            PayloadCheck::Uncorrectable { version } => Self {
+                status: DecodeStatus::Corrupt,
+                version: Some(version.to_string()),
+                ..absent
+            },
+            PayloadCheck::Unrecognized => absent,
+        }
+    }
+}
+
+/// A region of the image, and a scale applied to it, to decode a watermark from.
+struct DecodeCandidate {
+    /// `x`, `y`, `width` and `height` in pixels, or the whole image.
+    crop: Option<[u32; 4]>,
+    scale: f64,
+}
+
+impl DecodeCandidate {
+    /// Parses `{ candidates?: [{ crop?: { x, y, width, height }, scale? }] }`, defaulting to the
+    /// whole image at its own scale.
+    fn from_js(cx: &mut FunctionContext, arg_index: usize) -> NeonResult<Vec<Self>> {
+        let whole_image = || {
+            vec![Self {
+                crop: None,
+                scale: 1.0,
+            }]
+        };
+        let Some(obj) = cx
+            .argument_opt(arg_index)
+            .and_then(|value| value.downcast::<JsObject, _>(cx).ok())
+        else {
+            return Ok(whole_image());
+        };
+        let Some(candidates) = obj.get_opt::<JsArray, _, _>(cx, "candidates")? else {
+            return Ok(whole_image());
+        };
+        let candidates = candidates.to_vec(cx)?;
+        if candidates.is_empty() {
+            return cx.throw_error("Watermark configuration error: candidates must not be empty");
+        }
+        candidates
+            .into_iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let candidate = candidate.downcast_or_throw::<JsObject, _>(cx)?;
+                let crop = match candidate.get_opt::<JsObject, _, _>(cx, "crop")? {
+                    Some(crop) => {
+                        let mut crop_value = |name: &str| -> NeonResult<u32> {
+                            let value = crop.get::<JsNumber, _, _>(cx, name)?.value(cx);
+                            if value < 0.0 || value.fract() != 0.0 || value > f64::from(u32::MAX) {
+                                return cx.throw_error(format!(
+                                    "Watermark configuration error: candidate {index} crop {name} must be a whole number of pixels"
+                                ));
+                            }
+                            Ok(value as u32)
+                        };
+                        Some([
+                            crop_value("x")?,
+                            crop_value("y")?,
+                            crop_value("width")?,
+                            crop_value("height")?,
+                        ])
+                    }
+                    None => None,
+                };
+                let scale = match candidate.get_opt::<JsNumber, _, _>(cx, "scale")? {
+                    Some(scale) => scale.value(cx),
+                    None => 1.0,
+                };
+                if !(scale.is_finite() && scale > 0.0) {
+                    return cx.throw_error(format!(
+                        "Watermark configuration error: candidate {index} scale must be positive"
+                    ));
+                }
+                Ok(Self { crop, scale })
+            })
+            .collect()
+    }
+
+    /// Returns the region of `image` to decode.
+    fn apply(&self, image: &DynamicImage, index: usize) -> Result<DynamicImage> {
+        let region = match self.crop {
+            Some([x, y, width, height]) => {
+                let fits = |start: u32, len: u32, max: u32| {
+                    len > 0 && start.checked_add(len).is_some_and(|end| end <= max)
+                };
+                if !fits(x, width, image.width()) || !fits(y, height, image.height()) {
+                    return Err(Error::WatermarkConfiguration(format!(
+                        "candidate {index} crop does not fit in the {}x{} image",
+                        image.width(),
+                        image.height()
+                    )));
+                }
+                image.crop_imm(x, y, width, height)
+            }
+            None => image.clone(),
+        };
+        if self.scale == 1.0 {
+            return Ok(region);
+        }
+        let width = (f64::from(region.width()) * self.scale).round().max(1.0) as u32;
+        let height = (f64::from(region.height()) * self.scale).round().max(1.0) as u32;
+        Ok(region.resize_exact(width, height, FilterType::Lanczos3))
+    }
+}
+
+/// The formats a watermarked image can be encoded in.
+const OUTPUT_FORMATS: [ImageFormat; 3] = [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP];
+
+/// JPEG quality used when none is given.
+const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+/// How a watermarked image is encoded.
+pub(crate) struct OutputOptions {
+    /// One of `OUTPUT_FORMATS`, or the source image's format when possible.
+    pub format: Option<ImageFormat>,
+    /// JPEG quality, from 1 to 100.
+    pub quality: u8,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            format: None,
+            quality: DEFAULT_JPEG_QUALITY,
+        }
+    }
+}
+
+impl OutputOptions {
+    /// Parses `{ format?, quality? }`.
+    fn from_js(cx: &mut FunctionContext, arg_index: usize) -> NeonResult<Self> {
+        let Some(obj) = cx
+            .argument_opt(arg_index)
+            .and_then(|value| value.downcast::<JsObject, _>(cx).ok())
+        else {
+            return Ok(Self::default());
+        };
+        let format = match obj.get_opt::<JsString, _, _>(cx, "format")? {
+            Some(format) => {
+                let format = format.value(cx);
+                match OUTPUT_FORMATS
+                    .into_iter()
+                    .find(|f| format_name(*f) == format)
+                {
+                    Some(format) => Some(format),
+                    None => {
+                        return cx.throw_error(format!(
+                            "Watermark configuration error: unsupported output format {format}"
+                        ))
+                    }
+                }
+            }
+            None => None,
+        };
+        let quality = match obj.get_opt::<JsNumber, _, _>(cx, "quality")? {
+            Some(quality) => {
+                let quality = quality.value(cx);
+                if !(1.0..=100.0).contains(&quality) {
+                    return cx.throw_error(
+                        "Watermark configuration error: quality must be between 1 and 100",
+                    );
+                }
+                quality as u8
+            }
+            None => DEFAULT_JPEG_QUALITY,
+        };
+        Ok(Self { format, quality })
+    }
+
+    /// Encodes in the format of an asset's MIME type, which must be one of `OUTPUT_FORMATS`.
+    pub(crate) fn for_mime_type(mime_type: &str) -> Result<Self> {
+        match ImageFormat::from_mime_type(mime_type).filter(|f| OUTPUT_FORMATS.contains(f)) {
+            Some(format) => Ok(Self {
+                format: Some(format),
+                ..Self::default()
+            }),
+            None => Err(Error::WatermarkConfiguration(format!(
+                "cannot watermark {mime_type} assets"
+            ))),
+        }
+    }
+}
+
+/// A watermarked image, encoded.
+pub(crate) struct EncodedImage {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageFormat,
+}
+
+fn format_name(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Jpeg => "jpeg",
+        ImageFormat::WebP => "webp",
+        _ => "png",
+    }
+}
+
+/// Copies the colour profile and EXIF metadata of `source` into the `encoded` image, which the
+/// `image` crate writes without them.
+fn copy_metadata(source: &[u8], encoded: Vec<u8>) -> Result<Vec<u8>> {
+    // The source was decoded already, so one img-parts cannot parse only loses its metadata.
+    let Some(source) = DynImage::from_bytes(Bytes::copy_from_slice(source))
+        .ok()
+        .flatten()
+    else {
+        return Ok(encoded);
+    };
+    let (icc_profile, exif) = (source.icc_profile(), source.exif());
+    if icc_profile.is_none() && exif.is_none() {
+        return Ok(encoded);
+    }
+    let Some(mut image) =
+        DynImage::from_bytes(encoded.into()).map_err(|err| Error::Asset(err.to_string()))?
+    else {
+        return Err(Error::Asset(
+            "the watermarked image could not be parsed".to_string(),
+        ));
+    };
+    image.set_icc_profile(icc_profile);
+    image.set_exif(exif);
+    let mut bytes = Vec::new();
+    image.encoder().write_to(&mut bytes)?;
+    Ok(bytes)
 }
 
 fn check_strength(strength: f32) -> Result<()> {
@@ -190,9 +725,168 @@ const MIN_MODEL_SIZE: usize = 10 * 1024 * 1024;
 /// Maximum number of download attempts per model file.
 const MAX_RETRIES: u32 = 3;
 
+/// SHA-256 digests of the published models of every variant, in `sha256sum` format. Regenerate
+/// it by running `sha256sum *.onnx` in a directory holding the models.
+const PINNED_CHECKSUMS: &str = include_str!("trustmark_models.sha256");
+
+/// Where a Trustmark's models were provisioned. Models passed in memory are written to a private
+/// temporary directory, since Trustmark only loads models from files.
+enum ModelDir {
+    Configured(PathBuf),
+    Temporary(PathBuf),
+}
+
+impl ModelDir {
+    fn path(&self) -> &Path {
+        match self {
+            ModelDir::Configured(path) | ModelDir::Temporary(path) => path,
+        }
+    }
+
+    /// Removes a temporary directory, reporting the failure a drop ignores.
+    fn remove(self) -> Result<()> {
+        if let ModelDir::Temporary(path) = &self {
+            std::fs::remove_dir_all(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ModelDir {
+    fn drop(&mut self) {
+        if let ModelDir::Temporary(path) = self {
+            let _ = std::fs::remove_dir_all(path);
+        }
+    }
+}
+
+/// Loads the variant's models. A temporary directory holding models passed in memory is removed
+/// as soon as they are loaded, or fail to load.
+fn load_models(config: &WatermarkConfig) -> Result<(Trustmark, PayloadDecoder)> {
+    let model_dir = provision_models(config)?;
+    let [_, decoder_filename] = model_filenames(config.variant);
+    let trustmark = Trustmark::new(model_dir.path(), config.variant, config.version)?;
+    let decoder = PayloadDecoder::new(&model_dir.path().join(decoder_filename), config.variant)?;
+    model_dir.remove()?;
+    Ok((trustmark, decoder))
+}
+
+/// Makes the variant's models available to load, verifying them against their expected digests.
+///
+/// Models passed in memory are used as is. Otherwise missing models are downloaded to the model
+/// directory, unless the configuration is offline, in which case they are reported as missing.
+fn provision_models(config: &WatermarkConfig) -> Result<ModelDir> {
+    let filenames = model_filenames(config.variant);
+    let [encoder, decoder] = filenames
+        .each_ref()
+        .map(|filename| expected_checksum(config, filename));
+    let checksums = ModelPair {
+        encoder: encoder?,
+        decoder: decoder?,
+    };
+
+    if let Some(models) = &config.models {
+        for ((filename, bytes), expected) in
+            filenames.iter().zip(models.iter()).zip(checksums.iter())
+        {
+            verify_checksum(filename, bytes, expected)?;
+        }
+        let dir = std::env::temp_dir().join(format!(
+            "c2pa-trustmark-{}-{:016x}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(&dir)?;
+        let model_dir = ModelDir::Temporary(dir);
+        for (filename, bytes) in filenames.iter().zip(models.iter()) {
+            std::fs::write(model_dir.path().join(filename), bytes)?;
+        }
+        return Ok(model_dir);
+    }
+
+    let dir = &config.model_path;
+    if config.offline {
+        let missing: Vec<&str> = filenames
+            .iter()
+            .filter(|filename| !dir.join(filename).exists())
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(Error::ModelDownload(format!(
+                "offline mode does not download models, and {} is missing from {dir:?}; copy the models there or pass them as `models`",
+                missing.join(", ")
+            )));
+        }
+    } else {
+        fetch_model(config.variant, dir, &checksums)?;
+    }
+
+    for (filename, expected) in filenames.iter().zip(checksums.iter()) {
+        let path = dir.join(filename);
+        let bytes = std::fs::read(&path)
+            .map_err(|e| Error::ModelDownload(format!("Failed to read model {path:?}: {e}")))?;
+        verify_checksum(filename, &bytes, expected)?;
+    }
+    Ok(ModelDir::Configured(dir.clone()))
+}
+
+/// Returns the encoder and decoder model filenames of a variant.
+fn model_filenames(variant: Variant) -> [String; 2] {
+    // Construct filenames manually since encoder_filename/decoder_filename are private
+    [
+        format!("encoder_{variant}.onnx"),
+        format!("decoder_{variant}.onnx"),
+    ]
+}
+
+/// Returns the digest a model file must have: the one given in the configuration's
+/// `checksums`, or else the one pinned in the crate.
+fn expected_checksum(config: &WatermarkConfig, filename: &str) -> Result<String> {
+    if let Some(checksums) = &config.checksums {
+        return Ok(pinned_checksum(checksums, filename).to_owned());
+    }
+    PINNED_CHECKSUMS
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .find(|(_, name)| name.trim_start_matches([' ', '*']) == filename)
+        .map(|(digest, _)| digest.to_ascii_lowercase())
+        .ok_or_else(|| {
+            Error::WatermarkConfiguration(format!(
+                "no SHA-256 digest is pinned for {filename}; pass it in checksums.{}",
+                config.variant
+            ))
+        })
+}
+
+/// Checks `bytes` against the `expected` digest of `filename`.
+fn verify_checksum(filename: &str, bytes: &[u8], expected: &str) -> Result<()> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if actual != expected {
+        return Err(Error::ModelDownload(format!(
+            "{filename} has SHA-256 {actual}, which does not match the pinned {expected}"
+        )));
+    }
+    Ok(())
+}
+
+fn pinned_checksum<'a>(checksums: &'a ModelPair<String>, filename: &str) -> &'a str {
+    if filename.starts_with("encoder_") {
+        &checksums.encoder
+    } else {
+        &checksums.decoder
+    }
+}
+
 // Taken from Trustmark xtask.
 // Will not overwrite models if they already exist.
-pub fn fetch_model(variant: Variant, dir_path: &std::path::Path) -> Result<PathBuf> {
+pub fn fetch_model(
+    variant: Variant,
+    dir_path: &std::path::Path,
+    checksums: &ModelPair<String>,
+) -> Result<PathBuf> {
     let rt = runtime();
     let client = Client::builder()
         .timeout(Duration::from_secs(120))
@@ -206,11 +900,7 @@ pub fn fetch_model(variant: Variant, dir_path: &std::path::Path) -> Result<PathB
 
     let root = "https://cai-watermark.adobe.net/watermarking/trustmark-models";
 
-    // Construct filenames manually since encoder_filename/decoder_filename are private
-    let encoder_filename = format!("encoder_{variant}.onnx");
-    let decoder_filename = format!("decoder_{variant}.onnx");
-
-    for filename in [encoder_filename, decoder_filename] {
+    for filename in model_filenames(variant) {
         let model_path = dir_path.join(&filename);
         if model_path.exists() {
             continue;
@@ -228,6 +918,14 @@ pub fn fetch_model(variant: Variant, dir_path: &std::path::Path) -> Result<PathB
                         )));
                         continue;
                     }
+                    let expected = pinned_checksum(checksums, &filename);
+                    let actual = format!("{:x}", Sha256::digest(&model_bytes));
+                    if actual != expected {
+                        last_err = Some(Error::ModelDownload(format!(
+                            "Downloaded model {filename} has SHA-256 {actual}, which does not match the pinned {expected}"
+                        )));
+                        continue;
+                    }
 
                     // Write to a temporary file first, then rename for atomicity.
                     let tmp_path = dir_path.join(format!("{filename}.tmp"));
@@ -0,0 +1,156 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::fmt;
+use std::sync::Arc;
+
+use c2pa::{Signer, SigningAlg};
+use neon::prelude::*;
+use neon::types::buffer::TypedArray;
+
+use crate::stream::await_js;
+
+/// The space reserved for a response returned by an OCSP callback. The response isn't known
+/// until signing, so this leaves room for a typical CA response (1.5–3 KB) with headroom.
+pub(crate) const CALLBACK_RESERVE_SIZE: usize = 8 * 1024;
+
+/// Where a signer gets the OCSP response it staples to the manifests it signs, so validators
+/// can check the certificate's revocation status offline.
+#[derive(Clone)]
+pub enum OcspSource {
+    /// A DER OCSP response fetched ahead of time.
+    Response(Vec<u8>),
+    /// A JS `() => Buffer | undefined` callback, sync or async, called each time an asset is
+    /// signed.
+    Callback {
+        channel: Channel,
+        callback: Arc<Root<JsFunction>>,
+    },
+}
+
+impl fmt::Debug for OcspSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcspSource::Response(response) => f
+                .debug_tuple("Response")
+                .field(&format_args!("{} bytes", response.len()))
+                .finish(),
+            OcspSource::Callback { .. } => f.write_str("Callback"),
+        }
+    }
+}
+
+impl OcspSource {
+    /// Parses an `ocspResponse` option, either a Buffer or an async callback. Returns `None` when
+    /// the option is not set.
+    pub(crate) fn from_js<'a, C: Context<'a>>(
+        cx: &mut C,
+        value: Handle<'a, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        if value.is_a::<JsNull, _>(cx) || value.is_a::<JsUndefined, _>(cx) {
+            return Ok(None);
+        }
+        if let Ok(callback) = value.downcast::<JsFunction, _>(cx) {
+            return Ok(Some(OcspSource::Callback {
+                channel: cx.channel(),
+                callback: Arc::new(callback.root(cx)),
+            }));
+        }
+        let response = value.downcast_or_throw::<JsBuffer, _>(cx)?;
+        Ok(Some(OcspSource::Response(response.as_slice(cx).to_vec())))
+    }
+
+    /// The extra signature space needed to staple the response: its length when known up front,
+    /// otherwise `CALLBACK_RESERVE_SIZE`.
+    pub(crate) fn reserve_size(&self) -> usize {
+        match self {
+            OcspSource::Response(response) => response.len(),
+            OcspSource::Callback { .. } => CALLBACK_RESERVE_SIZE,
+        }
+    }
+
+    /// Returns the response to staple. A callback that throws, rejects or returns anything but a
+    /// Buffer leaves the manifest without one, as it would be without stapling.
+    pub(crate) async fn fetch(&self) -> Option<Vec<u8>> {
+        let (channel, callback) = match self {
+            OcspSource::Response(response) => return Some(response.clone()),
+            OcspSource::Callback { channel, callback } => (channel, Arc::clone(callback)),
+        };
+
+        await_js(
+            channel,
+            move |cx| callback.to_inner(cx).call_with(cx).apply(cx),
+            |mut cx, result| {
+                Ok(result.ok().and_then(|value| {
+                    value
+                        .downcast::<JsBuffer, _>(&mut cx)
+                        .ok()
+                        .map(|buffer| buffer.as_slice(&cx).to_vec())
+                }))
+            },
+        )
+        .await
+        .ok()?
+    }
+}
+
+/// Wraps a local signer to staple a fixed OCSP response. Like `TsaSigner`, it does not expose the
+/// inner signer's raw signer, so that c2pa-rs asks this signer for the response.
+pub(crate) struct StapledSigner {
+    signer: Box<dyn Signer>,
+    response: Vec<u8>,
+}
+
+impl StapledSigner {
+    pub(crate) fn new(signer: Box<dyn Signer>, response: Vec<u8>) -> Self {
+        Self { signer, response }
+    }
+}
+
+impl Signer for StapledSigner {
+    fn sign(&self, data: &[u8]) -> c2pa::Result<Vec<u8>> {
+        self.signer.sign(data)
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.signer.alg()
+    }
+
+    fn certs(&self) -> c2pa::Result<Vec<Vec<u8>>> {
+        self.signer.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.signer.reserve_size() + self.response.len()
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        Some(self.response.clone())
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.signer.time_authority_url()
+    }
+
+    fn timestamp_request_headers(&self) -> Option<Vec<(String, String)>> {
+        self.signer.timestamp_request_headers()
+    }
+
+    fn timestamp_request_body(&self, message: &[u8]) -> c2pa::Result<Vec<u8>> {
+        self.signer.timestamp_request_body(message)
+    }
+
+    fn send_timestamp_request(&self, message: &[u8]) -> Option<c2pa::Result<Vec<u8>>> {
+        self.signer.send_timestamp_request(message)
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::{Read, Result as IoResult, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+
+use neon::prelude::*;
+
+use crate::asset::{NeonReadStreamTrait, NeonWriteStreamTrait};
+
+/// Bytes processed between two progress reports within the same phase, so large assets do not
+/// cross into JS for every read.
+const REPORT_INTERVAL: u64 = 256 * 1024;
+
+/// The stage a signing or reading operation is in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Phase {
+    /// Reading the source asset, which is hashed as it is read.
+    Hashing,
+    /// Waiting on the signer.
+    Signing,
+    /// Waiting on the time stamp authority.
+    Timestamping,
+    /// Writing the signed asset.
+    Writing,
+}
+
+impl Phase {
+    fn as_str(self) -> &'static str {
+        match self {
+            Phase::Hashing => "hashing",
+            Phase::Signing => "signing",
+            Phase::Timestamping => "timestamping",
+            Phase::Writing => "writing",
+        }
+    }
+}
+
+struct Reporter {
+    channel: Channel,
+    callback: Arc<Root<JsFunction>>,
+    // The byte count last sent to JS for each phase, indexed by `Phase as usize`. The source is
+    // read while the destination is written, so each phase is throttled on its own.
+    last: Mutex<[Option<u64>; 4]>,
+}
+
+impl Reporter {
+    /// Reports `bytes` if the phase has just started or has advanced far enough since the last
+    /// report. A `last_report` is sent whenever the bytes have advanced at all.
+    fn report(&self, phase: Phase, bytes: u64, total: Option<u64>, last_report: bool) {
+        {
+            let mut last = self.last.lock().unwrap_or_else(|err| err.into_inner());
+            let last = &mut last[phase as usize];
+            let due = match *last {
+                None => true,
+                Some(last) => {
+                    bytes > last
+                        && (last_report || bytes >= last + REPORT_INTERVAL || Some(bytes) == total)
+                }
+            };
+            if !due {
+                return;
+            }
+            *last = Some(bytes);
+        }
+
+        let callback = Arc::clone(&self.callback);
+        // Progress is informational, so a report that cannot be delivered is dropped, and an
+        // exception thrown by the callback is ignored rather than left uncaught.
+        let _ = self.channel.try_send(move |mut cx| {
+            let _ = cx.try_catch(|cx| {
+                let event = cx.empty_object();
+                let js_phase = cx.string(phase.as_str());
+                event.set(cx, "phase", js_phase)?;
+                let js_bytes = cx.number(bytes as f64);
+                event.set(cx, "bytes", js_bytes)?;
+                if let Some(total) = total {
+                    let js_total = cx.number(total as f64);
+                    event.set(cx, "totalBytes", js_total)?;
+                }
+                callback.to_inner(cx).call_with(cx).arg(event).exec(cx)
+            });
+            Ok(())
+        });
+    }
+}
+
+/// Reports the progress of an operation to an optional JS `onProgress` callback, which receives
+/// `{ phase, bytes, totalBytes? }`.
+#[derive(Clone, Default)]
+pub(crate) struct Progress {
+    reporter: Option<Arc<Reporter>>,
+}
+
+impl Progress {
+    /// Parses an optional progress callback argument.
+    pub(crate) fn from_js(cx: &mut FunctionContext, arg_index: usize) -> NeonResult<Self> {
+        match cx
+            .argument_opt(arg_index)
+            .and_then(|value| value.downcast::<JsFunction, _>(cx).ok())
+        {
+            Some(callback) => Ok(Self::new(cx, callback)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub(crate) fn new(cx: &mut FunctionContext, callback: Handle<JsFunction>) -> Self {
+        Self {
+            reporter: Some(Arc::new(Reporter {
+                channel: cx.channel(),
+                callback: Arc::new(callback.root(cx)),
+                last: Mutex::new([None; 4]),
+            })),
+        }
+    }
+
+    /// Reports the start of a phase that has no byte count, such as signing.
+    pub(crate) fn phase(&self, phase: Phase) {
+        if let Some(reporter) = &self.reporter {
+            reporter.report(phase, 0, None, false);
+        }
+    }
+
+    /// Reports the bytes of the source read while hashing, out of `total` when it is known.
+    pub(crate) fn read_stream(
+        &self,
+        stream: Box<dyn NeonReadStreamTrait>,
+        total: Option<u64>,
+    ) -> Box<dyn NeonReadStreamTrait> {
+        match &self.reporter {
+            Some(reporter) => Box::new(ProgressStream::new(
+                stream,
+                Arc::clone(reporter),
+                Phase::Hashing,
+                total,
+            )),
+            None => stream,
+        }
+    }
+
+    /// Reports the bytes of the destination written.
+    pub(crate) fn write_stream(
+        &self,
+        stream: Box<dyn NeonWriteStreamTrait>,
+    ) -> Box<dyn NeonWriteStreamTrait> {
+        match &self.reporter {
+            Some(reporter) => Box::new(ProgressStream::new(
+                stream,
+                Arc::clone(reporter),
+                Phase::Writing,
+                None,
+            )),
+            None => stream,
+        }
+    }
+}
+
+/// Wraps a stream to report how far into it reads or writes have reached. Seeking back and
+/// re-reading does not count twice, so the reported bytes never exceed the asset size.
+struct ProgressStream<S> {
+    inner: S,
+    reporter: Arc<Reporter>,
+    phase: Phase,
+    total: Option<u64>,
+    position: u64,
+    furthest: u64,
+}
+
+impl<S> ProgressStream<S> {
+    fn new(inner: S, reporter: Arc<Reporter>, phase: Phase, total: Option<u64>) -> Self {
+        Self {
+            inner,
+            reporter,
+            phase,
+            total,
+            position: 0,
+            furthest: 0,
+        }
+    }
+
+    fn advance(&mut self, bytes: usize) {
+        self.position += bytes as u64;
+        if self.position > self.furthest {
+            self.furthest = self.position;
+            self.reporter
+                .report(self.phase, self.furthest, self.total, false);
+        }
+    }
+}
+
+impl<S: Read> Read for ProgressStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        if self.phase == Phase::Hashing {
+            self.advance(bytes_read);
+        } else {
+            self.position += bytes_read as u64;
+        }
+        Ok(bytes_read)
+    }
+}
+
+impl<S: Write> Write for ProgressStream<S> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let bytes_written = self.inner.write(buf)?;
+        self.advance(bytes_written);
+        Ok(bytes_written)
+    }
+
+    // The size of the output is not known up front, so the final byte count is reported once
+    // the output is flushed.
+    fn flush(&mut self) -> IoResult<()> {
+        self.inner.flush()?;
+        self.reporter
+            .report(self.phase, self.furthest, self.total, true);
+        Ok(())
+    }
+}
+
+impl<S: Seek> Seek for ProgressStream<S> {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        self.position = self.inner.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+impl NeonReadStreamTrait for ProgressStream<Box<dyn NeonReadStreamTrait>> {}
+impl NeonWriteStreamTrait for ProgressStream<Box<dyn NeonWriteStreamTrait>> {}
@@ -0,0 +1,206 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::stream::await_js;
+use async_trait::async_trait;
+use c2pa::http::http::{Method, Request, Response};
+use c2pa::http::{AsyncHttpResolver, HttpResolverError};
+use neon::prelude::*;
+use neon::types::buffer::TypedArray;
+use reqwest::{redirect, Client, Url};
+use std::io::{Cursor, Read};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default time allowed for fetching a remote manifest, including any redirects.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default cap on the size of a remote manifest store.
+const DEFAULT_MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// How a reader follows a remote manifest reference (`dcterms:provenance` or a `Link` header
+/// URL). Installed as the reader context's HTTP resolver, so c2pa-rs still does the fetching but
+/// every request it makes is held to these limits instead of going out unrestricted.
+pub(crate) struct RemoteManifestOptions {
+    timeout: Duration,
+    max_bytes: usize,
+    /// Hosts a request may be sent to. Empty allows any host.
+    allowed_hosts: Vec<String>,
+    /// A JS `resolve(url)` callback used in place of the built-in HTTP fetch for GET requests.
+    resolver: Option<(Channel, Arc<Root<JsFunction>>)>,
+}
+
+impl RemoteManifestOptions {
+    /// Parses the `remoteManifest` reader option:
+    /// `{ timeoutMs?, maxBytes?, allowedHosts?, resolve? }`.
+    pub(crate) fn from_js(cx: &mut FunctionContext, obj: Handle<JsObject>) -> NeonResult<Self> {
+        let timeout = obj
+            .get_opt::<JsNumber, _, _>(cx, "timeoutMs")?
+            .map(|ms| Duration::from_millis(ms.value(cx) as u64))
+            .unwrap_or(DEFAULT_TIMEOUT);
+        let max_bytes = obj
+            .get_opt::<JsNumber, _, _>(cx, "maxBytes")?
+            .map(|max_bytes| max_bytes.value(cx) as usize)
+            .unwrap_or(DEFAULT_MAX_BYTES);
+        let allowed_hosts = match obj.get_opt::<JsArray, _, _>(cx, "allowedHosts")? {
+            Some(hosts) => hosts
+                .to_vec(cx)?
+                .into_iter()
+                .map(|host| {
+                    host.downcast_or_throw::<JsString, _>(cx)
+                        .map(|host| host.value(cx).to_ascii_lowercase())
+                })
+                .collect::<NeonResult<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        let resolver = obj
+            .get_opt::<JsFunction, _, _>(cx, "resolve")?
+            .map(|resolve| (cx.channel(), Arc::new(resolve.root(cx))));
+
+        Ok(Self {
+            timeout,
+            max_bytes,
+            allowed_hosts,
+            resolver,
+        })
+    }
+
+    fn is_allowed(&self, url: &Url) -> bool {
+        matches!(url.scheme(), "http" | "https")
+            && (self.allowed_hosts.is_empty()
+                || url.host_str().is_some_and(|host| {
+                    self.allowed_hosts
+                        .iter()
+                        .any(|allowed| allowed.eq_ignore_ascii_case(host))
+                }))
+    }
+
+    async fn fetch(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<Vec<u8>>, HttpResolverError> {
+        // Redirects are held to the same host list as the original URL.
+        let allowed_hosts = self.allowed_hosts.clone();
+        let client = Client::builder()
+            .timeout(self.timeout)
+            .redirect(redirect::Policy::custom(move |attempt| {
+                let allowed = allowed_hosts.is_empty()
+                    || attempt.url().host_str().is_some_and(|host| {
+                        allowed_hosts
+                            .iter()
+                            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+                    });
+                if !allowed {
+                    attempt.error("redirect to a host that is not allowed")
+                } else if attempt.previous().len() >= 10 {
+                    attempt.error("too many redirects")
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .build()
+            .map_err(other)?;
+
+        let request = reqwest::Request::try_from(request).map_err(other)?;
+        let mut response = client.execute(request).await.map_err(other)?;
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_bytes as u64)
+        {
+            return Err(HttpResolverError::ResponseTooLarge);
+        }
+
+        let mut builder = Response::builder().status(response.status());
+        for (name, value) in response.headers() {
+            builder = builder.header(name, value);
+        }
+
+        // The declared length can't be trusted, so the cap is enforced while reading too.
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(other)? {
+            if body.len() + chunk.len() > self.max_bytes {
+                return Err(HttpResolverError::ResponseTooLarge);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(builder.body(body)?)
+    }
+}
+
+#[async_trait]
+impl AsyncHttpResolver for RemoteManifestOptions {
+    async fn http_resolve_async(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<Box<dyn Read>>, HttpResolverError> {
+        let uri = request.uri().to_string();
+        let url = Url::parse(&uri).map_err(other)?;
+        if !self.is_allowed(&url) {
+            return Err(HttpResolverError::UriDisallowed { uri });
+        }
+
+        // The JS resolver is only given the URL, so it answers plain GET requests such as a
+        // remote manifest fetch; anything else goes through the built-in fetch.
+        let response = match &self.resolver {
+            Some((channel, resolve)) if request.method() == Method::GET => {
+                let body = tokio::time::timeout(self.timeout, resolve_js(channel, resolve, url))
+                    .await
+                    .map_err(other)??;
+                if body.len() > self.max_bytes {
+                    return Err(HttpResolverError::ResponseTooLarge);
+                }
+                Response::new(body)
+            }
+            _ => self.fetch(request).await?,
+        };
+        Ok(response.map(|body| Box::new(Cursor::new(body)) as Box<dyn Read>))
+    }
+}
+
+/// Wraps an error of the underlying fetch or of the JS resolver.
+fn other(err: impl std::error::Error + Send + Sync + 'static) -> HttpResolverError {
+    HttpResolverError::Other(Box::new(err))
+}
+
+/// Calls the JS `resolve(url)` callback and waits for the manifest bytes it resolves to.
+async fn resolve_js(
+    channel: &Channel,
+    resolve: &Arc<Root<JsFunction>>,
+    url: Url,
+) -> Result<Vec<u8>, HttpResolverError> {
+    let resolve = Arc::clone(resolve);
+
+    await_js(
+        channel,
+        move |cx| {
+            let url = cx.string(url.as_str());
+            resolve.to_inner(cx).call_with(cx).arg(url).apply(cx)
+        },
+        |mut cx, result| match result {
+            Ok(value) => match value.downcast::<JsTypedArray<u8>, _>(&mut cx) {
+                Ok(bytes) => Ok(Ok(bytes.as_slice(&cx).to_vec())),
+                Err(_) => Ok(Err(
+                    "Remote manifest resolver must resolve to a Buffer or Uint8Array".to_string(),
+                )),
+            },
+            Err(err) => {
+                let message = err.to_string(&mut cx)?.value(&mut cx);
+                Ok(Err(format!("Remote manifest resolver failed: {message}")))
+            }
+        },
+    )
+    .await
+    .map_err(|e| format!("Remote manifest resolver failed: {e}"))
+    .and_then(|result| result)
+    .map_err(|message| HttpResolverError::Other(message.into()))
+}
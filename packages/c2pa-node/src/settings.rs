@@ -0,0 +1,100 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::sync::RwLock;
+
+use c2pa::Context;
+use neon::context::Context as NeonContext;
+use neon::prelude::*;
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+use crate::utils::settings_object;
+
+// Process-wide settings loaded with `loadSettings`. c2pa-rs keeps its own global settings per
+// thread, which would not reach the worker threads readers and builders run on, so they are kept
+// here and layered under the settings passed to each reader, builder and context instead.
+static GLOBAL_SETTINGS: RwLock<Option<Map<String, Value>>> = RwLock::new(None);
+
+/// Returns the settings loaded with `loadSettings`, if any.
+pub(crate) fn global_settings() -> Option<Map<String, Value>> {
+    GLOBAL_SETTINGS
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+}
+
+/// Deep-merges `overrides` into `base`, so nested sections are merged rather than replaced.
+pub(crate) fn merge_settings(base: &mut Map<String, Value>, overrides: Map<String, Value>) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Object(base)), Value::Object(overrides)) => {
+                merge_settings(base, overrides)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn load(settings: &str) -> Result<()> {
+    let mut global = GLOBAL_SETTINGS
+        .write()
+        .unwrap_or_else(|err| err.into_inner());
+
+    let mut merged = global.clone().unwrap_or_default();
+    merge_settings(&mut merged, settings_object(Some(settings))?);
+
+    // Reject settings c2pa-rs would not accept before they affect every later reader and builder.
+    let json = Value::Object(merged.clone()).to_string();
+    Context::new()
+        .with_settings(json.as_str())
+        .map_err(|e| Error::Settings(format!("Invalid settings: {e}")))?;
+
+    *global = Some(merged);
+    Ok(())
+}
+
+fn effective() -> Result<Value> {
+    let mut settings = match serde_json::to_value(c2pa::settings::Settings::default())? {
+        Value::Object(settings) => settings,
+        _ => Map::new(),
+    };
+    if let Some(global) = global_settings() {
+        merge_settings(&mut settings, global);
+    }
+    Ok(Value::Object(settings))
+}
+
+/// Loads JSON or TOML settings for the whole process. They are merged into any settings loaded
+/// before, and apply to every reader, builder and context created afterwards.
+pub fn load_settings(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let settings = cx.argument::<JsString>(0)?.value(&mut cx);
+    load(&settings).or_else(|err| cx.throw_error(err.to_string()))?;
+    Ok(cx.undefined())
+}
+
+/// Discards the settings loaded with `loadSettings`.
+pub fn reset_settings(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    *GLOBAL_SETTINGS
+        .write()
+        .unwrap_or_else(|err| err.into_inner()) = None;
+    Ok(cx.undefined())
+}
+
+/// Returns the c2pa-rs defaults with the loaded settings applied, as a JSON string.
+pub fn get_settings(mut cx: FunctionContext) -> JsResult<JsString> {
+    let settings = effective().or_else(|err| cx.throw_error(err.to_string()))?;
+    Ok(cx.string(settings.to_string()))
+}
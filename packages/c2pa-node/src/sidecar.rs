@@ -0,0 +1,187 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::asset::{Asset, NeonWriteStreamTrait};
+use crate::error::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extension of a manifest store written next to its asset.
+const SIDECAR_EXTENSION: &str = "c2pa";
+
+/// Returns the sidecar manifest store for the asset at `path`, if there is one. Both the
+/// c2pa-rs convention (`image.c2pa`) and the full file name form (`image.jpg.c2pa`) are found.
+pub(crate) fn find_sidecar(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let mut full_name = path.as_os_str().to_owned();
+    full_name.push(format!(".{SIDECAR_EXTENSION}"));
+
+    [
+        path.with_extension(SIDECAR_EXTENSION),
+        PathBuf::from(full_name),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.is_file())
+}
+
+/// A destination that is written to a temporary file next to its target path and only moved
+/// into place once complete. Buffer and stream destinations are written in place.
+struct StagedAsset {
+    asset: Asset,
+    target: Option<String>,
+}
+
+impl StagedAsset {
+    fn new(asset: Asset) -> Self {
+        match asset {
+            Asset::File(path, mime_type) => Self {
+                asset: Asset::File(format!("{path}.partial"), mime_type),
+                target: Some(path),
+            },
+            asset => Self {
+                asset,
+                target: None,
+            },
+        }
+    }
+
+    /// Moves the staged file into place. A file it replaces is kept aside until the commit is
+    /// finalized or rolled back.
+    fn commit(&self) -> Result<()> {
+        if let (Some(staged), Some(target)) = (self.asset.path(), &self.target) {
+            let previous = Self::previous(target);
+            if Path::new(target).is_file() {
+                fs::rename(target, &previous)?;
+            }
+            if let Err(err) = fs::rename(staged, target) {
+                let _ = fs::rename(&previous, target);
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the file replaced by a commit.
+    fn finalize(&self) {
+        if let Some(target) = &self.target {
+            let _ = fs::remove_file(Self::previous(target));
+        }
+    }
+
+    /// Undoes a commit, putting back the file it replaced, if any.
+    fn rollback(&self) {
+        if let Some(target) = &self.target {
+            let previous = Self::previous(target);
+            if Path::new(&previous).exists() {
+                let _ = fs::rename(&previous, target);
+            } else {
+                let _ = fs::remove_file(target);
+            }
+        }
+    }
+
+    fn previous(target: &str) -> String {
+        format!("{target}.previous")
+    }
+
+    fn discard(&self) {
+        if let (Some(staged), Some(_)) = (self.asset.path(), &self.target) {
+            let _ = fs::remove_file(staged);
+        }
+    }
+
+    /// Returns the written bytes of a destination buffer, which JS only sees once they are
+    /// copied back onto the asset object.
+    fn buffer(&self, stream: &mut dyn NeonWriteStreamTrait) -> Result<Option<Vec<u8>>> {
+        if self.asset.name() != "destination_buffer" {
+            return Ok(None);
+        }
+        let mut buffer = Vec::new();
+        stream.rewind()?;
+        stream.read_to_end(&mut buffer)?;
+        Ok(Some(buffer))
+    }
+}
+
+/// The bytes of any buffer destinations of a sidecar sign.
+pub(crate) struct SidecarBuffers {
+    pub asset: Option<Vec<u8>>,
+    pub manifest: Option<Vec<u8>>,
+}
+
+/// The two destinations of a sidecar sign: the asset, written without an embedded manifest, and
+/// the manifest store next to it.
+///
+/// File destinations are staged, and the manifest is moved into place before the asset, so a
+/// signed asset never appears without its manifest. If the asset can't be moved into place, the
+/// manifest is rolled back, so a failed sign leaves neither behind.
+pub(crate) struct SidecarDestination {
+    asset: StagedAsset,
+    manifest: StagedAsset,
+}
+
+impl SidecarDestination {
+    pub(crate) fn new(asset: Asset, manifest: Asset) -> Self {
+        Self {
+            asset: StagedAsset::new(asset),
+            manifest: StagedAsset::new(manifest),
+        }
+    }
+
+    pub(crate) fn asset_stream(&self) -> Result<Box<dyn NeonWriteStreamTrait>> {
+        self.asset.asset.write_stream()
+    }
+
+    /// Writes the manifest store and moves both destinations into place. On failure, any
+    /// staged files are removed.
+    pub(crate) fn finish(
+        &self,
+        mut asset_stream: Box<dyn NeonWriteStreamTrait>,
+        manifest: &[u8],
+    ) -> Result<SidecarBuffers> {
+        let result = (|| -> Result<SidecarBuffers> {
+            asset_stream.flush()?;
+            let mut manifest_stream = self.manifest.asset.write_stream()?;
+            manifest_stream.write_all(manifest)?;
+            manifest_stream.flush()?;
+
+            let buffers = SidecarBuffers {
+                asset: self.asset.buffer(asset_stream.as_mut())?,
+                manifest: self.manifest.buffer(manifest_stream.as_mut())?,
+            };
+            // Close the staged files before moving them.
+            drop(asset_stream);
+            drop(manifest_stream);
+
+            self.manifest.commit()?;
+            if let Err(err) = self.asset.commit() {
+                self.manifest.rollback();
+                return Err(err);
+            }
+            self.manifest.finalize();
+            self.asset.finalize();
+            Ok(buffers)
+        })();
+
+        if result.is_err() {
+            self.discard();
+        }
+        result
+    }
+
+    /// Removes any staged files after a failed sign.
+    pub(crate) fn discard(&self) {
+        self.asset.discard();
+        self.manifest.discard();
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::error::{Error, Result};
+use crate::neon_trustmark::{DecodedWatermark, NeonTrustmark, SoftBinding};
+use crate::stream::await_js;
+use c2pa::Reader;
+use c2pa_types::RecoveredSoftBinding;
+use neon::prelude::*;
+use neon::types::buffer::TypedArray;
+use serde_json::Value as JsonValue;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Extension of the manifest stores kept in a directory repository.
+const MANIFEST_EXTENSION: &str = "c2pa";
+
+/// How a reader recovers the manifest store of an asset that has none, by decoding the asset's
+/// Trustmark watermark and looking the watermark up in a manifest repository.
+pub(crate) struct SoftBindingRecovery {
+    trustmark: NeonTrustmark,
+    repository: ManifestRepository,
+}
+
+/// Where recovered manifest stores are looked up, by soft binding algorithm and value.
+enum ManifestRepository {
+    /// Manifest stores kept as `<directory>/<alg>/<value>.c2pa`.
+    Directory(PathBuf),
+    /// A JS `resolve({ alg, value })` callback resolving to the manifest store, or to `null`
+    /// when the repository has none.
+    Resolver(Channel, Arc<Root<JsFunction>>),
+}
+
+impl SoftBindingRecovery {
+    /// Parses the `softBinding` reader option: `{ trustmark, directory?, resolve? }`, with
+    /// exactly one of `directory` and `resolve`.
+    pub(crate) fn from_js(cx: &mut FunctionContext, obj: Handle<JsObject>) -> NeonResult<Self> {
+        let trustmark = match obj.get_opt::<JsBox<NeonTrustmark>, _, _>(cx, "trustmark")? {
+            Some(trustmark) => NeonTrustmark::clone(&trustmark),
+            None => return cx.throw_type_error("softBinding.trustmark must be a Trustmark"),
+        };
+        let directory = obj
+            .get_opt::<JsString, _, _>(cx, "directory")?
+            .map(|directory| PathBuf::from(directory.value(cx)));
+        let resolve = obj.get_opt::<JsFunction, _, _>(cx, "resolve")?;
+        let repository = match (directory, resolve) {
+            (Some(directory), None) => ManifestRepository::Directory(directory),
+            (None, Some(resolve)) => {
+                ManifestRepository::Resolver(cx.channel(), Arc::new(resolve.root(cx)))
+            }
+            _ => {
+                return cx
+                    .throw_type_error("softBinding needs either a directory or a resolve callback")
+            }
+        };
+
+        Ok(Self {
+            trustmark,
+            repository,
+        })
+    }
+
+    /// Decodes the watermark of `asset` and returns the manifest store it identifies, with the
+    /// soft binding it was found by. Returns `None` when the asset is not an image with a
+    /// watermark, or when the repository has no manifest store for the watermark. A watermark
+    /// too damaged to decode is an error.
+    pub(crate) async fn recover(
+        &self,
+        asset: Vec<u8>,
+    ) -> Result<Option<(Vec<u8>, RecoveredSoftBinding)>> {
+        let trustmark = self.trustmark.clone();
+        let soft_binding =
+            match tokio::task::spawn_blocking(move || trustmark.decode_soft_binding(&asset)).await?
+            {
+                Ok(DecodedWatermark::Decoded(soft_binding)) => soft_binding,
+                Ok(DecodedWatermark::Corrupt) => {
+                    return Err(Error::Reading(
+                        "The asset's watermark is corrupt, so its manifest cannot be recovered"
+                            .to_string(),
+                    ))
+                }
+                Ok(DecodedWatermark::Absent) | Err(Error::Image(_)) => return Ok(None),
+                Err(err) => return Err(err),
+            };
+        let SoftBinding { alg, value } = soft_binding;
+
+        let manifest_data = match &self.repository {
+            ManifestRepository::Directory(directory) => {
+                let path = directory
+                    .join(&alg)
+                    .join(format!("{value}.{MANIFEST_EXTENSION}"));
+                match std::fs::read(path) {
+                    Ok(manifest_data) => Some(manifest_data),
+                    Err(err) if err.kind() == ErrorKind::NotFound => None,
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            ManifestRepository::Resolver(channel, resolve) => {
+                resolve_js(channel, resolve, &alg, &value).await?
+            }
+        };
+        Ok(manifest_data.map(|manifest_data| (manifest_data, RecoveredSoftBinding { alg, value })))
+    }
+}
+
+/// Checks that the active manifest of a recovered manifest store records the soft binding it
+/// was recovered by, so that a repository cannot hand back the manifest of another asset.
+pub(crate) fn check_recovered(reader: &Reader, recovered_by: &RecoveredSoftBinding) -> Result<()> {
+    let store: JsonValue = serde_json::from_str(&reader.json())?;
+    let assertions = store
+        .get("active_manifest")
+        .and_then(JsonValue::as_str)
+        .and_then(|label| store.get("manifests")?.get(label)?.get("assertions"))
+        .and_then(JsonValue::as_array);
+
+    let recorded = assertions
+        .into_iter()
+        .flatten()
+        .filter(|assertion| {
+            assertion
+                .get("label")
+                .and_then(JsonValue::as_str)
+                .is_some_and(|label| label.starts_with(SoftBinding::LABEL))
+        })
+        .filter_map(|assertion| assertion.get("data"))
+        .any(|data| {
+            data.get("alg").and_then(JsonValue::as_str) == Some(recovered_by.alg.as_str())
+                && data
+                    .get("blocks")
+                    .and_then(JsonValue::as_array)
+                    .into_iter()
+                    .flatten()
+                    .any(|block| {
+                        block.get("value").and_then(JsonValue::as_str)
+                            == Some(recovered_by.value.as_str())
+                    })
+        });
+    if !recorded {
+        return Err(Error::Reading(format!(
+            "The recovered manifest has no {} soft binding for watermark {}",
+            recovered_by.alg, recovered_by.value
+        )));
+    }
+    Ok(())
+}
+
+/// Calls the JS `resolve({ alg, value })` callback and waits for the manifest bytes it resolves
+/// to, or `None` when it resolves to `null` or `undefined`. The callback may be sync or async.
+async fn resolve_js(
+    channel: &Channel,
+    resolve: &Arc<Root<JsFunction>>,
+    alg: &str,
+    value: &str,
+) -> Result<Option<Vec<u8>>> {
+    let resolve = Arc::clone(resolve);
+    let (alg, value) = (alg.to_owned(), value.to_owned());
+
+    await_js(
+        channel,
+        move |cx| {
+            let soft_binding = cx.empty_object();
+            let alg = cx.string(alg);
+            soft_binding.set(cx, "alg", alg)?;
+            let value = cx.string(value);
+            soft_binding.set(cx, "value", value)?;
+            resolve
+                .to_inner(cx)
+                .call_with(cx)
+                .arg(soft_binding)
+                .apply(cx)
+        },
+        |mut cx, result| match result {
+            Ok(value) if value.is_a::<JsNull, _>(&mut cx) => Ok(Ok(None)),
+            Ok(value) if value.is_a::<JsUndefined, _>(&mut cx) => Ok(Ok(None)),
+            Ok(value) => match value.downcast::<JsTypedArray<u8>, _>(&mut cx) {
+                Ok(bytes) => Ok(Ok(Some(bytes.as_slice(&cx).to_vec()))),
+                Err(_) => Ok(Err(Error::Reading(
+                    "Soft binding resolver must resolve to a Buffer, Uint8Array or null"
+                        .to_string(),
+                ))),
+            },
+            Err(err) => {
+                let message = err.to_string(&mut cx)?.value(&mut cx);
+                Ok(Err(Error::Reading(format!(
+                    "Soft binding resolver failed: {message}"
+                ))))
+            }
+        },
+    )
+    .await
+    .map_err(|e| Error::Reading(format!("Soft binding resolver failed: {e}")))?
+}
@@ -0,0 +1,377 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::asset::{NeonReadStreamTrait, NeonWriteStreamTrait};
+use neon::prelude::*;
+use neon::types::buffer::TypedArray;
+use neon::types::JsFuture;
+use std::io::{Error as IoError, ErrorKind, Read, Result as IoResult, Seek, SeekFrom, Write};
+use std::sync::{mpsc, Arc};
+use tokio::sync::oneshot;
+
+/// Size of a single request made to a JS `read` callback, and the size at which buffered writes
+/// are flushed to a JS `write` callback. c2pa-rs reads box headers a few bytes at a time, so
+/// fetching in larger chunks avoids crossing the JS boundary for every one of them.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Sends `call` to the JS main thread and blocks until the promise it returns settles.
+///
+/// This must never be called from the JS main thread itself, since it would wait on work that
+/// can only run once it returns. When called from a tokio worker the wait is wrapped in
+/// `block_in_place` so the runtime can move other tasks off this thread in the meantime.
+fn block_on_js<T, F>(channel: &Channel, call: F) -> IoResult<T>
+where
+    T: Send + 'static,
+    F: for<'a> FnOnce(&mut TaskContext<'a>) -> NeonResult<JsFuture<IoResult<T>>> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<Result<JsFuture<IoResult<T>>, String>>();
+
+    channel
+        .try_send(move |mut cx| {
+            // Catch synchronous exceptions (for instance a callback that is not a function) so
+            // they are reported to the waiting reader instead of surfacing as uncaught.
+            let result = cx.try_catch(|cx| call(cx)).map_err(|err| {
+                err.to_string(&mut cx)
+                    .map(|js_string| js_string.value(&mut cx))
+                    .unwrap_or_else(|_| "JS stream callback threw".to_string())
+            });
+            let _ = tx.send(result);
+            Ok(())
+        })
+        .map_err(IoError::other)?;
+
+    let wait = move || -> IoResult<T> {
+        let future = rx
+            .recv()
+            .map_err(|_| IoError::other("JS stream callback was dropped"))?
+            .map_err(IoError::other)?;
+        futures::executor::block_on(future).map_err(IoError::other)?
+    };
+
+    if tokio::runtime::Handle::try_current().is_ok() {
+        tokio::task::block_in_place(wait)
+    } else {
+        wait()
+    }
+}
+
+/// Calls a JS callback on the main thread and waits, without blocking, for its result.
+///
+/// `call` may return a promise or a plain value, which is treated as already resolved, and an
+/// exception it throws is treated as a rejection, so callbacks can be sync or async functions.
+/// `settle` converts the outcome. This only fails when the main thread cannot be reached or
+/// `settle` throws.
+pub(crate) async fn await_js<T, C, S>(channel: &Channel, call: C, settle: S) -> Result<T, String>
+where
+    T: Send + 'static,
+    C: for<'a> FnOnce(&mut TaskContext<'a>) -> JsResult<'a, JsValue> + Send + 'static,
+    S: FnOnce(TaskContext, Result<Handle<JsValue>, Handle<JsValue>>) -> NeonResult<T>
+        + Send
+        + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+
+    channel
+        .try_send(move |mut cx| {
+            let promise = match cx.try_catch(|cx| call(cx)) {
+                Ok(value) => match value.downcast::<JsPromise, _>(&mut cx) {
+                    Ok(promise) => promise,
+                    Err(_) => JsPromise::resolve(&mut cx, value),
+                },
+                Err(err) => JsPromise::reject(&mut cx, err),
+            };
+            let _ = tx.send(promise.to_future(&mut cx, settle)?);
+            Ok(())
+        })
+        .map_err(|err| err.to_string())?;
+
+    rx.await
+        .map_err(|_| "JS callback was dropped".to_string())?
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Converts the settled value of a JS `read` promise into bytes, accepting a `Buffer` or any
+/// `Uint8Array`.
+fn settled_bytes(
+    cx: &mut TaskContext,
+    result: Result<Handle<JsValue>, Handle<JsValue>>,
+) -> NeonResult<IoResult<Vec<u8>>> {
+    match result {
+        Ok(value) => match value.downcast::<JsTypedArray<u8>, _>(cx) {
+            Ok(bytes) => Ok(Ok(bytes.as_slice(cx).to_vec())),
+            Err(_) => Ok(Err(IoError::new(
+                ErrorKind::InvalidData,
+                "JS stream read must resolve to a Buffer or Uint8Array",
+            ))),
+        },
+        Err(err) => {
+            let message = err.to_string(cx)?.value(cx);
+            Ok(Err(IoError::other(message)))
+        }
+    }
+}
+
+/// Calls `read(offset, length)` on the JS asset object and waits for the resulting bytes.
+fn js_read(
+    channel: &Channel,
+    object: &Arc<Root<JsObject>>,
+    read_fn: &Arc<Root<JsFunction>>,
+    offset: u64,
+    length: usize,
+) -> IoResult<Vec<u8>> {
+    let object = Arc::clone(object);
+    let read_fn = Arc::clone(read_fn);
+
+    block_on_js(channel, move |cx| {
+        let this = object.to_inner(cx);
+        let offset = cx.number(offset as f64);
+        let length = cx.number(length as f64);
+        read_fn
+            .to_inner(cx)
+            .call_with(cx)
+            .this(this)
+            .arg(offset)
+            .arg(length)
+            .apply::<JsPromise, _>(cx)?
+            .to_future(cx, |mut cx, result| settled_bytes(&mut cx, result))
+    })
+}
+
+/// Resolves a `SeekFrom` against the current position and, when known, the stream length.
+fn resolve_seek(pos: SeekFrom, position: u64, len: Option<u64>) -> IoResult<u64> {
+    let overflow = || IoError::new(ErrorKind::InvalidInput, "seek overflow");
+    let new_position: i64 = match pos {
+        SeekFrom::Start(offset) => i64::try_from(offset).map_err(|_| overflow())?,
+        SeekFrom::Current(offset) => (position as i64).checked_add(offset).ok_or_else(overflow)?,
+        SeekFrom::End(offset) => {
+            let len = len.ok_or_else(|| {
+                IoError::new(
+                    ErrorKind::Unsupported,
+                    "Cannot seek from the end of a stream asset without a known size",
+                )
+            })?;
+            (len as i64).checked_add(offset).ok_or_else(overflow)?
+        }
+    };
+    if new_position < 0 {
+        return Err(IoError::new(
+            ErrorKind::InvalidInput,
+            "seek before start of stream",
+        ));
+    }
+    Ok(new_position as u64)
+}
+
+/// A read-only stream backed by a JS object exposing `read(offset, length): Promise<Uint8Array>`.
+///
+/// The object is typically an adapter over an S3 or HTTP range request, so the asset is never
+/// held in memory as a whole. Reads are fetched in [`CHUNK_SIZE`] windows and served from the
+/// last window while they stay within it.
+pub(crate) struct JsReadStream {
+    channel: Channel,
+    object: Arc<Root<JsObject>>,
+    read_fn: Arc<Root<JsFunction>>,
+    size: Option<u64>,
+    position: u64,
+    window: Vec<u8>,
+    window_start: u64,
+}
+
+impl JsReadStream {
+    pub(crate) fn new(
+        channel: Channel,
+        object: Arc<Root<JsObject>>,
+        read_fn: Arc<Root<JsFunction>>,
+        size: Option<u64>,
+    ) -> Self {
+        Self {
+            channel,
+            object,
+            read_fn,
+            size,
+            position: 0,
+            window: Vec::new(),
+            window_start: 0,
+        }
+    }
+
+    /// The size of the asset, when the JS object gave one.
+    pub(crate) fn size(&self) -> Option<u64> {
+        self.size
+    }
+}
+
+impl Read for JsReadStream {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let window_end = self.window_start + self.window.len() as u64;
+        if self.position < self.window_start || self.position >= window_end {
+            self.window = js_read(
+                &self.channel,
+                &self.object,
+                &self.read_fn,
+                self.position,
+                buf.len().max(CHUNK_SIZE),
+            )?;
+            self.window_start = self.position;
+        }
+
+        let start = (self.position - self.window_start) as usize;
+        let available = &self.window[start.min(self.window.len())..];
+        let bytes_read = available.len().min(buf.len());
+        buf[..bytes_read].copy_from_slice(&available[..bytes_read]);
+        self.position += bytes_read as u64;
+        Ok(bytes_read)
+    }
+}
+
+impl Seek for JsReadStream {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        self.position = resolve_seek(pos, self.position, self.size)?;
+        Ok(self.position)
+    }
+}
+
+impl NeonReadStreamTrait for JsReadStream {}
+
+/// A random-access destination backed by a JS object exposing
+/// `write(offset, data): Promise<void>` and, optionally, `read(offset, length)`.
+///
+/// c2pa-rs seeks back into the destination to patch the manifest once the asset is written, and
+/// reads it back for some formats, so a purely sequential sink is not enough. Contiguous writes
+/// are coalesced into [`CHUNK_SIZE`] blocks before being sent to JS. Callers must `flush` the
+/// stream before dropping it; dropping does not flush, because the last owner may be the JS
+/// main thread, which cannot wait on its own callbacks.
+#[derive(Clone)]
+pub(crate) struct JsWriteStream {
+    channel: Channel,
+    object: Arc<Root<JsObject>>,
+    write_fn: Arc<Root<JsFunction>>,
+    read_fn: Option<Arc<Root<JsFunction>>>,
+    position: u64,
+    len: u64,
+    pending: Vec<u8>,
+    pending_start: u64,
+}
+
+impl JsWriteStream {
+    pub(crate) fn new(
+        channel: Channel,
+        object: Arc<Root<JsObject>>,
+        write_fn: Arc<Root<JsFunction>>,
+        read_fn: Option<Arc<Root<JsFunction>>>,
+    ) -> Self {
+        Self {
+            channel,
+            object,
+            write_fn,
+            read_fn,
+            position: 0,
+            len: 0,
+            pending: Vec::new(),
+            pending_start: 0,
+        }
+    }
+
+    fn flush_pending(&mut self) -> IoResult<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let data = std::mem::take(&mut self.pending);
+        let offset = self.pending_start;
+        let object = Arc::clone(&self.object);
+        let write_fn = Arc::clone(&self.write_fn);
+
+        block_on_js(&self.channel, move |cx| {
+            let this = object.to_inner(cx);
+            let offset = cx.number(offset as f64);
+            let data = JsBuffer::from_slice(cx, &data)?;
+            write_fn
+                .to_inner(cx)
+                .call_with(cx)
+                .this(this)
+                .arg(offset)
+                .arg(data)
+                .apply::<JsPromise, _>(cx)?
+                .to_future(cx, |mut cx, result| match result {
+                    Ok(_) => Ok(Ok(())),
+                    Err(err) => {
+                        let message = err.to_string(&mut cx)?.value(&mut cx);
+                        Ok(Err(IoError::other(message)))
+                    }
+                })
+        })
+    }
+}
+
+impl Write for JsWriteStream {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        if self.pending.is_empty() {
+            self.pending_start = self.position;
+        } else if self.pending_start + self.pending.len() as u64 != self.position {
+            self.flush_pending()?;
+            self.pending_start = self.position;
+        }
+
+        self.pending.extend_from_slice(buf);
+        self.position += buf.len() as u64;
+        self.len = self.len.max(self.position);
+
+        if self.pending.len() >= CHUNK_SIZE {
+            self.flush_pending()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.flush_pending()
+    }
+}
+
+impl Read for JsWriteStream {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let Some(read_fn) = self.read_fn.clone() else {
+            return Err(IoError::new(
+                ErrorKind::Unsupported,
+                "Destination stream asset does not provide a read callback",
+            ));
+        };
+        if buf.is_empty() || self.position >= self.len {
+            return Ok(0);
+        }
+
+        // Everything written so far must be visible to the JS side before reading it back.
+        self.flush_pending()?;
+
+        let length = buf.len().min((self.len - self.position) as usize);
+        let data = js_read(&self.channel, &self.object, &read_fn, self.position, length)?;
+        let bytes_read = data.len().min(buf.len());
+        buf[..bytes_read].copy_from_slice(&data[..bytes_read]);
+        self.position += bytes_read as u64;
+        Ok(bytes_read)
+    }
+}
+
+impl Seek for JsWriteStream {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        self.position = resolve_seek(pos, self.position, Some(self.len))?;
+        Ok(self.position)
+    }
+}
+
+impl NeonWriteStreamTrait for JsWriteStream {}
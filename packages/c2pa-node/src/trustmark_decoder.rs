@@ -0,0 +1,109 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Runs a Trustmark decoder model up to the raw payload bits, which the trustmark crate checks
+//! itself without saying how many bit flips it corrected or whether the payload was corrupt
+//! rather than missing.
+
+use crate::bch::{self, PayloadCheck, PAYLOAD_BITS};
+use crate::error::{Error, Result};
+use fast_image_resize::{FilterType, ResizeAlg, ResizeOptions, Resizer};
+use image::{DynamicImage, GenericImageView as _};
+use ndarray::{Array, Axis};
+use ort::{GraphOptimizationLevel, Session};
+use std::path::Path;
+use trustmark::Variant;
+
+/// A decoder model, run the way the trustmark crate runs it.
+pub(crate) struct PayloadDecoder {
+    session: Session,
+    variant: Variant,
+}
+
+impl PayloadDecoder {
+    /// Loads the decoder model of `variant` from `path`.
+    pub(crate) fn new(path: &Path, variant: Variant) -> Result<Self> {
+        let session = Session::builder()
+            .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|builder| builder.with_intra_threads(8))
+            .and_then(|builder| builder.commit_from_file(path))
+            .map_err(trustmark::Error::from)?;
+        Ok(Self { session, variant })
+    }
+
+    /// Decodes the payload embedded in `image` and checks it against its BCH code.
+    pub(crate) fn decode(&self, image: &DynamicImage) -> Result<PayloadCheck> {
+        // The P variant decodes a smaller image.
+        let size = if self.variant == Variant::P { 224 } else { 256 };
+        let input = model_input(self.variant, image, size)?;
+        let inputs = ort::inputs!["image" => input].map_err(trustmark::Error::from)?;
+        let outputs = self.session.run(inputs).map_err(trustmark::Error::from)?;
+        let output = outputs["output"]
+            .try_extract_tensor::<f32>()
+            .map_err(trustmark::Error::from)?;
+        if output.shape() != [1, PAYLOAD_BITS] {
+            return Err(Error::WatermarkDecoding(format!(
+                "the decoder output has shape {:?}",
+                output.shape()
+            )));
+        }
+
+        let mut bits = [false; PAYLOAD_BITS];
+        for (bit, &value) in bits.iter_mut().zip(output.iter()) {
+            *bit = value >= 0.0;
+        }
+        Ok(bch::check_payload(&bits))
+    }
+}
+
+/// Resizes `image` to the model's `size`, center cropped to a square for the P variant and for
+/// images more than twice as long as they are wide, as a `[1, 3, size, size]` tensor of values
+/// between -1 and 1.
+fn model_input(
+    variant: Variant,
+    image: &DynamicImage,
+    size: u32,
+) -> Result<ort::Value<ort::TensorValueType<f32>>> {
+    let (width, height) = image.dimensions();
+    let side = width.min(height);
+    let (x, y, crop_width, crop_height) =
+        if variant == Variant::P || width > height * 2 || height > width * 2 {
+            ((width - side) / 2, (height - side) / 2, side, side)
+        } else {
+            (0, 0, width, height)
+        };
+
+    let options = ResizeOptions::new()
+        .crop(
+            f64::from(x),
+            f64::from(y),
+            f64::from(crop_width),
+            f64::from(crop_height),
+        )
+        .resize_alg(ResizeAlg::Interpolation(FilterType::Bilinear));
+    let mut resized = DynamicImage::new(size, size, image.color());
+    Resizer::new()
+        .resize(image, &mut resized, &options)
+        .map_err(|err| Error::WatermarkDecoding(err.to_string()))?;
+
+    let size = size as usize;
+    let pixels = Array::from(resized.into_rgb32f().into_vec()) * 2.0 - 1.0;
+    let tensor = pixels
+        .into_shape_with_order((size, size, 3))
+        .map_err(|err| Error::WatermarkDecoding(err.to_string()))?
+        .permuted_axes([2, 0, 1])
+        .insert_axis(Axis(0))
+        .as_standard_layout()
+        .into_owned();
+    Ok(ort::Value::from_array(tensor).map_err(trustmark::Error::from)?)
+}
@@ -0,0 +1,250 @@
+// Copyright 2025 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::Read;
+use std::time::Duration;
+
+use c2pa::crypto::time_stamp::{default_rfc3161_message, default_rfc3161_request};
+use c2pa::http::http::{Request, Response};
+use c2pa::http::{HttpResolverError, SyncHttpResolver};
+use c2pa::{Context, Signer, SigningAlg};
+use neon::context::Context as NeonContext;
+use neon::prelude::*;
+use reqwest::blocking::Client;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+/// Default time allowed for each time stamp authority to respond.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// DER encoding of the SHA-256 algorithm identifier, 2.16.840.1.101.3.4.2.1.
+const SHA256_OID: [u8; 11] = [
+    0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01,
+];
+
+/// Whether a time stamp request carries a nonce.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum NoncePolicy {
+    /// A random 64-bit nonce, which the authority echoes in its token.
+    #[default]
+    Random,
+    /// No nonce, for authorities that reject or mishandle them.
+    None,
+}
+
+/// How a local signer requests RFC 3161 time stamps. The authorities are tried in order until
+/// one grants the request.
+#[derive(Clone, Debug)]
+pub(crate) struct TsaOptions {
+    urls: Vec<String>,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+    nonce: NoncePolicy,
+}
+
+impl TsaOptions {
+    /// Parses the time stamp options of `LocalSigner.newSigner`:
+    /// `{ urls, headers?, timeoutMs?, nonce? }`.
+    pub(crate) fn from_js(cx: &mut FunctionContext, obj: Handle<JsObject>) -> NeonResult<Self> {
+        let urls = obj
+            .get::<JsArray, _, _>(cx, "urls")?
+            .to_vec(cx)?
+            .into_iter()
+            .map(|url| {
+                url.downcast_or_throw::<JsString, _>(cx)
+                    .map(|url| url.value(cx))
+            })
+            .collect::<NeonResult<Vec<_>>>()?;
+        if urls.is_empty() {
+            return cx.throw_error("Time stamp options must include at least one URL");
+        }
+        let headers = match obj.get_opt::<JsArray, _, _>(cx, "headers")? {
+            Some(headers) => headers
+                .to_vec(cx)?
+                .into_iter()
+                .map(|header| {
+                    let header = header.downcast_or_throw::<JsArray, _>(cx)?;
+                    let name = header.get::<JsString, _, _>(cx, 0)?.value(cx);
+                    let value = header.get::<JsString, _, _>(cx, 1)?.value(cx);
+                    Ok((name, value))
+                })
+                .collect::<NeonResult<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        let timeout = obj
+            .get_opt::<JsNumber, _, _>(cx, "timeoutMs")?
+            .map(|ms| Duration::from_millis(ms.value(cx) as u64))
+            .unwrap_or(DEFAULT_TIMEOUT);
+        let nonce = match obj.get_opt::<JsString, _, _>(cx, "nonce")? {
+            Some(nonce) => match nonce.value(cx).as_str() {
+                "random" => NoncePolicy::Random,
+                "none" => NoncePolicy::None,
+                other => {
+                    return cx.throw_error(format!(
+                        "Unknown nonce policy {other:?}, expected \"random\" or \"none\""
+                    ))
+                }
+            },
+            None => NoncePolicy::default(),
+        };
+
+        Ok(Self {
+            urls,
+            headers,
+            timeout,
+            nonce,
+        })
+    }
+
+    /// The authority tried first.
+    pub(crate) fn primary_url(&self) -> &str {
+        &self.urls[0]
+    }
+
+    /// Builds a DER `TimeStampReq` for the SHA-256 hash of `message`, asking for the
+    /// authority's certificate in the response.
+    fn request_body(&self, message: &[u8]) -> Result<Vec<u8>> {
+        match self.nonce {
+            NoncePolicy::Random => {
+                default_rfc3161_message(message).map_err(|e| Error::TimeStamp(e.to_string()))
+            }
+            // c2pa-rs always adds a nonce, so a request without one is encoded here.
+            NoncePolicy::None => {
+                let algorithm = der(0x30, &[&SHA256_OID[..], &[0x05, 0x00]].concat());
+                let imprint = der(
+                    0x30,
+                    &[algorithm, der(0x04, &Sha256::digest(message))].concat(),
+                );
+                let request = [der(0x02, &[0x01]), imprint, der(0x01, &[0xff])].concat();
+                Ok(der(0x30, &request))
+            }
+        }
+    }
+
+    /// Requests a time stamp for `message` from each authority in turn, returning the first
+    /// token that c2pa-rs accepts: one that grants the request, echoes the nonce and covers
+    /// `message`.
+    fn request(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let body = self.request_body(message)?;
+        let client = Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| Error::TimeStamp(e.to_string()))?;
+        let context = Context::new().with_resolver(BlockingResolver(client));
+
+        let mut failures = Vec::new();
+        for url in &self.urls {
+            let headers = Some(self.headers.clone());
+            match default_rfc3161_request(url, headers, &body, message, &context) {
+                Ok(token) => return Ok(token),
+                Err(e) => failures.push(format!("{url}: {e}")),
+            }
+        }
+        Err(Error::TimeStamp(failures.join("; ")))
+    }
+}
+
+/// Sends c2pa-rs's time stamp requests with a blocking client, since local signers sign
+/// synchronously and c2pa-rs is built without a blocking HTTP resolver of its own.
+struct BlockingResolver(Client);
+
+impl SyncHttpResolver for BlockingResolver {
+    fn http_resolve(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> std::result::Result<Response<Box<dyn Read>>, HttpResolverError> {
+        let other = |e: reqwest::Error| HttpResolverError::Other(Box::new(e));
+        let request = reqwest::blocking::Request::try_from(request).map_err(other)?;
+        let response = self.0.execute(request).map_err(other)?;
+
+        let mut builder = Response::builder().status(response.status());
+        for (name, value) in response.headers() {
+            builder = builder.header(name, value);
+        }
+        Ok(builder.body(Box::new(response) as Box<dyn Read>)?)
+    }
+}
+
+/// Wraps a local signer to request time stamps with [`TsaOptions`]. It does not expose the inner
+/// signer's raw signer, so that c2pa-rs asks this signer for time stamps instead.
+pub(crate) struct TsaSigner {
+    signer: Box<dyn Signer>,
+    options: TsaOptions,
+}
+
+impl TsaSigner {
+    pub(crate) fn new(signer: Box<dyn Signer>, options: TsaOptions) -> Self {
+        Self { signer, options }
+    }
+}
+
+impl Signer for TsaSigner {
+    fn sign(&self, data: &[u8]) -> c2pa::Result<Vec<u8>> {
+        self.signer.sign(data)
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.signer.alg()
+    }
+
+    fn certs(&self) -> c2pa::Result<Vec<Vec<u8>>> {
+        self.signer.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.signer.reserve_size()
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        self.signer.ocsp_val()
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        Some(self.options.primary_url().to_owned())
+    }
+
+    fn timestamp_request_headers(&self) -> Option<Vec<(String, String)>> {
+        Some(self.options.headers.clone())
+    }
+
+    fn timestamp_request_body(&self, message: &[u8]) -> c2pa::Result<Vec<u8>> {
+        self.options
+            .request_body(message)
+            .map_err(|err| c2pa::Error::OtherError(Box::new(err)))
+    }
+
+    fn send_timestamp_request(&self, message: &[u8]) -> Option<c2pa::Result<Vec<u8>>> {
+        Some(
+            self.options
+                .request(message)
+                .map_err(|err| c2pa::Error::OtherError(Box::new(err))),
+        )
+    }
+}
+
+/// Encodes a DER value with a definite length.
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let len_bytes = len.to_be_bytes();
+        let skip = len_bytes.iter().take_while(|byte| **byte == 0).count();
+        out.push(0x80 | (len_bytes.len() - skip) as u8);
+        out.extend_from_slice(&len_bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+    out
+}
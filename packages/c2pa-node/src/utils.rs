@@ -12,9 +12,16 @@
 // each license.
 
 use c2pa::Context;
+use c2pa_types::TrustConfig;
+use neon::context::Context as _;
 use neon::prelude::*;
+use serde_json::{Map, Value};
+use std::sync::Arc;
 
+use crate::abort::continue_operation;
 use crate::error::{Error, Result};
+use crate::neon_context::NeonContext;
+use crate::settings::{global_settings, merge_settings};
 
 #[allow(dead_code)]
 // Used in debugging
@@ -32,14 +39,114 @@ pub fn log_message<'a, C: neon::context::Context<'a>>(cx: &mut C, message: &str)
     log.call(cx, this, args).unwrap();
 }
 
-/// Parse optional settings string from JS argument and create a Context.
+/// Parse optional settings from JS argument and create a Context.
+/// The argument may be a settings string, or a shared Context handle which is used as is.
+/// A trust config, parsed by the caller with `parse_trust_config`, is applied on top of settings.
 /// Returns Ok(Some(Context)) if settings are provided, Ok(None) if not provided,
 /// or Err if settings are invalid.
 pub fn parse_settings(
     cx: &mut FunctionContext,
     arg_index: usize,
+    trust: Option<&TrustConfig>,
     error_prefix: &str,
-) -> Result<Option<Context>> {
+) -> Result<Option<Arc<Context>>> {
+    if let Some(context) = parse_shared_context(cx, arg_index) {
+        return match trust {
+            Some(_) => Err(Error::Settings(format!(
+                "{error_prefix}: A trust config cannot be combined with a shared Context; \
+                 pass it to Context.new instead",
+            ))),
+            None => Ok(Some(context)),
+        };
+    }
+
+    let settings = parse_settings_string(cx, arg_index, error_prefix)?;
+    settings_with_trust(settings, trust)?
+        .map(|settings_string| context_from_settings(&settings_string, error_prefix).map(Arc::new))
+        .transpose()
+}
+
+/// Returns the shared Context when the JS argument is a Context handle.
+pub fn parse_shared_context(cx: &mut FunctionContext, arg_index: usize) -> Option<Arc<Context>> {
+    let value = cx.argument_opt(arg_index)?;
+    NeonContext::from_js(cx, value)
+}
+
+/// Parse the optional `trust` config of the options object at the JS argument.
+/// Returns Ok(None) if there is no options object or its `trust` is null or undefined.
+pub fn parse_trust_config(
+    cx: &mut FunctionContext,
+    arg_index: usize,
+    error_prefix: &str,
+) -> NeonResult<Option<TrustConfig>> {
+    let Some(options) = cx
+        .argument_opt(arg_index)
+        .and_then(|value| value.downcast::<JsObject, _>(cx).ok())
+    else {
+        return Ok(None);
+    };
+
+    let js_value = options.get_value(cx, "trust")?;
+    if js_value.is_a::<JsNull, _>(cx) || js_value.is_a::<JsUndefined, _>(cx) {
+        return Ok(None);
+    }
+    let trust = if js_value.is_a::<JsObject, _>(cx) {
+        neon_serde4::from_value(cx, js_value)
+            .map_err(|e| Error::Settings(format!("{error_prefix}: Invalid trust config: {e}")))
+    } else {
+        Err(Error::Settings(format!(
+            "{error_prefix}: Trust config must be an object, null, or undefined",
+        )))
+    };
+    trust
+        .map(Some)
+        .or_else(|err| cx.throw_error(err.to_string()))
+}
+
+/// Apply an optional settings string and trust config on top of the global settings.
+/// Returns the settings unchanged when there are no global settings and no trust config.
+pub fn settings_with_trust(
+    settings: Option<String>,
+    trust: Option<&TrustConfig>,
+) -> Result<Option<String>> {
+    let global = global_settings();
+    if global.is_none() && trust.is_none() {
+        return Ok(settings);
+    }
+
+    let mut merged = global.unwrap_or_default();
+    merge_settings(&mut merged, settings_object(settings.as_deref())?);
+    if let Some(trust) = trust {
+        trust.apply(&mut merged)?;
+    }
+    Ok(Some(Value::Object(merged).to_string()))
+}
+
+/// Parse a JSON or TOML settings string into an object so individual values can be overridden.
+/// Missing settings parse as an empty object.
+pub fn settings_object(settings: Option<&str>) -> Result<Map<String, Value>> {
+    let value = match settings {
+        Some(settings) => serde_json::from_str::<Value>(settings).or_else(|_| {
+            toml::from_str::<Value>(settings)
+                .map_err(|e| Error::Settings(format!("Invalid settings: {e}")))
+        })?,
+        None => return Ok(Map::new()),
+    };
+
+    match value {
+        Value::Object(settings) => Ok(settings),
+        _ => Err(Error::Settings("Settings must be an object".to_string())),
+    }
+}
+
+/// Parse optional settings string from JS argument without creating a Context.
+/// Returns Ok(Some(String)) if settings are provided, Ok(None) if not provided,
+/// or Err if the argument is not a string.
+pub fn parse_settings_string(
+    cx: &mut FunctionContext,
+    arg_index: usize,
+    error_prefix: &str,
+) -> Result<Option<String>> {
     let settings_opt = cx.argument_opt(arg_index);
 
     match settings_opt {
@@ -48,21 +155,14 @@ pub fn parse_settings(
                 let settings_string = js_value
                     .downcast::<JsString, _>(cx)
                     .map_err(|_| {
-                        Error::Signing(format!("{error_prefix}: Expected settings string"))
-                    })? 
+                        Error::Settings(format!("{error_prefix}: Expected settings string"))
+                    })?
                     .value(cx);
-
-                // Create context with settings
-                let context = Context::new()
-                    .with_settings(settings_string.as_str())
-                    .map_err(|e| {
-                        Error::Signing(format!("{error_prefix}: Invalid settings: {e}"))
-                    })?;
-                Ok(Some(context))
+                Ok(Some(settings_string))
             } else if js_value.is_a::<JsNull, _>(cx) || js_value.is_a::<JsUndefined, _>(cx) {
                 Ok(None)
             } else {
-                Err(Error::Signing(format!(
+                Err(Error::Settings(format!(
                     "{error_prefix}: Settings must be a string, null, or undefined",
                 )))
             }
@@ -70,3 +170,26 @@ pub fn parse_settings(
         None => Ok(None),
     }
 }
+
+/// Returns a context that belongs to a single reader, so it can be changed without affecting
+/// anyone else: `context` itself when nothing else holds it, or a copy of its settings when it
+/// is a shared Context.
+pub fn owned_context(context: Option<Arc<Context>>) -> Result<Context> {
+    match context.map(Arc::try_unwrap) {
+        Some(Ok(context)) => Ok(context),
+        Some(Err(shared)) => Ok(new_context().with_settings(shared.settings())?),
+        None => Ok(new_context()),
+    }
+}
+
+/// Creates a context with default settings whose operations can be aborted.
+pub fn new_context() -> Context {
+    Context::new().with_progress_callback(continue_operation)
+}
+
+/// Create a Context with the given settings string.
+pub fn context_from_settings(settings_string: &str, error_prefix: &str) -> Result<Context> {
+    new_context()
+        .with_settings(settings_string)
+        .map_err(|e| Error::Settings(format!("{error_prefix}: Invalid settings: {e}")))
+}
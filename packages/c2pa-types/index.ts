@@ -66,3 +66,18 @@ export type {
 export type { Action } from './types/Action.js';
 
 export type { C2PaReason as C2paReason } from './types/Action.js';
+
+export type { TrustConfig } from './types/TrustConfig.js';
+
+export type {
+  ManifestValidationReport,
+  RecoveredSoftBinding,
+  ValidationReport
+} from './types/ValidationReport.js';
+
+export type {
+  CertificateSummary,
+  SignerDiagnostics,
+  SignerIssue,
+  SignerIssueCode
+} from './types/SignerDiagnostics.js';
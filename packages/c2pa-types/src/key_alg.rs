@@ -0,0 +1,86 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+use c2pa::SigningAlg;
+
+const EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+const P256: &str = "1.2.840.10045.3.1.7";
+const P384: &str = "1.3.132.0.34";
+const P521: &str = "1.3.132.0.35";
+const ED25519: &str = "1.3.101.112";
+const RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.1";
+const RSASSA_PSS: &str = "1.2.840.113549.1.1.10";
+
+/// Why [`key_signing_alg`] could not pick an algorithm for a key.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum UnsupportedKey {
+    #[error("unsupported elliptic curve {0}")]
+    Curve(String),
+
+    #[error("the elliptic curve is not named")]
+    UnnamedCurve,
+
+    #[error("unsupported key type {0}")]
+    KeyType(String),
+}
+
+/// Picks the signing algorithm for a key from the OIDs of its algorithm identifier: the key type,
+/// and for elliptic curve keys the named curve. RSA keys sign with PS256, since C2PA does not
+/// allow PKCS#1 v1.5 signatures.
+///
+/// Private keys and certificates both identify their keys this way, so signers and
+/// [`SignerDiagnostics`](crate::SignerDiagnostics) agree on the algorithm.
+pub fn key_signing_alg(
+    key_oid: &str,
+    curve_oid: Option<&str>,
+) -> Result<SigningAlg, UnsupportedKey> {
+    match key_oid {
+        EC_PUBLIC_KEY => match curve_oid {
+            Some(P256) => Ok(SigningAlg::Es256),
+            Some(P384) => Ok(SigningAlg::Es384),
+            Some(P521) => Ok(SigningAlg::Es512),
+            Some(curve) => Err(UnsupportedKey::Curve(curve.to_owned())),
+            None => Err(UnsupportedKey::UnnamedCurve),
+        },
+        ED25519 => Ok(SigningAlg::Ed25519),
+        RSA_ENCRYPTION | RSASSA_PSS => Ok(SigningAlg::Ps256),
+        oid => Err(UnsupportedKey::KeyType(oid.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_algorithm_of_supported_keys() {
+        assert_eq!(
+            key_signing_alg(EC_PUBLIC_KEY, Some(P384)),
+            Ok(SigningAlg::Es384)
+        );
+        assert_eq!(key_signing_alg(ED25519, None), Ok(SigningAlg::Ed25519));
+        assert_eq!(key_signing_alg(RSA_ENCRYPTION, None), Ok(SigningAlg::Ps256));
+    }
+
+    #[test]
+    fn rejects_unsupported_keys() {
+        // secp256k1
+        assert_eq!(
+            key_signing_alg(EC_PUBLIC_KEY, Some("1.3.132.0.10")),
+            Err(UnsupportedKey::Curve("1.3.132.0.10".to_owned()))
+        );
+        assert_eq!(
+            key_signing_alg(EC_PUBLIC_KEY, None),
+            Err(UnsupportedKey::UnnamedCurve)
+        );
+        // DSA
+        assert_eq!(
+            key_signing_alg("1.2.840.10040.4.1", None),
+            Err(UnsupportedKey::KeyType("1.2.840.10040.4.1".to_owned()))
+        );
+    }
+}
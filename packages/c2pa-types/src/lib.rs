@@ -0,0 +1,21 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+//! Types shared by the c2pa-js bindings that do not exist in c2pa-rs itself.
+//!
+//! They are exported as JSON schema by the `c2pa-types` binary alongside the c2pa-rs types, so
+//! the TypeScript definitions for both are generated the same way.
+
+mod key_alg;
+mod signer_diagnostics;
+mod trust_config;
+mod validation_report;
+
+pub use key_alg::{UnsupportedKey, key_signing_alg};
+pub use signer_diagnostics::{CertificateSummary, SignerDiagnostics, SignerIssue, SignerIssueCode};
+pub use trust_config::{TrustConfig, TrustConfigError};
+pub use validation_report::{ManifestValidationReport, RecoveredSoftBinding, ValidationReport};
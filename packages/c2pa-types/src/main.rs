@@ -8,6 +8,7 @@
 use std::{fs, path::Path};
 
 use c2pa::{Builder, Ingredient, ManifestDefinition, Reader, assertions::Action};
+use c2pa_types::{SignerDiagnostics, TrustConfig, ValidationReport};
 use schemars::{Schema, schema_for};
 
 fn main() {
@@ -28,6 +29,17 @@ fn main() {
     write_schema(&schema_for!(Ingredient), "Ingredient", output_dir);
     write_schema(&schema_for!(Builder), "Builder", output_dir);
     write_schema(&schema_for!(Action), "Action", output_dir);
+    write_schema(&schema_for!(TrustConfig), "TrustConfig", output_dir);
+    write_schema(
+        &schema_for!(ValidationReport),
+        "ValidationReport",
+        output_dir,
+    );
+    write_schema(
+        &schema_for!(SignerDiagnostics),
+        "SignerDiagnostics",
+        output_dir,
+    );
 }
 
 fn write_schema(schema: &Schema, name: &str, output_dir: &Path) {
@@ -0,0 +1,520 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+use std::fmt;
+
+use c2pa::SigningAlg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use x509_parser::{certificate::X509Certificate, prelude::FromDer};
+
+use crate::key_alg::{UnsupportedKey, key_signing_alg};
+
+/// `id-kp-documentSigning` and `c2pa-kp-claimSigning`. Together with `id-kp-emailProtection`,
+/// these are the extended key usages C2PA accepts on a claim signing certificate by default;
+/// `trust.trust_config` in the c2pa settings can allow others.
+const DOCUMENT_SIGNING: &str = "1.3.6.1.5.5.7.3.36";
+const C2PA_CLAIM_SIGNING: &str = "1.3.6.1.4.1.62558.2.1";
+
+/// The result of checking a signer's certificate chain and algorithm before anything is signed.
+///
+/// Problems found here would otherwise surface only when c2pa-rs signs, or when a validator
+/// rejects the manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerDiagnostics {
+    /// True when no issues were found. Warnings do not affect it.
+    pub valid: bool,
+
+    /// Algorithm that matches the key of the signing certificate, when the key type is one C2PA
+    /// supports. RSA keys are reported as PS256.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detected_alg: Option<SigningAlg>,
+
+    /// The signing (leaf) certificate, when it could be parsed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leaf: Option<CertificateSummary>,
+
+    /// Every problem found, in chain order.
+    pub issues: Vec<SignerIssue>,
+
+    /// Problems that only matter under the default trust settings, such as an extended key usage
+    /// that the configured `trust.trust_config` may allow.
+    pub warnings: Vec<SignerIssue>,
+}
+
+/// Identifies a certificate in a [`SignerDiagnostics`] report.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateSummary {
+    /// Distinguished name of the subject.
+    pub subject: String,
+
+    /// Distinguished name of the issuer.
+    pub issuer: String,
+
+    /// Start of the validity period, in seconds since the Unix epoch.
+    pub not_before: i64,
+
+    /// End of the validity period, in seconds since the Unix epoch.
+    pub not_after: i64,
+}
+
+/// A single problem with a signer's certificates or algorithm.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerIssue {
+    /// What kind of problem this is.
+    pub code: SignerIssueCode,
+
+    /// A description of the problem that can be shown as is.
+    pub message: String,
+
+    /// Index in the chain of the certificate the issue is about, starting with the leaf at 0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate_index: Option<usize>,
+}
+
+/// The kinds of problem reported by [`SignerDiagnostics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SignerIssueCode {
+    /// The chain contains no certificates.
+    NoCertificates,
+    /// A certificate is not valid PEM or DER.
+    InvalidCertificate,
+    /// The signing certificate's key type cannot be used to sign C2PA manifests.
+    UnsupportedKey,
+    /// The signing certificate's key does not match the declared algorithm.
+    AlgorithmMismatch,
+    /// A certificate is not issued by the certificate that follows it.
+    ChainOrder,
+    /// The signing certificate's extended key usage does not allow claim signing by default.
+    /// Reported as a warning.
+    ExtendedKeyUsage,
+    /// A certificate has expired.
+    Expired,
+    /// A certificate is not valid yet.
+    NotYetValid,
+}
+
+impl SignerDiagnostics {
+    /// Checks a PEM certificate chain, leaf first, against the algorithm it will sign with.
+    ///
+    /// When `alg` is `None`, only the chain is checked and `detected_alg` tells which algorithm
+    /// to use. `now` is the time the validity periods are checked at, in seconds since the Unix
+    /// epoch; it is passed in because WebAssembly has no system clock.
+    pub fn check_pem(certs_pem: &[u8], alg: Option<SigningAlg>, now: i64) -> Self {
+        match pem::parse_many(certs_pem) {
+            Ok(blocks) => {
+                let ders: Vec<Vec<u8>> = blocks
+                    .into_iter()
+                    .filter(|block| block.tag() == "CERTIFICATE")
+                    .map(|block| block.into_contents())
+                    .collect();
+                Self::check(&ders, alg, now)
+            }
+            Err(err) => Self::from_issues(
+                None,
+                None,
+                vec![SignerIssue::new(
+                    SignerIssueCode::InvalidCertificate,
+                    format!("the certificate chain is not valid PEM: {err}"),
+                    None,
+                )],
+            ),
+        }
+    }
+
+    /// Checks a chain of DER certificates, leaf first. See [`SignerDiagnostics::check_pem`].
+    pub fn check(certs: &[Vec<u8>], alg: Option<SigningAlg>, now: i64) -> Self {
+        if certs.is_empty() {
+            return Self::from_issues(
+                None,
+                None,
+                vec![SignerIssue::new(
+                    SignerIssueCode::NoCertificates,
+                    "the certificate chain does not contain any certificates".to_owned(),
+                    None,
+                )],
+            );
+        }
+
+        let mut issues = Vec::new();
+        let mut parsed = Vec::with_capacity(certs.len());
+        for (index, der) in certs.iter().enumerate() {
+            match X509Certificate::from_der(der) {
+                Ok((_, cert)) => parsed.push(cert),
+                Err(err) => {
+                    issues.push(SignerIssue::new(
+                        SignerIssueCode::InvalidCertificate,
+                        format!("certificate {index} could not be parsed: {err}"),
+                        Some(index),
+                    ));
+                    return Self::from_issues(None, None, issues);
+                }
+            }
+        }
+
+        let leaf = &parsed[0];
+        let detected_alg = match key_alg(leaf) {
+            Ok(detected) => {
+                if let Some(alg) = alg
+                    && !key_matches(detected, alg)
+                {
+                    issues.push(SignerIssue::new(
+                        SignerIssueCode::AlgorithmMismatch,
+                        format!(
+                            "the signing certificate has a {} key, which cannot sign with {alg}",
+                            key_description(detected)
+                        ),
+                        Some(0),
+                    ));
+                }
+                Some(detected)
+            }
+            Err(message) => {
+                issues.push(SignerIssue::new(
+                    SignerIssueCode::UnsupportedKey,
+                    message,
+                    Some(0),
+                ));
+                None
+            }
+        };
+
+        for (index, pair) in parsed.windows(2).enumerate() {
+            if pair[0].issuer().as_raw() != pair[1].subject().as_raw() {
+                issues.push(SignerIssue::new(
+                    SignerIssueCode::ChainOrder,
+                    format!(
+                        "certificate {index} is issued by \"{}\", but certificate {} is \"{}\"; \
+                         the chain must start with the signing certificate and list each issuer \
+                         after the certificate it issued",
+                        pair[0].issuer(),
+                        index + 1,
+                        pair[1].subject()
+                    ),
+                    Some(index),
+                ));
+            }
+        }
+
+        let warnings = check_eku(leaf).into_iter().collect();
+
+        for (index, cert) in parsed.iter().enumerate() {
+            let validity = cert.validity();
+            if validity.not_after.timestamp() < now {
+                issues.push(SignerIssue::new(
+                    SignerIssueCode::Expired,
+                    format!(
+                        "certificate {index} (\"{}\") expired on {}",
+                        cert.subject(),
+                        validity.not_after
+                    ),
+                    Some(index),
+                ));
+            } else if validity.not_before.timestamp() > now {
+                issues.push(SignerIssue::new(
+                    SignerIssueCode::NotYetValid,
+                    format!(
+                        "certificate {index} (\"{}\") is not valid until {}",
+                        cert.subject(),
+                        validity.not_before
+                    ),
+                    Some(index),
+                ));
+            }
+        }
+
+        Self {
+            warnings,
+            ..Self::from_issues(
+                detected_alg,
+                Some(CertificateSummary::from_certificate(leaf)),
+                issues,
+            )
+        }
+    }
+
+    fn from_issues(
+        detected_alg: Option<SigningAlg>,
+        leaf: Option<CertificateSummary>,
+        issues: Vec<SignerIssue>,
+    ) -> Self {
+        Self {
+            valid: issues.is_empty(),
+            detected_alg,
+            leaf,
+            issues,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// Lists the issue messages, one per line. Warnings are not included.
+impl fmt::Display for SignerDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(&issue.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl CertificateSummary {
+    fn from_certificate(cert: &X509Certificate) -> Self {
+        Self {
+            subject: cert.subject().to_string(),
+            issuer: cert.issuer().to_string(),
+            not_before: cert.validity().not_before.timestamp(),
+            not_after: cert.validity().not_after.timestamp(),
+        }
+    }
+}
+
+impl SignerIssue {
+    fn new(code: SignerIssueCode, message: String, certificate_index: Option<usize>) -> Self {
+        Self {
+            code,
+            message,
+            certificate_index,
+        }
+    }
+}
+
+/// Picks the algorithm for the certificate's public key with [`key_signing_alg`], which c2pa-node
+/// also uses for private keys.
+fn key_alg(cert: &X509Certificate) -> Result<SigningAlg, String> {
+    let algorithm = &cert.public_key().algorithm;
+    let curve = algorithm
+        .parameters
+        .as_ref()
+        .and_then(|parameters| parameters.as_oid().ok())
+        .map(|oid| oid.to_id_string());
+    key_signing_alg(&algorithm.algorithm.to_id_string(), curve.as_deref()).map_err(
+        |err| match err {
+            UnsupportedKey::Curve(curve) => {
+                format!("the signing certificate uses the unsupported elliptic curve {curve}")
+            }
+            UnsupportedKey::UnnamedCurve => {
+                "the signing certificate's elliptic curve is not named".to_owned()
+            }
+            UnsupportedKey::KeyType(oid) => {
+                format!(
+                    "the signing certificate has a key of type {oid}, which C2PA does not support"
+                )
+            }
+        },
+    )
+}
+
+fn key_matches(detected: SigningAlg, alg: SigningAlg) -> bool {
+    match detected {
+        SigningAlg::Ps256 | SigningAlg::Ps384 | SigningAlg::Ps512 => matches!(
+            alg,
+            SigningAlg::Ps256 | SigningAlg::Ps384 | SigningAlg::Ps512
+        ),
+        _ => detected == alg,
+    }
+}
+
+fn key_description(detected: SigningAlg) -> &'static str {
+    match detected {
+        SigningAlg::Es256 => "P-256",
+        SigningAlg::Es384 => "P-384",
+        SigningAlg::Es512 => "P-521",
+        SigningAlg::Ed25519 => "Ed25519",
+        _ => "RSA",
+    }
+}
+
+/// C2PA requires the signing certificate to list its extended key usages, to include one that
+/// allows claim signing, and not to use `anyExtendedKeyUsage`. c2pa-rs checks the usages against
+/// `trust.trust_config`, which this can't see, so the result is only a warning.
+fn check_eku(leaf: &X509Certificate) -> Option<SignerIssue> {
+    let message = match leaf.extended_key_usage() {
+        Ok(Some(eku)) if eku.value.any => {
+            "the signing certificate uses anyExtendedKeyUsage, which C2PA does not allow".to_owned()
+        }
+        Ok(Some(eku))
+            if eku.value.email_protection
+                || eku.value.other.iter().any(|oid| {
+                    matches!(
+                        oid.to_id_string().as_str(),
+                        DOCUMENT_SIGNING | C2PA_CLAIM_SIGNING
+                    )
+                }) =>
+        {
+            return None;
+        }
+        Ok(Some(_)) => "the signing certificate's extended key usage does not include \
+                        emailProtection, documentSigning or C2PA claim signing"
+            .to_owned(),
+        Ok(None) => "the signing certificate has no extended key usage extension".to_owned(),
+        Err(err) => format!("the signing certificate's extended key usage is invalid: {err}"),
+    };
+    Some(SignerIssue::new(
+        SignerIssueCode::ExtendedKeyUsage,
+        message,
+        Some(0),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ES256_CHAIN: &[u8] = include_bytes!("../../c2pa-node/tests/fixtures/certs/es256.pub");
+    const ED25519_CHAIN: &[u8] = include_bytes!("../../c2pa-node/tests/fixtures/certs/ed25519.pub");
+    const EXPIRED_SERVER_CERT: &[u8] =
+        include_bytes!("../../c2pa-node/tests/fixtures/certs/es256_expired_server.pub");
+
+    /// 2025-01-01, when the test chains are valid.
+    const NOW: i64 = 1_735_689_600;
+
+    fn codes(issues: &[SignerIssue]) -> Vec<(SignerIssueCode, Option<usize>)> {
+        issues
+            .iter()
+            .map(|issue| (issue.code, issue.certificate_index))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_valid_chain() {
+        let diagnostics = SignerDiagnostics::check_pem(ES256_CHAIN, Some(SigningAlg::Es256), NOW);
+
+        assert!(diagnostics.valid, "{diagnostics}");
+        assert_eq!(diagnostics.detected_alg, Some(SigningAlg::Es256));
+        assert!(diagnostics.warnings.is_empty());
+        assert!(diagnostics.leaf.unwrap().subject.contains("CN=C2PA Signer"));
+    }
+
+    #[test]
+    fn reports_expired_certificates() {
+        let diagnostics = SignerDiagnostics::check_pem(EXPIRED_SERVER_CERT, None, NOW);
+
+        assert!(!diagnostics.valid);
+        assert_eq!(
+            codes(&diagnostics.issues),
+            [(SignerIssueCode::Expired, Some(0))]
+        );
+        assert!(diagnostics.issues[0].message.contains("expired on"));
+
+        // Every certificate of the chain is checked, not just the leaf.
+        let later = SignerDiagnostics::check_pem(ES256_CHAIN, None, 2_000_000_000);
+        assert_eq!(
+            codes(&later.issues),
+            [
+                (SignerIssueCode::Expired, Some(0)),
+                (SignerIssueCode::Expired, Some(1))
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_certificates_that_are_not_valid_yet() {
+        let diagnostics = SignerDiagnostics::check_pem(ES256_CHAIN, None, 0);
+
+        assert_eq!(
+            codes(&diagnostics.issues),
+            [
+                (SignerIssueCode::NotYetValid, Some(0)),
+                (SignerIssueCode::NotYetValid, Some(1))
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_about_an_extended_key_usage_that_does_not_allow_claim_signing() {
+        // The certificate only allows TLS server authentication.
+        let diagnostics = SignerDiagnostics::check_pem(EXPIRED_SERVER_CERT, None, 1_600_000_000);
+
+        assert!(diagnostics.valid, "{diagnostics}");
+        assert_eq!(
+            codes(&diagnostics.warnings),
+            [(SignerIssueCode::ExtendedKeyUsage, Some(0))]
+        );
+        assert!(
+            diagnostics.warnings[0]
+                .message
+                .contains("does not include emailProtection")
+        );
+        // Warnings are not part of the displayed issues.
+        assert_eq!(diagnostics.to_string(), "");
+    }
+
+    #[test]
+    fn reports_a_key_that_does_not_match_the_algorithm() {
+        let diagnostics = SignerDiagnostics::check_pem(ES256_CHAIN, Some(SigningAlg::Es384), NOW);
+
+        assert!(!diagnostics.valid);
+        assert_eq!(diagnostics.detected_alg, Some(SigningAlg::Es256));
+        assert_eq!(
+            codes(&diagnostics.issues),
+            [(SignerIssueCode::AlgorithmMismatch, Some(0))]
+        );
+        assert_eq!(
+            diagnostics.to_string(),
+            "the signing certificate has a P-256 key, which cannot sign with es384"
+        );
+
+        let ed25519 = SignerDiagnostics::check_pem(ED25519_CHAIN, Some(SigningAlg::Ps256), NOW);
+        assert_eq!(ed25519.detected_alg, Some(SigningAlg::Ed25519));
+        assert_eq!(
+            codes(&ed25519.issues),
+            [(SignerIssueCode::AlgorithmMismatch, Some(0))]
+        );
+    }
+
+    #[test]
+    fn reports_a_chain_out_of_order() {
+        let mut chain: Vec<Vec<u8>> = pem::parse_many(ES256_CHAIN)
+            .unwrap()
+            .into_iter()
+            .map(|block| block.into_contents())
+            .collect();
+        chain.reverse();
+
+        let diagnostics = SignerDiagnostics::check(&chain, Some(SigningAlg::Es256), NOW);
+
+        assert!(!diagnostics.valid);
+        assert_eq!(
+            codes(&diagnostics.issues),
+            [(SignerIssueCode::ChainOrder, Some(0))]
+        );
+        assert!(
+            diagnostics.issues[0]
+                .message
+                .contains("the chain must start with the signing certificate")
+        );
+        // The intermediate certificate that now comes first has no extended key usage.
+        assert_eq!(
+            codes(&diagnostics.warnings),
+            [(SignerIssueCode::ExtendedKeyUsage, Some(0))]
+        );
+    }
+
+    #[test]
+    fn reports_chains_that_cannot_be_parsed() {
+        let empty = SignerDiagnostics::check_pem(b"", None, NOW);
+        assert_eq!(
+            codes(&empty.issues),
+            [(SignerIssueCode::NoCertificates, None)]
+        );
+
+        let invalid = SignerDiagnostics::check(&[b"not a certificate".to_vec()], None, NOW);
+        assert_eq!(
+            codes(&invalid.issues),
+            [(SignerIssueCode::InvalidCertificate, Some(0))]
+        );
+        assert!(invalid.leaf.is_none());
+    }
+}
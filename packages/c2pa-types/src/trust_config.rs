@@ -0,0 +1,255 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Trust list configuration for a single reader or builder, applied on top of its settings.
+///
+/// This is a typed alternative to writing the `trust` and `verify` sections of a settings
+/// document by hand. It is validated before it is applied, so a malformed certificate bundle is
+/// reported against the field and certificate it came from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustConfig {
+    /// PEM bundle of trust anchor certificates. Signing credentials that chain to one of them
+    /// are reported as `signingCredential.trusted`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchors: Option<String>,
+
+    /// PEM bundle of end-entity certificates that are trusted as is, without a chain to an
+    /// anchor.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_certificates: Option<String>,
+
+    /// Extended key usage OIDs a signing certificate must carry, in dotted-decimal form.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eku_oids: Option<Vec<String>>,
+
+    /// Whether to validate signing credentials against the trust lists at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_trust: Option<bool>,
+}
+
+/// Describes why a [`TrustConfig`] was rejected.
+#[derive(Debug, thiserror::Error)]
+pub enum TrustConfigError {
+    #[error("{field} does not contain any PEM certificates")]
+    NoCertificates { field: &'static str },
+
+    #[error("{field}: PEM block at index {index} is invalid: {reason}")]
+    InvalidPem {
+        field: &'static str,
+        index: usize,
+        reason: String,
+    },
+
+    #[error("{field}: PEM block at index {index} is a {label}, expected a CERTIFICATE")]
+    NotACertificate {
+        field: &'static str,
+        index: usize,
+        label: String,
+    },
+
+    #[error("ekuOids: entry at index {index} ({oid:?}) is not a dotted-decimal OID")]
+    InvalidOid { index: usize, oid: String },
+}
+
+impl TrustConfig {
+    /// Checks that every certificate bundle holds only well-formed certificates and that every
+    /// EKU is a dotted-decimal OID.
+    pub fn validate(&self) -> Result<(), TrustConfigError> {
+        if let Some(anchors) = &self.anchors {
+            validate_certificates("anchors", anchors)?;
+        }
+        if let Some(allowed) = &self.allowed_certificates {
+            validate_certificates("allowedCertificates", allowed)?;
+        }
+        for (index, oid) in self.eku_oids.iter().flatten().enumerate() {
+            if !is_dotted_oid(oid) {
+                return Err(TrustConfigError::InvalidOid {
+                    index,
+                    oid: oid.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates the configuration and writes it into the `trust` and `verify` sections of a
+    /// c2pa-rs settings object, replacing any values already set for the same keys.
+    pub fn apply(&self, settings: &mut Map<String, Value>) -> Result<(), TrustConfigError> {
+        self.validate()?;
+
+        let trust = section(settings, "trust");
+        if let Some(anchors) = &self.anchors {
+            trust.insert("trust_anchors".to_owned(), Value::from(anchors.as_str()));
+        }
+        if let Some(allowed) = &self.allowed_certificates {
+            trust.insert("allowed_list".to_owned(), Value::from(allowed.as_str()));
+        }
+        if let Some(oids) = &self.eku_oids {
+            trust.insert("trust_config".to_owned(), Value::from(oids.join("\n")));
+        }
+
+        if let Some(verify_trust) = self.verify_trust {
+            section(settings, "verify")
+                .insert("verify_trust".to_owned(), Value::from(verify_trust));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the named settings section, creating it when it is missing or not an object.
+fn section<'a>(settings: &'a mut Map<String, Value>, name: &str) -> &'a mut Map<String, Value> {
+    let value = settings
+        .entry(name)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    match value {
+        Value::Object(map) => map,
+        _ => unreachable!("settings section was just replaced with an object"),
+    }
+}
+
+/// Parses each PEM block of `bundle` on its own, so a failure can be reported by index.
+fn validate_certificates(field: &'static str, bundle: &str) -> Result<(), TrustConfigError> {
+    const BEGIN: &str = "-----BEGIN ";
+
+    let starts: Vec<usize> = bundle
+        .match_indices(BEGIN)
+        .map(|(start, _)| start)
+        .collect();
+    if starts.is_empty() {
+        return Err(TrustConfigError::NoCertificates { field });
+    }
+
+    let ends = starts.iter().skip(1).copied().chain([bundle.len()]);
+    let blocks = starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| &bundle[start..end]);
+
+    for (index, block) in blocks.enumerate() {
+        let pem = pem::parse(block).map_err(|err| TrustConfigError::InvalidPem {
+            field,
+            index,
+            reason: err.to_string(),
+        })?;
+        if pem.tag() != "CERTIFICATE" {
+            return Err(TrustConfigError::NotACertificate {
+                field,
+                index,
+                label: pem.tag().to_owned(),
+            });
+        }
+        // A DER certificate is always a SEQUENCE.
+        if pem.contents().first() != Some(&0x30) {
+            return Err(TrustConfigError::InvalidPem {
+                field,
+                index,
+                reason: "contents are not a DER-encoded certificate".to_owned(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn is_dotted_oid(oid: &str) -> bool {
+    let arcs: Vec<&str> = oid.split('.').collect();
+    arcs.len() >= 2
+        && matches!(arcs[0], "0" | "1" | "2")
+        && arcs
+            .iter()
+            .all(|arc| !arc.is_empty() && arc.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ES256_CHAIN: &str = include_str!("../../c2pa-node/tests/fixtures/certs/es256.pub");
+    const ES256_KEY: &str = include_str!("../../c2pa-node/tests/fixtures/certs/es256.pem");
+
+    #[test]
+    fn applies_the_config_on_top_of_settings() {
+        let config = TrustConfig {
+            anchors: Some(ES256_CHAIN.to_owned()),
+            eku_oids: Some(vec![
+                "1.3.6.1.5.5.7.3.4".to_owned(),
+                "1.3.6.1.5.5.7.3.36".to_owned(),
+            ]),
+            verify_trust: Some(false),
+            ..TrustConfig::default()
+        };
+        let mut settings = serde_json::json!({
+            "trust": "not an object",
+            "verify": { "verify_after_reading": false },
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+
+        config.apply(&mut settings).unwrap();
+
+        assert_eq!(
+            Value::Object(settings),
+            serde_json::json!({
+                "trust": {
+                    "trust_anchors": ES256_CHAIN,
+                    "trust_config": "1.3.6.1.5.5.7.3.4\n1.3.6.1.5.5.7.3.36",
+                },
+                "verify": { "verify_after_reading": false, "verify_trust": false },
+            })
+        );
+    }
+
+    #[test]
+    fn reports_the_certificate_that_is_wrong() {
+        let bundle = format!("{ES256_CHAIN}{ES256_KEY}");
+        let config = TrustConfig {
+            allowed_certificates: Some(bundle),
+            ..TrustConfig::default()
+        };
+
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "allowedCertificates: PEM block at index 2 is a PRIVATE KEY, expected a CERTIFICATE"
+        );
+
+        let config = TrustConfig {
+            anchors: Some("not a certificate".to_owned()),
+            ..TrustConfig::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(TrustConfigError::NoCertificates { field: "anchors" })
+        ));
+    }
+
+    #[test]
+    fn rejects_oids_that_are_not_dotted_decimal() {
+        let config = TrustConfig {
+            eku_oids: Some(vec![
+                "1.3.6.1.5.5.7.3.4".to_owned(),
+                "emailProtection".to_owned(),
+            ]),
+            ..TrustConfig::default()
+        };
+
+        assert!(matches!(
+            config.validate(),
+            Err(TrustConfigError::InvalidOid { index: 1, .. })
+        ));
+        assert!(!is_dotted_oid("3.1"));
+        assert!(!is_dotted_oid("1..2"));
+        assert!(is_dotted_oid("2.5.29.37"));
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+use std::collections::HashSet;
+
+use c2pa::{
+    Reader, ValidationState, validation_results::StatusCodes, validation_status::ValidationStatus,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Validation status codes reported when the claim signing credential was checked against the
+/// configured trust lists. Their presence on the claim signature is how a report tells whether
+/// trust was evaluated; CAWG identity assertions report the same codes for their own signers.
+const TRUST_STATUS_CODES: [&str; 2] = ["signingCredential.trusted", "signingCredential.untrusted"];
+
+/// A summary of the validation results of a manifest store.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ValidationReport {
+    /// Overall validation state of the asset.
+    pub state: ValidationState,
+
+    /// True when the signing credential of the active manifest was checked against the trust
+    /// lists. When false, `state` can never be `Trusted`.
+    pub trust_evaluated: bool,
+
+    /// Results for the active manifest, followed by the results for each ingredient manifest.
+    pub manifests: Vec<ManifestValidationReport>,
+
+    /// Set when the asset had no manifest store of its own and it was recovered through a soft
+    /// binding instead. The asset's hard bindings are then not expected to match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovered_by: Option<RecoveredSoftBinding>,
+}
+
+/// The soft binding, such as a watermark, through which a manifest store was recovered.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecoveredSoftBinding {
+    /// The soft binding algorithm, for instance `com.adobe.trustmark.B`.
+    pub alg: String,
+
+    /// The value read from the asset, which the manifest's `c2pa.soft-binding` assertion records.
+    pub value: String,
+}
+
+/// The validation status codes reported for a single manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ManifestValidationReport {
+    /// Label of the manifest, when it can be resolved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// URI of the ingredient assertion that references this manifest. Not set for the active
+    /// manifest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingredient_uri: Option<String>,
+
+    /// Status codes of checks that passed.
+    pub success: Vec<String>,
+
+    /// Status codes that are informational only and do not affect the validation state.
+    pub informational: Vec<String>,
+
+    /// Status codes of checks that failed.
+    pub failure: Vec<String>,
+
+    /// URIs of the assertions referenced by a failure.
+    pub failed_assertions: Vec<String>,
+}
+
+impl ValidationReport {
+    /// Builds a report from the validation results of `reader`.
+    pub fn from_reader(reader: &Reader) -> Self {
+        let mut manifests = Vec::new();
+        let mut trust_evaluated = false;
+
+        if let Some(results) = reader.validation_results() {
+            if let Some(codes) = results.active_manifest() {
+                trust_evaluated = codes
+                    .success()
+                    .iter()
+                    .chain(codes.informational().iter())
+                    .chain(codes.failure().iter())
+                    .any(|status| {
+                        TRUST_STATUS_CODES.contains(&status.code())
+                            && status
+                                .url()
+                                .is_some_and(|url| url.ends_with("/c2pa.signature"))
+                    });
+                manifests.push(ManifestValidationReport::from_status_codes(
+                    reader.active_label().map(str::to_owned),
+                    None,
+                    codes,
+                ));
+            }
+
+            for delta in results.ingredient_deltas().into_iter().flatten() {
+                let uri = delta.ingredient_assertion_uri();
+                manifests.push(ManifestValidationReport::from_status_codes(
+                    ingredient_manifest_label(reader, uri),
+                    Some(uri.to_owned()),
+                    delta.validation_deltas(),
+                ));
+            }
+        }
+
+        Self {
+            state: reader.validation_state(),
+            trust_evaluated,
+            manifests,
+            recovered_by: None,
+        }
+    }
+}
+
+impl ManifestValidationReport {
+    fn from_status_codes(
+        label: Option<String>,
+        ingredient_uri: Option<String>,
+        codes: &StatusCodes,
+    ) -> Self {
+        // An assertion can fail more than one check; list it once, in the order first reported.
+        let mut seen = HashSet::new();
+        let failed_assertions: Vec<String> = codes
+            .failure()
+            .iter()
+            .filter_map(|status| status.url())
+            .filter(|url| url.contains("/c2pa.assertions/") && seen.insert(*url))
+            .map(str::to_owned)
+            .collect();
+
+        Self {
+            label,
+            ingredient_uri,
+            success: to_codes(codes.success()),
+            informational: to_codes(codes.informational()),
+            failure: to_codes(codes.failure()),
+            failed_assertions,
+        }
+    }
+}
+
+fn to_codes(statuses: &[ValidationStatus]) -> Vec<String> {
+    statuses
+        .iter()
+        .map(|status| status.code().to_owned())
+        .collect()
+}
+
+/// Resolves the label of the manifest an ingredient assertion points to.
+///
+/// The assertion URI has the form `self#jumbf=/c2pa/<manifest>/c2pa.assertions/<assertion>`, so
+/// the ingredient is looked up by its instance label in the manifest that contains it.
+fn ingredient_manifest_label(reader: &Reader, uri: &str) -> Option<String> {
+    let path = uri.split_once("jumbf=")?.1.trim_start_matches('/');
+    let path = path.strip_prefix("c2pa/")?;
+    let (parent_label, assertion_label) = path.split_once("/c2pa.assertions/")?;
+
+    reader
+        .get_manifest(parent_label)?
+        .ingredients()
+        .iter()
+        .find(|ingredient| ingredient.label() == Some(assertion_label))
+        .and_then(|ingredient| ingredient.active_manifest())
+        .map(str::to_owned)
+}
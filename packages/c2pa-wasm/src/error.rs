@@ -8,6 +8,7 @@
 use std::error::Error;
 
 use js_sys::{Error as JsError, JsString};
+use wasm_bindgen::JsValue;
 
 #[derive(thiserror::Error, Debug)]
 pub enum WasmError {
@@ -17,6 +18,9 @@ pub enum WasmError {
     #[error(transparent)]
     Serde(#[from] serde_wasm_bindgen::Error),
 
+    #[error(transparent)]
+    TrustConfig(#[from] c2pa_types::TrustConfigError),
+
     #[error(transparent)]
     Other(Box<dyn Error>),
 }
@@ -38,3 +42,9 @@ impl From<WasmError> for JsString {
         format!("{value:?}").into()
     }
 }
+
+impl From<WasmError> for JsValue {
+    fn from(value: WasmError) -> Self {
+        JsString::from(value).into()
+    }
+}
@@ -0,0 +1,478 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+//! Fragmented BMFF signing without filesystem access.
+//!
+//! c2pa-rs only signs fragmented MP4 through `Builder::sign_fragmented_files`, which needs the
+//! `file_io` feature. This mirrors its layout in memory: each fragment gets a C2PA Merkle `uuid`
+//! box in front of its `moof`, and the manifest carries a `c2pa.hash.bmff.v3` assertion whose
+//! Merkle map binds the fragments and the init segment.
+//!
+//! Like c2pa-rs, the init segment is hashed with a placeholder manifest of the final size in
+//! place, laid out by [`PlaceholderSigner`], so the manifest is only signed once.
+
+use std::io::Cursor;
+
+use c2pa::{
+    AsyncSigner, Builder, Error, Result, Signer, SigningAlg,
+    assertions::{BmffHash, BmffMerkleMap, MerkleMap, VecByteBuf},
+    hash_stream_by_alg,
+};
+use serde_bytes::ByteBuf;
+
+/// The C2PA extended type used by C2PA `uuid` boxes.
+const C2PA_UUID: [u8; 16] = [
+    0xd8, 0xfe, 0xc3, 0xd6, 0x1b, 0x0e, 0x48, 0x3c, 0x92, 0x97, 0x58, 0x28, 0x87, 0x7e, 0xc4, 0x81,
+];
+
+/// The zero-terminated purpose of a fragment's Merkle `uuid` box.
+const MERKLE_PURPOSE: &[u8] = b"merkle\0";
+
+/// The rendition IDs written to the Merkle map; a builder signs one rendition at a time.
+const RENDITION_ID: usize = 1;
+
+/// The label of the BMFF hash assertion a binding adds to the builder.
+const BINDING_LABEL: &str = "c2pa.hash.bmff.v3";
+
+/// A top-level BMFF box.
+struct BoxInfo {
+    box_type: [u8; 4],
+    offset: usize,
+}
+
+/// Binds a set of fragments to an init segment through a Merkle tree of fragment hashes.
+pub(crate) struct FragmentedBinding {
+    alg: String,
+    count: usize,
+    hashes: Vec<Vec<u8>>,
+}
+
+impl FragmentedBinding {
+    /// Inserts a Merkle `uuid` box into each fragment, in place, and computes the tree row that
+    /// the manifest stores.
+    pub(crate) fn new(builder: &Builder, fragments: &mut [Vec<u8>]) -> Result<Self> {
+        if fragments.is_empty() {
+            return Err(Error::BadParam(
+                "at least one fragment must be provided".to_string(),
+            ));
+        }
+        if builder
+            .definition
+            .assertions
+            .iter()
+            .any(|a| a.label.starts_with(BmffHash::LABEL))
+        {
+            return Err(Error::BadParam(
+                "a fragmented asset is bound by its own BMFF hash assertion".to_string(),
+            ));
+        }
+
+        let alg = builder
+            .definition
+            .hash_alg
+            .clone()
+            .unwrap_or_else(|| "sha256".to_string());
+        let max_proofs = builder.context().settings().core.merkle_tree_max_proofs;
+        let hash_len = hash_len(&alg)?;
+
+        // Proofs only depend on the tree's shape, so a tree of zeroed leaves gives placeholder
+        // boxes the same size as the final ones.
+        let placeholder = MerkleTree::new(&alg, vec![vec![0; hash_len]; fragments.len()])?;
+        let mut placeholders = Vec::with_capacity(fragments.len());
+        for (location, fragment) in fragments.iter_mut().enumerate() {
+            let boxes = top_level_boxes(fragment)?;
+            let mut moofs = boxes.iter().filter(|b| &b.box_type == b"moof");
+            let moof = match (moofs.next(), moofs.next()) {
+                (Some(moof), None) => moof.offset,
+                _ => return Err(Error::BadParam("expected 1 moof in fragment".to_string())),
+            };
+            if boxes.iter().filter(|b| &b.box_type == b"mdat").count() != 1 {
+                return Err(Error::BadParam("expected 1 mdat in fragment".to_string()));
+            }
+            if boxes.iter().any(|b| is_c2pa_box(fragment, b)) {
+                return Err(Error::BadParam(
+                    "fragment already contains a C2PA box".to_string(),
+                ));
+            }
+
+            let merkle_box = merkle_box(location, placeholder.proof(location, max_proofs))?;
+            placeholders.push(moof..moof + merkle_box.len());
+            fragment.splice(moof..moof, merkle_box);
+        }
+
+        let leaves = fragments
+            .iter()
+            .map(|fragment| segment_hash(&alg, fragment))
+            .collect::<Result<Vec<_>>>()?;
+        let mut tree = MerkleTree::new(&alg, leaves)?;
+
+        // Replace each placeholder proof with the real one.
+        for (location, (fragment, range)) in fragments.iter_mut().zip(placeholders).enumerate() {
+            let merkle_box = merkle_box(location, tree.proof(location, max_proofs))?;
+            if merkle_box.len() != range.len() {
+                return Err(Error::InvalidAsset(
+                    "mp4 fragment Merkle box size does not match".to_string(),
+                ));
+            }
+            fragment[range].copy_from_slice(&merkle_box);
+        }
+
+        let row = max_proofs.min(tree.layers.len() - 1);
+        Ok(FragmentedBinding {
+            count: fragments.len(),
+            hashes: tree.layers.swap_remove(row),
+            alg,
+        })
+    }
+
+    /// Hashes an init segment that already carries a manifest of its final size.
+    pub(crate) fn init_hash(&self, signed_init: &[u8]) -> Result<Vec<u8>> {
+        segment_hash(&self.alg, signed_init)
+    }
+
+    /// Replaces the builder's BMFF hash assertion with one binding these fragments. Without an
+    /// init hash, a zeroed placeholder of the same size is written.
+    pub(crate) fn apply(&self, builder: &mut Builder, init_hash: Option<Vec<u8>>) -> Result<()> {
+        let init_hash = match init_hash {
+            Some(init_hash) => init_hash,
+            None => vec![0; hash_len(&self.alg)?],
+        };
+
+        self.remove(builder);
+        builder.add_assertion(BINDING_LABEL, &self.bmff_hash(init_hash))?;
+
+        Ok(())
+    }
+
+    /// Removes the assertion added by [`FragmentedBinding::apply`] so the builder can sign other
+    /// assets afterwards.
+    pub(crate) fn remove(&self, builder: &mut Builder) {
+        builder
+            .definition
+            .assertions
+            .retain(|a| a.label != BINDING_LABEL);
+    }
+
+    fn bmff_hash(&self, init_hash: Vec<u8>) -> BmffHash {
+        let mut bmff_hash = BmffHash::new("jumbf manifest", &self.alg, None);
+        bmff_hash.set_default_exclusions();
+        bmff_hash.set_merkle(vec![MerkleMap {
+            unique_id: RENDITION_ID,
+            local_id: RENDITION_ID,
+            count: self.count,
+            alg: Some(self.alg.clone()),
+            init_hash: Some(ByteBuf::from(init_hash)),
+            hashes: VecByteBuf(self.hashes.iter().cloned().map(ByteBuf::from).collect()),
+            fixed_block_size: None,
+            variable_block_sizes: None,
+        }]);
+
+        bmff_hash
+    }
+}
+
+/// Signs nothing, so that c2pa-rs lays out a manifest with its placeholder signature, which is the
+/// size of the signature it reserves for `signer`.
+pub(crate) struct PlaceholderSigner {
+    alg: SigningAlg,
+    certs: Vec<Vec<u8>>,
+    reserve_size: usize,
+}
+
+impl PlaceholderSigner {
+    pub(crate) fn new(signer: &dyn AsyncSigner) -> Result<Self> {
+        Ok(PlaceholderSigner {
+            alg: signer.alg(),
+            certs: signer.certs()?,
+            reserve_size: signer.reserve_size(),
+        })
+    }
+}
+
+impl Signer for PlaceholderSigner {
+    fn sign(&self, _data: &[u8]) -> Result<Vec<u8>> {
+        // An empty COSE signature leaves the placeholder in place.
+        Ok(Vec::new())
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.alg
+    }
+
+    fn certs(&self) -> Result<Vec<Vec<u8>>> {
+        Ok(self.certs.clone())
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.reserve_size
+    }
+
+    fn direct_cose_handling(&self) -> bool {
+        true
+    }
+}
+
+/// A Merkle tree laid out the way c2pa-rs builds it: an odd node at the end of a layer is
+/// promoted unchanged.
+struct MerkleTree {
+    layers: Vec<Vec<Vec<u8>>>,
+}
+
+impl MerkleTree {
+    fn new(alg: &str, leaves: Vec<Vec<u8>>) -> Result<Self> {
+        let mut layers = vec![leaves];
+        while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+            let parents = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash(alg, &[left.as_slice(), right].concat()),
+                    [node] => Ok(node.clone()),
+                    _ => unreachable!(),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            layers.push(parents);
+        }
+
+        Ok(MerkleTree { layers })
+    }
+
+    fn proof(&self, index: usize, max_proofs: usize) -> Vec<Vec<u8>> {
+        let mut proof = Vec::new();
+        let mut index = index;
+        for layer in self.layers.iter().take(max_proofs) {
+            let sibling = if index % 2 == 1 {
+                Some(index - 1)
+            } else {
+                Some(index + 1).filter(|&i| i < layer.len())
+            };
+            if let Some(sibling) = sibling {
+                proof.push(layer[sibling].clone());
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+/// Hashes a segment with the default exclusions of the BMFF hash assertion.
+fn segment_hash(alg: &str, segment: &[u8]) -> Result<Vec<u8>> {
+    let mut bmff_hash = BmffHash::new("jumbf manifest", alg, None);
+    bmff_hash.set_default_exclusions();
+    bmff_hash.gen_hash_from_stream(&mut Cursor::new(segment))?;
+    bmff_hash
+        .hash()
+        .cloned()
+        .ok_or(Error::BadParam("failed to hash segment".to_string()))
+}
+
+fn hash(alg: &str, data: &[u8]) -> Result<Vec<u8>> {
+    hash_stream_by_alg(alg, &mut Cursor::new(data), None, true)
+}
+
+fn hash_len(alg: &str) -> Result<usize> {
+    Ok(hash(alg, &[0])?.len())
+}
+
+/// Builds the C2PA Merkle `uuid` box that is inserted in front of a fragment's `moof`.
+fn merkle_box(location: usize, proof: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+    let merkle_map = BmffMerkleMap {
+        unique_id: RENDITION_ID,
+        local_id: RENDITION_ID,
+        location,
+        hashes: (!proof.is_empty())
+            .then(|| VecByteBuf(proof.into_iter().map(ByteBuf::from).collect())),
+    };
+    let cbor =
+        c2pa_cbor::to_vec(&merkle_map).map_err(|err| Error::AssertionEncoding(err.to_string()))?;
+
+    // header + extended type + version/flags + purpose + Merkle map
+    let size = 8 + C2PA_UUID.len() + 4 + MERKLE_PURPOSE.len() + cbor.len();
+    let mut data = Vec::with_capacity(size);
+    data.extend_from_slice(
+        &u32::try_from(size)
+            .map_err(|_| Error::JumbfCreationError)?
+            .to_be_bytes(),
+    );
+    data.extend_from_slice(b"uuid");
+    data.extend_from_slice(&C2PA_UUID);
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(MERKLE_PURPOSE);
+    data.extend_from_slice(&cbor);
+
+    Ok(data)
+}
+
+fn is_c2pa_box(data: &[u8], info: &BoxInfo) -> bool {
+    &info.box_type == b"uuid" && data.get(info.offset + 8..info.offset + 24) == Some(&C2PA_UUID)
+}
+
+/// Lists the top-level boxes of a BMFF segment.
+fn top_level_boxes(data: &[u8]) -> Result<Vec<BoxInfo>> {
+    let bad_bmff = || Error::InvalidAsset("Bad BMFF".to_string());
+    let read_u32 = |at: usize| -> Result<u64> {
+        let bytes = data.get(at..at + 4).ok_or_else(bad_bmff)?;
+        Ok(u32::from_be_bytes(bytes.try_into().map_err(|_| bad_bmff())?).into())
+    };
+
+    let mut boxes = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let box_type: [u8; 4] = data
+            .get(offset + 4..offset + 8)
+            .and_then(|t| t.try_into().ok())
+            .ok_or_else(bad_bmff)?;
+        let size = match read_u32(offset)? {
+            0 => (data.len() - offset) as u64,
+            1 => (read_u32(offset + 8)? << 32) | read_u32(offset + 12)?,
+            size => size,
+        };
+        let size = usize::try_from(size)
+            .ok()
+            .filter(|&size| size >= 8 && size <= data.len() - offset)
+            .ok_or_else(bad_bmff)?;
+
+        boxes.push(BoxInfo { box_type, offset });
+        offset += size;
+    }
+
+    Ok(boxes)
+}
+
+#[cfg(test)]
+mod tests {
+    use c2pa::{Context, Reader, ValidationState, create_signer};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    const INIT: &[u8] = include_bytes!("../../c2pa-web/test/assets/unsigned_dashinit.mp4");
+    const FRAGMENT: &[u8] = include_bytes!("../../c2pa-web/test/assets/unsigned_dash1.m4s");
+    /// Three copies of `unsigned_dash1.m4s`, signed by c2pa-rs as one rendition.
+    const SIGNED_FRAGMENTS: [&[u8]; 3] = [
+        include_bytes!("../../c2pa-web/test/assets/c2pa_rs_dash1.m4s"),
+        include_bytes!("../../c2pa-web/test/assets/c2pa_rs_dash2.m4s"),
+        include_bytes!("../../c2pa-web/test/assets/c2pa_rs_dash3.m4s"),
+    ];
+    const CERTS: &[u8] = include_bytes!("../../c2pa-node/tests/fixtures/certs/es256.pub");
+    const KEY: &[u8] = include_bytes!("../../c2pa-node/tests/fixtures/certs/es256.pem");
+
+    const SETTINGS: &str = r#"{"verify":{"verify_trust":false}}"#;
+    const DEFINITION: &str = r#"{
+        "format": "video/mp4",
+        "assertions": [
+            {
+                "label": "c2pa.actions",
+                "data": {
+                    "actions": [
+                        {
+                            "action": "c2pa.created",
+                            "digitalSourceType": "http://c2pa.org/digitalsourcetype/empty"
+                        }
+                    ]
+                }
+            }
+        ]
+    }"#;
+
+    fn builder() -> Builder {
+        Builder::from_context(Context::new().with_settings(SETTINGS).unwrap())
+            .with_definition(DEFINITION)
+            .unwrap()
+    }
+
+    fn fragments() -> Vec<Vec<u8>> {
+        vec![FRAGMENT.to_vec(); SIGNED_FRAGMENTS.len()]
+    }
+
+    #[wasm_bindgen_test]
+    fn inserts_the_merkle_boxes_c2pa_rs_writes() {
+        let mut fragments = fragments();
+        FragmentedBinding::new(&builder(), &mut fragments).unwrap();
+
+        for (fragment, expected) in fragments.iter().zip(SIGNED_FRAGMENTS) {
+            assert_eq!(fragment.as_slice(), expected);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn signs_once_over_the_placeholder_layout() {
+        let mut builder = builder();
+        let mut fragments = fragments();
+        let binding = FragmentedBinding::new(&builder, &mut fragments).unwrap();
+        let signer = create_signer::from_keys(CERTS, KEY, SigningAlg::Es256, None).unwrap();
+        let placeholder_signer = PlaceholderSigner {
+            alg: signer.alg(),
+            certs: signer.certs().unwrap(),
+            reserve_size: signer.reserve_size(),
+        };
+
+        binding.apply(&mut builder, None).unwrap();
+        let mut layout = Vec::new();
+        builder
+            .sign(
+                &placeholder_signer,
+                "video/mp4",
+                &mut Cursor::new(INIT),
+                &mut Cursor::new(&mut layout),
+            )
+            .unwrap();
+        let init_hash = binding.init_hash(&layout).unwrap();
+        binding.apply(&mut builder, Some(init_hash)).unwrap();
+        let mut signed = Vec::new();
+        builder
+            .sign(
+                signer.as_ref(),
+                "video/mp4",
+                &mut Cursor::new(INIT),
+                &mut Cursor::new(&mut signed),
+            )
+            .unwrap();
+        assert_eq!(signed.len(), layout.len());
+
+        for fragment in &fragments {
+            let reader = Reader::from_context(Context::new().with_settings(SETTINGS).unwrap())
+                .with_fragment(
+                    "video/mp4",
+                    &mut Cursor::new(&signed),
+                    &mut Cursor::new(fragment),
+                )
+                .unwrap();
+            assert_eq!(reader.validation_state(), ValidationState::Valid);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn removes_only_its_own_assertion() {
+        let mut builder = builder();
+        let binding = FragmentedBinding::new(&builder, &mut fragments()).unwrap();
+
+        binding.apply(&mut builder, None).unwrap();
+        binding.remove(&mut builder);
+
+        let labels: Vec<_> = builder
+            .definition
+            .assertions
+            .iter()
+            .map(|a| a.label.as_str())
+            .collect();
+        assert_eq!(labels, ["c2pa.actions"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn rejects_a_builder_with_a_bmff_hash() {
+        let mut builder = builder();
+        let bmff_hash = BmffHash::new("jumbf manifest", "sha256", None);
+        builder.add_assertion(BmffHash::LABEL, &bmff_hash).unwrap();
+
+        assert!(matches!(
+            FragmentedBinding::new(&builder, &mut fragments()),
+            Err(Error::BadParam(_))
+        ));
+    }
+}
@@ -22,9 +22,18 @@ pub mod wasm_reader;
 /// Exposes a builder API to JS via wasm-bindgen.
 pub mod wasm_builder;
 
+/// Exposes a shared context API to JS via wasm-bindgen.
+pub mod wasm_context;
+
 /// Exposes a JS Callback signer API to JS via wasm-bindgen.
 pub mod wasm_signer;
 
+/// Signs fragmented BMFF assets in memory.
+pub(crate) mod fragmented;
+
+/// Signs with a Web Crypto key on behalf of the builder.
+pub(crate) mod wasm_webcrypto_signer;
+
 /// Provides a JS interface for loading c2pa-rs settings.
 pub mod settings;
 
@@ -6,5 +6,7 @@
 // it.
 
 mod blob_stream;
+mod progress_stream;
 
 pub use blob_stream::BlobStream;
+pub(crate) use progress_stream::{Phase, Progress};
@@ -0,0 +1,269 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+use std::cell::RefCell;
+use std::io::{Read, Result as IoResult, Seek, SeekFrom, Write};
+use std::rc::Rc;
+
+use js_sys::{Function as JsFunction, Object, Reflect};
+use wasm_bindgen::JsValue;
+
+/// Bytes processed between two progress reports within the same phase, so large assets do not
+/// cross into JS for every read.
+const REPORT_INTERVAL: u64 = 256 * 1024;
+
+/// The stage a signing or reading operation is in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Phase {
+    /// Reading the source asset, which is hashed as it is read.
+    Hashing,
+    /// Waiting on the signer.
+    Signing,
+    /// Writing the signed asset.
+    Writing,
+}
+
+impl Phase {
+    fn as_str(self) -> &'static str {
+        match self {
+            Phase::Hashing => "hashing",
+            Phase::Signing => "signing",
+            Phase::Writing => "writing",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Reporter {
+    callback: JsFunction,
+    // The byte count last sent to JS for each phase, indexed by `Phase as usize`. The source is
+    // read while the destination is written, so each phase is throttled on its own.
+    last: RefCell<[Option<u64>; 3]>,
+}
+
+impl Reporter {
+    /// Reports `bytes` if the phase has just started or has advanced far enough since the last
+    /// report. A `last_report` is sent whenever the bytes have advanced at all.
+    fn report(&self, phase: Phase, bytes: u64, total: Option<u64>, last_report: bool) {
+        {
+            let mut last = self.last.borrow_mut();
+            let last = &mut last[phase as usize];
+            let due = match *last {
+                None => true,
+                Some(last) => {
+                    bytes > last
+                        && (last_report || bytes >= last + REPORT_INTERVAL || Some(bytes) == total)
+                }
+            };
+            if !due {
+                return;
+            }
+            *last = Some(bytes);
+        }
+
+        let event = Object::new();
+        let _ = Reflect::set(&event, &"phase".into(), &phase.as_str().into());
+        let _ = Reflect::set(&event, &"bytes".into(), &JsValue::from_f64(bytes as f64));
+        if let Some(total) = total {
+            let _ = Reflect::set(
+                &event,
+                &"totalBytes".into(),
+                &JsValue::from_f64(total as f64),
+            );
+        }
+        // Progress is informational, so a callback that throws does not fail the operation.
+        let _ = self.callback.call1(&JsValue::undefined(), &event);
+    }
+}
+
+/// Reports the progress of an operation to an optional JS `onProgress` callback, which receives
+/// `{ phase, bytes, totalBytes? }`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Progress {
+    reporter: Option<Rc<Reporter>>,
+}
+
+impl Progress {
+    /// Creates a reporter for `callback`, or one that reports nothing without a callback.
+    pub(crate) fn new(callback: Option<JsFunction>) -> Self {
+        Self {
+            reporter: callback.map(|callback| {
+                Rc::new(Reporter {
+                    callback,
+                    last: RefCell::new([None; 3]),
+                })
+            }),
+        }
+    }
+
+    /// Reports the start of a phase that has no byte count, such as signing.
+    pub(crate) fn phase(&self, phase: Phase) {
+        if let Some(reporter) = &self.reporter {
+            reporter.report(phase, 0, None, false);
+        }
+    }
+
+    /// Wraps `inner` to report the bytes read from it while hashing, out of `total`.
+    pub(crate) fn read_stream<S>(&self, inner: S, total: u64) -> ProgressStream<S> {
+        ProgressStream::new(inner, self.reporter.clone(), Phase::Hashing, Some(total))
+    }
+
+    /// Wraps `inner` to report the bytes written to it.
+    pub(crate) fn write_stream<S>(&self, inner: S) -> ProgressStream<S> {
+        ProgressStream::new(inner, self.reporter.clone(), Phase::Writing, None)
+    }
+}
+
+/// Wraps a stream to report how far into it reads or writes have reached. Seeking back and
+/// re-reading does not count twice, so the reported bytes never exceed the asset size.
+pub(crate) struct ProgressStream<S> {
+    inner: S,
+    reporter: Option<Rc<Reporter>>,
+    phase: Phase,
+    total: Option<u64>,
+    position: u64,
+    furthest: u64,
+}
+
+impl<S> ProgressStream<S> {
+    fn new(inner: S, reporter: Option<Rc<Reporter>>, phase: Phase, total: Option<u64>) -> Self {
+        Self {
+            inner,
+            reporter,
+            phase,
+            total,
+            position: 0,
+            furthest: 0,
+        }
+    }
+
+    fn advance(&mut self, bytes: usize) {
+        self.position += bytes as u64;
+        if self.position > self.furthest {
+            self.furthest = self.position;
+            if let Some(reporter) = &self.reporter {
+                reporter.report(self.phase, self.furthest, self.total, false);
+            }
+        }
+    }
+}
+
+impl<S: Read> Read for ProgressStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        if self.phase == Phase::Hashing {
+            self.advance(bytes_read);
+        } else {
+            self.position += bytes_read as u64;
+        }
+        Ok(bytes_read)
+    }
+}
+
+impl<S: Write> Write for ProgressStream<S> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let bytes_written = self.inner.write(buf)?;
+        self.advance(bytes_written);
+        Ok(bytes_written)
+    }
+
+    // The size of the output is not known up front, so the final byte count is reported once
+    // the output is flushed.
+    fn flush(&mut self) -> IoResult<()> {
+        self.inner.flush()?;
+        if let Some(reporter) = &self.reporter {
+            reporter.report(self.phase, self.furthest, self.total, true);
+        }
+        Ok(())
+    }
+}
+
+impl<S: Seek> Seek for ProgressStream<S> {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        self.position = self.inner.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+// SAFETY: WASM is single-threaded.
+unsafe impl<S: Send> Send for ProgressStream<S> {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use js_sys::Array;
+    use wasm_bindgen::{JsCast, closure::Closure};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    fn recording_progress() -> (Progress, Array, Closure<dyn FnMut(JsValue)>) {
+        let events = Array::new();
+        let recorded = events.clone();
+        let closure = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            recorded.push(&event);
+        });
+        let callback: JsFunction = closure.as_ref().clone().unchecked_into();
+        (Progress::new(Some(callback)), events, closure)
+    }
+
+    fn bytes_of(event: JsValue) -> f64 {
+        Reflect::get(&event, &"bytes".into())
+            .unwrap()
+            .as_f64()
+            .unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn test_read_reports_furthest_position_once() {
+        let (progress, events, _closure) = recording_progress();
+        let mut stream = progress.read_stream(Cursor::new(vec![0u8; 8]), 8);
+
+        let mut buf = vec![0; 8];
+        stream.read_exact(&mut buf).unwrap();
+        stream.seek(SeekFrom::Start(0)).unwrap();
+        stream.read_exact(&mut buf).unwrap();
+
+        // The first read starts the phase and reaches the total; re-reading reports nothing.
+        assert_eq!(events.length(), 1);
+        assert_eq!(bytes_of(events.get(0)), 8.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_write_is_throttled_until_flushed() {
+        let (progress, events, _closure) = recording_progress();
+        let mut stream = progress.write_stream(Cursor::new(Vec::new()));
+
+        for _ in 0..4 {
+            stream.write_all(&[0u8; 1024]).unwrap();
+        }
+        assert_eq!(events.length(), 1);
+        assert_eq!(
+            Reflect::get(&events.get(0), &"phase".into()).unwrap(),
+            JsValue::from_str("writing")
+        );
+
+        // Flushing reports the final size, which was held back by the throttle.
+        stream.flush().unwrap();
+        assert_eq!(events.length(), 2);
+        assert_eq!(bytes_of(events.get(1)), 4096.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_without_callback_passes_through() {
+        let progress = Progress::default();
+        let mut stream = progress.read_stream(Cursor::new(vec![1u8, 2, 3]), 3);
+
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).unwrap();
+
+        assert_eq!(buf, vec![1, 2, 3]);
+    }
+}
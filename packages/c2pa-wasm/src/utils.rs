@@ -5,11 +5,16 @@
 // accordance with the terms of the Adobe license agreement accompanying
 // it.
 
-use std::io::Cursor;
+use std::io::{Cursor, Read};
 
+use c2pa::Context;
+use c2pa_types::TrustConfig;
 use js_sys::Uint8Array;
+use serde_json::{Map, Value};
+use wasm_bindgen::JsValue;
+use web_sys::Blob;
 
-use crate::error::WasmError;
+use crate::{error::WasmError, stream::BlobStream};
 
 pub fn cursor_to_u8array(cursor: Cursor<Vec<u8>>) -> Result<Uint8Array, WasmError> {
     let data = cursor.into_inner();
@@ -18,3 +23,38 @@ pub fn cursor_to_u8array(cursor: Cursor<Vec<u8>>) -> Result<Uint8Array, WasmErro
     uint8array.copy_from(&data);
     Ok(uint8array)
 }
+
+/// Reads the whole of a `Blob` into memory.
+pub fn blob_to_vec(blob: &Blob) -> Result<Vec<u8>, WasmError> {
+    let mut data = Vec::with_capacity(blob.size() as usize);
+    BlobStream::new(blob)
+        .and_then(|mut stream| stream.read_to_end(&mut data))
+        .map_err(WasmError::other)?;
+    Ok(data)
+}
+
+/// Creates a `Context` from an optional context JSON string and an optional `TrustConfig` object,
+/// which is validated and applied on top of the settings. Returns `None` when neither is given.
+pub fn context_from_json(
+    context_json: Option<String>,
+    trust: JsValue,
+) -> Result<Option<Context>, WasmError> {
+    let trust: Option<TrustConfig> = serde_wasm_bindgen::from_value(trust)?;
+
+    let settings = match trust {
+        Some(trust) => {
+            let mut settings = match context_json {
+                Some(json) => serde_json::from_str(&json).map_err(WasmError::other)?,
+                None => Map::new(),
+            };
+            trust.apply(&mut settings)?;
+            Some(Value::Object(settings).to_string())
+        }
+        None => context_json,
+    };
+
+    settings
+        .map(|json| Context::new().with_settings(json.as_str()))
+        .transpose()
+        .map_err(WasmError::from)
+}
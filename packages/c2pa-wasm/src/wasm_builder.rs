@@ -5,23 +5,27 @@
 // accordance with the terms of the Adobe license agreement accompanying
 // it.
 
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 
 use c2pa::{
-    Builder, BuilderIntent, Context, Ingredient,
+    AsyncSigner, Builder, BuilderIntent, Context, Ingredient,
     assertions::{Action, Actions, C2paReason},
 };
-use js_sys::{JsString, Uint8Array};
+use js_sys::{Function as JsFunction, JsString, Uint8Array};
 use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
 use serde_wasm_bindgen::Serializer;
 use wasm_bindgen::prelude::*;
 use web_sys::Blob;
 
 use crate::{
     error::WasmError,
-    stream::BlobStream,
-    utils::cursor_to_u8array,
+    fragmented::{FragmentedBinding, PlaceholderSigner},
+    stream::{BlobStream, Progress},
+    utils::{blob_to_vec, context_from_json, cursor_to_u8array},
+    wasm_context::WasmContext,
     wasm_signer::{SignerDefinition, WasmSigner},
+    wasm_webcrypto_signer::WebCryptoSigner,
 };
 
 /// Wraps a `c2pa::Builder`.
@@ -40,6 +44,14 @@ struct AssetAndManifestBytes {
     pub manifest: Vec<u8>,
 }
 
+/// Holds the bytes of a signed init segment and its fragments.
+#[derive(Serialize)]
+struct SignedFragments {
+    #[serde(with = "serde_bytes")]
+    pub init: Vec<u8>,
+    pub fragments: Vec<ByteBuf>,
+}
+
 /**
  * NOTE: we can only return Err(JsString) or Err(JsValue) as error types here, because for some as-of-yet unknown
  * reason, wasm-bindgen appears to mishandle JsErrors when created in a Firefox web worker.
@@ -50,20 +62,22 @@ struct AssetAndManifestBytes {
 #[wasm_bindgen]
 impl WasmBuilder {
     /// Creates a new `WasmBuilder` with a minimal manifest definition.
-    /// Optionally accepts a context JSON string to configure the builder.
+    /// Optionally accepts a context JSON string and a `TrustConfig` object to configure the builder.
     #[wasm_bindgen(js_name = new)]
-    pub fn new(context_json: Option<String>) -> Result<WasmBuilder, JsString> {
-        let context = match context_json {
-            Some(json) => Context::new()
-                .with_settings(json.as_str())
-                .map_err(WasmError::from)?,
-            None => Context::new(),
-        };
+    pub fn new(context_json: Option<String>, trust: JsValue) -> Result<WasmBuilder, JsString> {
+        let context = context_from_json(context_json, trust)?.unwrap_or_else(Context::new);
         let builder = Builder::from_context(context);
 
         Ok(WasmBuilder::from_builder(builder))
     }
 
+    /// Creates a new `WasmBuilder` with a minimal manifest definition, configured by a shared
+    /// `WasmContext`.
+    #[wasm_bindgen(js_name = newWithContext)]
+    pub fn new_with_context(context: &WasmContext) -> WasmBuilder {
+        WasmBuilder::from_builder(Builder::from_shared_context(&context.shared()))
+    }
+
     /// Sets the builder "intent."
     #[wasm_bindgen(js_name = setIntent)]
     pub fn set_intent(&mut self, json_intent: JsValue) -> Result<(), JsString> {
@@ -75,15 +89,14 @@ impl WasmBuilder {
     }
 
     /// Attempts to create a new `WasmBuilder` from a JSON ManifestDefinition string.
-    /// Optionally accepts a context JSON string to configure the builder.
+    /// Optionally accepts a context JSON string and a `TrustConfig` object to configure the builder.
     #[wasm_bindgen(js_name = fromJson)]
-    pub fn from_json(json: &str, context_json: Option<String>) -> Result<WasmBuilder, JsString> {
-        let context = match context_json {
-            Some(ctx_json) => Context::new()
-                .with_settings(ctx_json.as_str())
-                .map_err(WasmError::from)?,
-            None => Context::new(),
-        };
+    pub fn from_json(
+        json: &str,
+        context_json: Option<String>,
+        trust: JsValue,
+    ) -> Result<WasmBuilder, JsString> {
+        let context = context_from_json(context_json, trust)?.unwrap_or_else(Context::new);
         let builder = Builder::from_context(context)
             .with_definition(json)
             .map_err(WasmError::from)?;
@@ -91,25 +104,57 @@ impl WasmBuilder {
         Ok(WasmBuilder::from_builder(builder))
     }
 
+    /// Attempts to create a new `WasmBuilder` from a JSON ManifestDefinition string, configured by
+    /// a shared `WasmContext`.
+    #[wasm_bindgen(js_name = fromJsonWithContext)]
+    pub fn from_json_with_context(
+        json: &str,
+        context: &WasmContext,
+    ) -> Result<WasmBuilder, JsString> {
+        let builder = Builder::from_shared_context(&context.shared())
+            .with_definition(json)
+            .map_err(WasmError::from)?;
+
+        Ok(WasmBuilder::from_builder(builder))
+    }
+
     /// Attempts to create a new `WasmBuilder` from a builder archive.
-    /// Optionally accepts a context JSON string to configure the builder.
+    /// Optionally accepts a context JSON string and a `TrustConfig` object to configure the builder.
     #[wasm_bindgen(js_name = fromArchive)]
     pub fn from_archive(
         archive: &Blob,
         context_json: Option<String>,
+        trust: JsValue,
     ) -> Result<WasmBuilder, JsString> {
         let stream = BlobStream::new(archive).map_err(WasmError::other)?;
-        let mut builder = if let Some(ctx_json) = context_json {
-            let context = Context::new()
-                .with_settings(ctx_json.as_str())
-                .map_err(WasmError::from)?;
-            Builder::from_context(context)
+        let builder = match context_from_json(context_json, trust)? {
+            Some(context) => Builder::from_context(context)
                 .with_archive(stream)
-                .map_err(WasmError::from)?
-        } else {
-            Builder::default().with_archive(stream).map_err(WasmError::from)?
+                .map_err(WasmError::from)?,
+            None => Builder::default()
+                .with_archive(stream)
+                .map_err(WasmError::from)?,
         };
 
+        Ok(WasmBuilder::from_archive_builder(builder))
+    }
+
+    /// Attempts to create a new `WasmBuilder` from a builder archive, configured by a shared
+    /// `WasmContext`.
+    #[wasm_bindgen(js_name = fromArchiveWithContext)]
+    pub fn from_archive_with_context(
+        archive: &Blob,
+        context: &WasmContext,
+    ) -> Result<WasmBuilder, JsString> {
+        let stream = BlobStream::new(archive).map_err(WasmError::other)?;
+        let builder = Builder::from_shared_context(&context.shared())
+            .with_archive(stream)
+            .map_err(WasmError::from)?;
+
+        Ok(WasmBuilder::from_archive_builder(builder))
+    }
+
+    fn from_archive_builder(mut builder: Builder) -> WasmBuilder {
         // The ARCHIVE_METADATA assertion is merely working-store bookkeeping used to
         // reconstruct a builder from an archive.
         builder
@@ -117,7 +162,7 @@ impl WasmBuilder {
             .assertions
             .retain(|a| a.label != c2pa::assertions::labels::ARCHIVE_METADATA);
 
-        Ok(WasmBuilder::from_builder(builder))
+        WasmBuilder::from_builder(builder)
     }
 
     fn from_builder(builder: Builder) -> WasmBuilder {
@@ -253,7 +298,8 @@ impl WasmBuilder {
         self.builder
             .filter_actions_and_ingredients(
                 |_action| {
-                    let keep = u32::try_from(action_i).is_ok_and(|idx| action_indices.contains(&idx));
+                    let keep =
+                        u32::try_from(action_i).is_ok_and(|idx| action_indices.contains(&idx));
                     action_i += 1;
                     keep
                 },
@@ -319,9 +365,8 @@ impl WasmBuilder {
         let mut rewritten: Vec<(usize, Option<serde_json::Value>)> =
             Vec::with_capacity(positions.len());
         for (pos, group) in positions.into_iter().zip(action_groups) {
-            let value =
-                serde_json::to_value(&self.builder.definition.assertions[pos].data)
-                    .map_err(WasmError::other)?;
+            let value = serde_json::to_value(&self.builder.definition.assertions[pos].data)
+                .map_err(WasmError::other)?;
             let mut actions: Actions = serde_json::from_value(value).map_err(WasmError::other)?;
 
             if group.is_empty() {
@@ -450,34 +495,50 @@ impl WasmBuilder {
     }
 
     /// Sign an asset using the provided SignerDefinition, format, and source Blob.
+    /// Optionally accepts an `on_progress` callback that receives `{ phase, bytes, totalBytes? }`.
     #[wasm_bindgen]
     pub async fn sign(
         &mut self,
         signer_definition: &SignerDefinition,
         format: &str,
         source: &Blob,
-    ) -> Result<Vec<u8>, JsString> {
+        on_progress: Option<JsFunction>,
+    ) -> Result<Vec<u8>, JsValue> {
         let mut asset: Vec<u8> = Vec::new();
 
-        self.sign_internal(signer_definition, format, source, &mut asset)
-            .await?;
+        self.sign_internal(
+            signer_definition,
+            format,
+            source,
+            &mut asset,
+            Progress::new(on_progress),
+        )
+        .await?;
 
         Ok(asset)
     }
 
     /// Sign an asset using the provided SignerDefinition, format, and source Blob.
     /// Use this method to get both the manifest bytes and the bytes of the signed asset.
+    /// Optionally accepts an `on_progress` callback that receives `{ phase, bytes, totalBytes? }`.
     #[wasm_bindgen(js_name = signAndGetManifestBytes)]
     pub async fn sign_and_get_manifest_bytes(
         &mut self,
         signer_definition: &SignerDefinition,
         format: &str,
         source: &Blob,
-    ) -> Result<JsValue, JsString> {
+        on_progress: Option<JsFunction>,
+    ) -> Result<JsValue, JsValue> {
         let mut asset: Vec<u8> = Vec::new();
 
         let manifest = self
-            .sign_internal(signer_definition, format, source, &mut asset)
+            .sign_internal(
+                signer_definition,
+                format,
+                source,
+                &mut asset,
+                Progress::new(on_progress),
+            )
             .await?;
 
         let result = AssetAndManifestBytes { manifest, asset }
@@ -487,23 +548,134 @@ impl WasmBuilder {
         Ok(result)
     }
 
+    /// Sign a fragmented MP4 using the provided SignerDefinition, format, init segment [`Blob`] and
+    /// fragment [`Blob`]s, given in presentation order. Returns the signed init segment and fragments.
+    /// Optionally accepts an `on_progress` callback that receives `{ phase, bytes, totalBytes? }`.
+    ///
+    /// The manifest's BMFF hash covers the init segment with the manifest in place, so the init
+    /// segment is first laid out with a placeholder manifest of the final size, then signed once.
+    #[wasm_bindgen(js_name = signFragmented)]
+    pub async fn sign_fragmented(
+        &mut self,
+        signer_definition: &SignerDefinition,
+        format: &str,
+        init: &Blob,
+        fragments: Vec<Blob>,
+        on_progress: Option<JsFunction>,
+    ) -> Result<JsValue, JsValue> {
+        let init = blob_to_vec(init)?;
+        let mut fragments = fragments
+            .iter()
+            .map(blob_to_vec)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let binding =
+            FragmentedBinding::new(&self.builder, &mut fragments).map_err(WasmError::from)?;
+        let signed = self
+            .sign_fragmented_init(
+                signer_definition,
+                format,
+                &init,
+                &binding,
+                Progress::new(on_progress),
+            )
+            .await;
+        // The binding only describes these fragments, so don't leave it on the builder.
+        binding.remove(&mut self.builder);
+
+        let result = SignedFragments {
+            init: signed?,
+            fragments: fragments.into_iter().map(ByteBuf::from).collect(),
+        }
+        .serialize(&self.serializer)
+        .map_err(WasmError::from)?;
+
+        Ok(result)
+    }
+
+    async fn sign_fragmented_init(
+        &mut self,
+        signer_definition: &SignerDefinition,
+        format: &str,
+        init: &[u8],
+        binding: &FragmentedBinding,
+        progress: Progress,
+    ) -> Result<Vec<u8>, JsValue> {
+        let signer = Self::signer(signer_definition, &progress)?;
+
+        binding
+            .apply(&mut self.builder, None)
+            .map_err(WasmError::from)?;
+        let placeholder_signer =
+            PlaceholderSigner::new(signer.as_ref()).map_err(WasmError::from)?;
+        let mut layout = Vec::new();
+        self.builder
+            .sign(
+                &placeholder_signer,
+                format,
+                &mut Cursor::new(init),
+                &mut Cursor::new(&mut layout),
+            )
+            .map_err(WasmError::from)?;
+
+        let init_hash = binding.init_hash(&layout).map_err(WasmError::from)?;
+        binding
+            .apply(&mut self.builder, Some(init_hash))
+            .map_err(WasmError::from)?;
+        let mut signed = Vec::new();
+        let mut stream = progress.read_stream(Cursor::new(init), init.len() as u64);
+        let mut cursor = progress.write_stream(Cursor::new(&mut signed));
+        self.builder
+            .sign_async(signer.as_ref(), format, &mut stream, &mut cursor)
+            .await
+            .map_err(WasmError::from)?;
+        cursor.flush().map_err(WasmError::other)?;
+        drop(cursor);
+
+        // A signature that outgrows the reserved size would move the boxes the init hash covers.
+        if signed.len() != layout.len() {
+            return Err(WasmError::from(c2pa::Error::JumbfCreationError).into());
+        }
+
+        Ok(signed)
+    }
+
+    fn signer(
+        signer_definition: &SignerDefinition,
+        progress: &Progress,
+    ) -> Result<Box<dyn AsyncSigner>, JsValue> {
+        let js_value: &JsValue = signer_definition.as_ref();
+        let signer: Box<dyn AsyncSigner> = if WebCryptoSigner::is_definition(js_value) {
+            Box::new(WebCryptoSigner::from_definition(js_value)?.with_progress(progress.clone()))
+        } else {
+            Box::new(
+                WasmSigner::from_definition(signer_definition)?.with_progress(progress.clone()),
+            )
+        };
+
+        Ok(signer)
+    }
+
     async fn sign_internal(
         &mut self,
         signer_definition: &SignerDefinition,
         format: &str,
         source: &Blob,
         dest: &mut Vec<u8>,
-    ) -> Result<Vec<u8>, JsString> {
-        let signer = WasmSigner::from_definition(signer_definition)?;
-        let mut stream = BlobStream::new(source).map_err(WasmError::other)?;
+        progress: Progress,
+    ) -> Result<Vec<u8>, JsValue> {
+        let signer = Self::signer(signer_definition, &progress)?;
+        let stream = BlobStream::new(source).map_err(WasmError::other)?;
+        let mut stream = progress.read_stream(stream, source.size() as u64);
 
-        let mut cursor = Cursor::new(dest);
+        let mut cursor = progress.write_stream(Cursor::new(dest));
 
         let manifest = self
             .builder
-            .sign_async(&signer, format, &mut stream, &mut cursor)
+            .sign_async(signer.as_ref(), format, &mut stream, &mut cursor)
             .await
             .map_err(WasmError::from)?;
+        cursor.flush().map_err(WasmError::other)?;
 
         Ok(manifest)
     }
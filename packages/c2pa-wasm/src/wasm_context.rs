@@ -0,0 +1,42 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+use std::sync::Arc;
+
+use c2pa::Context;
+use js_sys::JsString;
+use wasm_bindgen::prelude::*;
+
+use crate::utils::context_from_json;
+
+/// Wraps a `c2pa::Context` that is parsed once and shared by the readers and builders created
+/// from it.
+#[wasm_bindgen]
+pub struct WasmContext {
+    context: Arc<Context>,
+}
+
+#[wasm_bindgen]
+impl WasmContext {
+    /// Creates a new `WasmContext`.
+    /// Optionally accepts a context JSON string and a `TrustConfig` object to configure it.
+    #[wasm_bindgen(js_name = new)]
+    pub fn new(context_json: Option<String>, trust: JsValue) -> Result<WasmContext, JsString> {
+        let context = context_from_json(context_json, trust)?.unwrap_or_else(Context::new);
+
+        Ok(WasmContext {
+            context: Arc::new(context),
+        })
+    }
+}
+
+impl WasmContext {
+    /// Returns a new handle to the shared context.
+    pub(crate) fn shared(&self) -> Arc<Context> {
+        Arc::clone(&self.context)
+    }
+}
@@ -5,16 +5,25 @@
 // accordance with the terms of the Adobe license agreement accompanying
 // it.
 
-use std::io::{Cursor, Read, Seek};
+use std::{
+    io::{Cursor, Read, Seek},
+    sync::Arc,
+};
 
 use c2pa::{Context, Reader};
-use js_sys::{JsString, Uint8Array};
+use c2pa_types::ValidationReport;
+use js_sys::{Function as JsFunction, JsString, Uint8Array};
 use serde::Serialize;
 use serde_wasm_bindgen::Serializer;
 use wasm_bindgen::prelude::*;
 use web_sys::Blob;
 
-use crate::{error::WasmError, stream::BlobStream, utils::cursor_to_u8array};
+use crate::{
+    error::WasmError,
+    stream::{BlobStream, Progress},
+    utils::{context_from_json, cursor_to_u8array},
+    wasm_context::WasmContext,
+};
 
 /// Wraps a `c2pa::Reader`.
 #[wasm_bindgen]
@@ -33,29 +42,42 @@ pub struct WasmReader {
 #[wasm_bindgen]
 impl WasmReader {
     /// Attempts to create a new `WasmReader` from an asset format and `Blob` of the asset's bytes.
-    /// Optionally accepts a context JSON string to configure the reader.
+    /// Optionally accepts a context JSON string and a `TrustConfig` object to configure the reader,
+    /// and an `on_progress` callback that receives `{ phase, bytes, totalBytes? }`.
     #[wasm_bindgen(js_name = fromBlob)]
     pub async fn from_blob(
         format: &str,
         blob: &Blob,
         context_json: Option<String>,
+        trust: JsValue,
+        on_progress: Option<JsFunction>,
     ) -> Result<WasmReader, JsString> {
         let stream = BlobStream::new(blob).map_err(WasmError::other)?;
-        WasmReader::from_stream(format, stream, context_json).await
+        let stream = Progress::new(on_progress).read_stream(stream, blob.size() as u64);
+        let context = context_from_json(context_json, trust)?.unwrap_or_else(Context::new);
+        WasmReader::from_stream(format, stream, Arc::new(context)).await
+    }
+
+    /// Attempts to create a new `WasmReader` from an asset format and `Blob` of the asset's bytes,
+    /// configured by a shared `WasmContext`. Optionally accepts an `on_progress` callback.
+    #[wasm_bindgen(js_name = fromBlobWithContext)]
+    pub async fn from_blob_with_context(
+        format: &str,
+        blob: &Blob,
+        context: &WasmContext,
+        on_progress: Option<JsFunction>,
+    ) -> Result<WasmReader, JsString> {
+        let stream = BlobStream::new(blob).map_err(WasmError::other)?;
+        let stream = Progress::new(on_progress).read_stream(stream, blob.size() as u64);
+        WasmReader::from_stream(format, stream, context.shared()).await
     }
 
     async fn from_stream(
         format: &str,
         stream: impl Read + Seek + Send,
-        context_json: Option<String>,
+        context: Arc<Context>,
     ) -> Result<WasmReader, JsString> {
-        let context = match context_json {
-            Some(json) => Context::new()
-                .with_settings(json.as_str())
-                .map_err(WasmError::from)?,
-            None => Context::new(),
-        };
-        let reader = Reader::from_context(context)
+        let reader = Reader::from_shared_context(&context)
             .with_stream_async(format, stream)
             .await
             .map_err(WasmError::from)?;
@@ -64,33 +86,46 @@ impl WasmReader {
     }
 
     /// Attempts to create a new `WasmReader` from an asset format, a `Blob` of the bytes of the initial segment, and a fragment `Blob`.
-    /// Optionally accepts a context JSON string to configure the reader.
+    /// Optionally accepts a context JSON string and a `TrustConfig` object to configure the reader.
     #[wasm_bindgen(js_name = fromBlobFragment)]
     pub async fn from_blob_fragment(
         format: &str,
         init: &Blob,
         fragment: &Blob,
         context_json: Option<String>,
+        trust: JsValue,
+    ) -> Result<WasmReader, JsString> {
+        let init_stream = BlobStream::new(init).map_err(WasmError::other)?;
+        let fragment_stream = BlobStream::new(fragment).map_err(WasmError::other)?;
+        let context = context_from_json(context_json, trust)?.unwrap_or_else(Context::new);
+
+        WasmReader::from_stream_fragment(format, init_stream, fragment_stream, Arc::new(context))
+            .await
+    }
+
+    /// Attempts to create a new `WasmReader` from an asset format, a `Blob` of the bytes of the initial segment, and a fragment `Blob`,
+    /// configured by a shared `WasmContext`.
+    #[wasm_bindgen(js_name = fromBlobFragmentWithContext)]
+    pub async fn from_blob_fragment_with_context(
+        format: &str,
+        init: &Blob,
+        fragment: &Blob,
+        context: &WasmContext,
     ) -> Result<WasmReader, JsString> {
         let init_stream = BlobStream::new(init).map_err(WasmError::other)?;
         let fragment_stream = BlobStream::new(fragment).map_err(WasmError::other)?;
 
-        WasmReader::from_stream_fragment(format, init_stream, fragment_stream, context_json).await
+        WasmReader::from_stream_fragment(format, init_stream, fragment_stream, context.shared())
+            .await
     }
 
     async fn from_stream_fragment(
         format: &str,
         init: impl Read + Seek + Send,
         fragment: impl Read + Seek + Send,
-        context_json: Option<String>,
+        context: Arc<Context>,
     ) -> Result<WasmReader, JsString> {
-        let context = match context_json {
-            Some(json) => Context::new()
-                .with_settings(json.as_str())
-                .map_err(WasmError::from)?,
-            None => Context::new(),
-        };
-        let reader = Reader::from_context(context)
+        let reader = Reader::from_shared_context(&context)
             .with_fragment_async(format, init, fragment)
             .await
             .map_err(WasmError::from)?;
@@ -133,6 +168,16 @@ impl WasmReader {
         Ok(active_manifest)
     }
 
+    /// Returns a summary of the validation results of the asset's manifest store.
+    #[wasm_bindgen(js_name = validationReport)]
+    pub fn validation_report(&self) -> Result<JsValue, JsString> {
+        let report = ValidationReport::from_reader(&self.reader)
+            .serialize(&self.serializer)
+            .map_err(WasmError::from)?;
+
+        Ok(report)
+    }
+
     /// Returns a JSON representation of the asset's manifest store.
     #[wasm_bindgen]
     pub fn json(&self) -> String {
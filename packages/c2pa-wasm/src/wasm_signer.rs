@@ -8,23 +8,45 @@
 use async_trait::async_trait;
 use c2pa::SigningAlg;
 use c2pa::{AsyncSigner, Result as C2paResult};
-use js_sys::{Function as JsFunction, JsString, Number, Promise as JsPromise, Reflect, Uint8Array};
+use c2pa_types::SignerDiagnostics;
+use js_sys::{
+    Date, Function as JsFunction, JsString, Number, Object, Promise as JsPromise, Reflect,
+    Uint8Array,
+};
+use serde::Serialize;
+use serde_wasm_bindgen::Serializer;
 use wasm_bindgen::JsValue;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 
+use crate::error::WasmError;
+use crate::stream::{Phase, Progress};
+
+const SIGNING_ALGS: &str = "es256, es384, es512, ps256, ps384, ps512, ed25519";
+
 #[wasm_bindgen(typescript_custom_section)]
 const SIGNER_DEFINITION: &'static str = r#"
-interface SignerDefinition {
+type SignerDefinition = CallbackSignerDefinition | WebCryptoSignerDefinition;
+
+interface CallbackSignerDefinition {
     sign: (bytes: Uint8Array<ArrayBuffer>) => Promise<Uint8Array<ArrayBuffer>>;
     reserveSize: number;
-    alg: string;
+    alg?: string;
+    certs?: Uint8Array;
+}
+
+interface WebCryptoSignerDefinition {
+    key: CryptoKey;
+    certs: Uint8Array | Uint8Array[];
+    alg?: string;
+    reserveSize?: number;
 }
 "#;
 
 #[wasm_bindgen]
 extern "C" {
-    /// Contains the configuration and necessary callbacks for signing.
+    /// Contains the configuration and necessary callbacks for signing, or a Web Crypto key to
+    /// sign with.
     #[wasm_bindgen(typescript_type = "SignerDefinition")]
     pub type SignerDefinition;
 }
@@ -35,6 +57,7 @@ pub(crate) struct WasmSigner {
     sign_fn: JsFunction,
     reserve_size: f64,
     signing_alg: SigningAlg,
+    progress: Progress,
 }
 
 /**
@@ -47,36 +70,119 @@ pub(crate) struct WasmSigner {
 #[wasm_bindgen]
 impl WasmSigner {
     /// Attempt to create a new [`WasmSigner`] from a SignerDefinition.
+    ///
+    /// When the definition includes `certs`, a PEM certificate chain, the chain is checked
+    /// against the algorithm up front, and the algorithm is derived from it when `alg` is left
+    /// out. The certificates are not embedded; the `sign` callback still builds the signature.
+    ///
+    /// When the chain cannot be used, the error is an object with the `message` a Node signer
+    /// throws and the `SignerDiagnostics` report as `diagnostics`.
     #[wasm_bindgen(js_name = fromDefinition)]
-    pub fn from_definition(def: &SignerDefinition) -> Result<Self, JsString> {
+    pub fn from_definition(def: &SignerDefinition) -> Result<Self, JsValue> {
         let js_value: JsValue = def.into();
 
         let reserve_size_result: Number = Reflect::get(&js_value, &"reserveSize".into())?.into();
 
-        let alg_result: JsString = Reflect::get(&js_value, &"alg".into())?.into();
-
-        let signing_alg: SigningAlg = match alg_result.as_string() {
-            Some(alg) => match alg.as_str().parse() {
-                Ok(alg) => alg,
-                Err(_) => SigningAlg::Ps256,
-            },
-            None => SigningAlg::Ps256,
+        let alg_value = Reflect::get(&js_value, &"alg".into())?;
+        let declared_alg = if alg_value.is_undefined() || alg_value.is_null() {
+            None
+        } else {
+            Some(parse_alg(&alg_value)?)
         };
 
+        let certs_value = Reflect::get(&js_value, &"certs".into())?;
+        let signing_alg = if certs_value.is_undefined() || certs_value.is_null() {
+            declared_alg
+        } else {
+            let certs: Uint8Array = certs_value
+                .dyn_into()
+                .map_err(|_| JsString::from("The signer's certs must be a Uint8Array of PEM"))?;
+            let diagnostics = SignerDiagnostics::check_pem(&certs.to_vec(), declared_alg, now());
+            if !diagnostics.valid {
+                return Err(diagnostics_error(&diagnostics));
+            }
+            declared_alg.or(diagnostics.detected_alg)
+        }
+        .ok_or_else(|| {
+            JsString::from("The signer needs an alg, or certs to derive the algorithm from")
+        })?;
+
         let sign_fn: JsFunction = Reflect::get(&js_value, &"sign".into())?.into();
 
         Ok(WasmSigner {
             reserve_size: reserve_size_result.into(),
             signing_alg,
             sign_fn,
+            progress: Progress::default(),
         })
     }
 }
 
+/// Checks a PEM certificate chain, and optionally the algorithm it will sign with, without
+/// creating a signer. Returns a `SignerDiagnostics` report.
+#[wasm_bindgen(js_name = checkSignerCertificates)]
+pub fn check_signer_certificates(certs: &[u8], alg: Option<String>) -> Result<JsValue, JsString> {
+    let alg = alg.map(|alg| parse_alg(&alg.into())).transpose()?;
+    let diagnostics = SignerDiagnostics::check_pem(certs, alg, now());
+    let report = diagnostics_value(&diagnostics)?;
+
+    Ok(report)
+}
+
+/// The error for a certificate chain that `diagnostics` found a problem with: an object with the
+/// same `message` and `diagnostics` as the error a Node signer throws. It is not an `Error`, for
+/// the reason given on [`WasmSigner`].
+pub(crate) fn diagnostics_error(diagnostics: &SignerDiagnostics) -> JsValue {
+    let message =
+        format!("The signing certificates cannot be used with this signer:\n{diagnostics}");
+    let error = Object::new();
+    let result = diagnostics_value(diagnostics)
+        .map_err(JsValue::from)
+        .and_then(|report| {
+            Reflect::set(&error, &"message".into(), &message.into())?;
+            Reflect::set(&error, &"diagnostics".into(), &report)
+        });
+
+    match result {
+        Ok(_) => error.into(),
+        Err(err) => err,
+    }
+}
+
+fn diagnostics_value(diagnostics: &SignerDiagnostics) -> Result<JsValue, WasmError> {
+    let serializer = Serializer::new().serialize_maps_as_objects(true);
+    Ok(diagnostics.serialize(&serializer)?)
+}
+
+/// Parses an algorithm name, listing the supported names when it is not one of them.
+pub(crate) fn parse_alg(value: &JsValue) -> Result<SigningAlg, JsString> {
+    let Some(alg) = value.as_string() else {
+        return Err(format!("The signer's alg must be one of {SIGNING_ALGS}").into());
+    };
+    alg.parse().map_err(|_| {
+        format!("Unsupported signing algorithm \"{alg}\"; expected one of {SIGNING_ALGS}").into()
+    })
+}
+
+/// The current time in seconds since the Unix epoch, from the JS clock.
+pub(crate) fn now() -> i64 {
+    (Date::now() / 1000.0) as i64
+}
+
+impl WasmSigner {
+    /// Reports the signing phase to `progress` each time the signer is called.
+    pub(crate) fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+}
+
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl AsyncSigner for WasmSigner {
     async fn sign(&self, data: Vec<u8>) -> C2paResult<Vec<u8>> {
+        self.progress.phase(Phase::Signing);
+
         let len: u32 = data.len().try_into().unwrap();
         let to_be_signed = Uint8Array::new_with_length(len);
         to_be_signed.copy_from(&data);
@@ -119,3 +225,43 @@ impl AsyncSigner for WasmSigner {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use js_sys::Array;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    const ES256_CHAIN: &[u8] = include_bytes!("../../c2pa-web/test/certs/es256.pub");
+
+    #[wasm_bindgen_test]
+    fn reports_unusable_certificates_with_diagnostics() {
+        let def = Object::new();
+        Reflect::set(&def, &"sign".into(), &JsFunction::new_no_args("")).unwrap();
+        Reflect::set(&def, &"reserveSize".into(), &10000.into()).unwrap();
+        Reflect::set(&def, &"alg".into(), &"es384".into()).unwrap();
+        Reflect::set(&def, &"certs".into(), &Uint8Array::from(ES256_CHAIN)).unwrap();
+
+        let error = WasmSigner::from_definition(def.unchecked_ref()).unwrap_err();
+
+        let message = Reflect::get(&error, &"message".into())
+            .unwrap()
+            .as_string()
+            .unwrap();
+        assert!(message.starts_with("The signing certificates cannot be used with this signer:"));
+        assert!(message.ends_with("which cannot sign with es384"));
+
+        let diagnostics = Reflect::get(&error, &"diagnostics".into()).unwrap();
+        let valid = Reflect::get(&diagnostics, &"valid".into()).unwrap();
+        assert_eq!(valid.as_bool(), Some(false));
+        let issues: Array = Reflect::get(&diagnostics, &"issues".into())
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+        let code = Reflect::get(&issues.get(0), &"code".into()).unwrap();
+        assert_eq!(code.as_string().as_deref(), Some("algorithmMismatch"));
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright 2025 Adobe
+// All Rights Reserved.
+//
+// NOTICE: Adobe permits you to use, modify, and distribute this file in
+// accordance with the terms of the Adobe license agreement accompanying
+// it.
+
+use async_trait::async_trait;
+use c2pa::{AsyncSigner, Result as C2paResult, SigningAlg};
+use c2pa_types::SignerDiagnostics;
+use js_sys::{
+    Array, Function as JsFunction, JsString, Object, Promise as JsPromise, Reflect, Uint8Array,
+};
+use wasm_bindgen::JsValue;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::stream::{Phase, Progress};
+use crate::wasm_signer::{diagnostics_error, now, parse_alg};
+
+/// The reserve size used when a definition does not set one. It leaves room for the signature
+/// and a typical certificate chain.
+const DEFAULT_RESERVE_SIZE: usize = 10000;
+
+/// Signs with a Web Crypto `CryptoKey`, so the private key never has to leave the browser's key
+/// store. Unlike [`crate::wasm_signer::WasmSigner`], c2pa-rs builds the COSE signature and
+/// embeds the certificate chain; Web Crypto only signs the bytes it is given.
+#[derive(Debug)]
+pub(crate) struct WebCryptoSigner {
+    key: JsValue,
+    params: Object,
+    signing_alg: SigningAlg,
+    certs: Vec<Vec<u8>>,
+    reserve_size: usize,
+    progress: Progress,
+}
+
+impl WebCryptoSigner {
+    /// Returns true when the definition describes a Web Crypto signer, that is, it has a `key`.
+    pub(crate) fn is_definition(js_value: &JsValue) -> bool {
+        Reflect::get(js_value, &"key".into()).is_ok_and(|key| !key.is_undefined())
+    }
+
+    /// Creates a signer from a `{ key, certs, alg?, reserveSize? }` definition.
+    ///
+    /// The algorithm is derived from the key; a declared `alg` must agree with it. The
+    /// certificate chain is checked as it is for a callback signer with `certs`.
+    pub(crate) fn from_definition(js_value: &JsValue) -> Result<Self, JsValue> {
+        let key = Reflect::get(js_value, &"key".into())?;
+        let signing_alg = key_alg(&key)?;
+
+        let alg_value = Reflect::get(js_value, &"alg".into())?;
+        if !alg_value.is_undefined() && !alg_value.is_null() {
+            let declared_alg = parse_alg(&alg_value)?;
+            if declared_alg != signing_alg {
+                return Err(format!(
+                    "The signer's alg is {declared_alg}, but its key signs with {signing_alg}"
+                )
+                .into());
+            }
+        }
+
+        let certs = certs_from_js(&Reflect::get(js_value, &"certs".into())?)?;
+        let diagnostics = SignerDiagnostics::check(&certs, Some(signing_alg), now());
+        if !diagnostics.valid {
+            return Err(diagnostics_error(&diagnostics));
+        }
+
+        let reserve_size = Reflect::get(js_value, &"reserveSize".into())?
+            .as_f64()
+            .map_or(DEFAULT_RESERVE_SIZE, |size| size as usize);
+
+        Ok(Self {
+            key,
+            params: sign_params(signing_alg)?,
+            signing_alg,
+            certs,
+            reserve_size,
+            progress: Progress::default(),
+        })
+    }
+
+    /// Reports the signing phase to `progress` each time the signer is called.
+    pub(crate) fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    async fn subtle_sign(&self, data: &[u8]) -> Result<Vec<u8>, JsValue> {
+        let crypto = Reflect::get(&js_sys::global(), &"crypto".into())?;
+        let subtle = Reflect::get(&crypto, &"subtle".into())?;
+        let sign_fn: JsFunction = Reflect::get(&subtle, &"sign".into())?.dyn_into()?;
+
+        let sign_promise: JsPromise = sign_fn
+            .call3(&subtle, &self.params, &self.key, &Uint8Array::from(data))?
+            .dyn_into()?;
+        let signature = JsFuture::from(sign_promise).await?;
+
+        Ok(Uint8Array::new(&signature).to_vec())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AsyncSigner for WebCryptoSigner {
+    async fn sign(&self, data: Vec<u8>) -> C2paResult<Vec<u8>> {
+        self.progress.phase(Phase::Signing);
+
+        // Web Crypto returns ECDSA signatures as raw `r || s`, the form COSE expects.
+        self.subtle_sign(&data)
+            .await
+            .map_err(|err| c2pa::Error::BadParam(format!("Error signing with Web Crypto: {err:?}")))
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.signing_alg
+    }
+
+    fn certs(&self) -> C2paResult<Vec<Vec<u8>>> {
+        Ok(self.certs.clone())
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.reserve_size
+    }
+}
+
+/// Picks the signing algorithm from a `CryptoKey`'s `algorithm`, checking that it is a private
+/// key that may sign.
+fn key_alg(key: &JsValue) -> Result<SigningAlg, String> {
+    if string_property(key, "type").as_deref() != Some("private") {
+        return Err("The signer's key must be a private CryptoKey".to_owned());
+    }
+    let usages = Reflect::get(key, &"usages".into()).unwrap_or_default();
+    if !Array::from(&usages).includes(&"sign".into(), 0) {
+        return Err("The signer's key must have the \"sign\" usage".to_owned());
+    }
+
+    let algorithm = Reflect::get(key, &"algorithm".into()).unwrap_or_default();
+    let name = string_property(&algorithm, "name").unwrap_or_default();
+    match name.as_str() {
+        "ECDSA" => match string_property(&algorithm, "namedCurve").as_deref() {
+            Some("P-256") => Ok(SigningAlg::Es256),
+            Some("P-384") => Ok(SigningAlg::Es384),
+            Some("P-521") => Ok(SigningAlg::Es512),
+            curve => Err(format!(
+                "ECDSA keys on curve {} cannot sign C2PA manifests",
+                curve.unwrap_or("(unknown)")
+            )),
+        },
+        "RSA-PSS" => {
+            let hash = Reflect::get(&algorithm, &"hash".into()).unwrap_or_default();
+            match string_property(&hash, "name").as_deref() {
+                Some("SHA-256") => Ok(SigningAlg::Ps256),
+                Some("SHA-384") => Ok(SigningAlg::Ps384),
+                Some("SHA-512") => Ok(SigningAlg::Ps512),
+                hash => Err(format!(
+                    "RSA-PSS keys with hash {} cannot sign C2PA manifests",
+                    hash.unwrap_or("(unknown)")
+                )),
+            }
+        }
+        "Ed25519" => Ok(SigningAlg::Ed25519),
+        name => Err(format!(
+            "{name} keys cannot sign C2PA manifests; use an ECDSA, RSA-PSS or Ed25519 key"
+        )),
+    }
+}
+
+/// Builds the `algorithm` argument of `SubtleCrypto.sign` for `alg`. PSS salts are as long as
+/// the hash, as C2PA requires.
+fn sign_params(alg: SigningAlg) -> Result<Object, JsValue> {
+    let params = Object::new();
+    let (name, hash, salt_length) = match alg {
+        SigningAlg::Es256 => ("ECDSA", Some("SHA-256"), None),
+        SigningAlg::Es384 => ("ECDSA", Some("SHA-384"), None),
+        SigningAlg::Es512 => ("ECDSA", Some("SHA-512"), None),
+        SigningAlg::Ps256 => ("RSA-PSS", None, Some(32)),
+        SigningAlg::Ps384 => ("RSA-PSS", None, Some(48)),
+        SigningAlg::Ps512 => ("RSA-PSS", None, Some(64)),
+        SigningAlg::Ed25519 => ("Ed25519", None, None),
+    };
+    Reflect::set(&params, &"name".into(), &name.into())?;
+    if let Some(hash) = hash {
+        Reflect::set(&params, &"hash".into(), &hash.into())?;
+    }
+    if let Some(salt_length) = salt_length {
+        Reflect::set(&params, &"saltLength".into(), &salt_length.into())?;
+    }
+    Ok(params)
+}
+
+/// Reads `certs` as a PEM bundle or a single certificate, or an array of PEM or DER
+/// certificates, leaf first. Returns the chain as DER.
+fn certs_from_js(value: &JsValue) -> Result<Vec<Vec<u8>>, JsString> {
+    let items: Vec<JsValue> = if Array::is_array(value) {
+        Array::from(value).iter().collect()
+    } else {
+        vec![value.clone()]
+    };
+
+    let mut certs = Vec::new();
+    for item in items {
+        let bytes = item
+            .dyn_into::<Uint8Array>()
+            .map_err(|_| {
+                JsString::from("The signer's certs must be a Uint8Array or an array of them")
+            })?
+            .to_vec();
+        if bytes.trim_ascii_start().starts_with(b"-----BEGIN") {
+            let blocks = pem::parse_many(&bytes).map_err(|err| {
+                JsString::from(format!("The signer's certs are not valid PEM: {err}"))
+            })?;
+            certs.extend(
+                blocks
+                    .into_iter()
+                    .filter(|block| block.tag() == "CERTIFICATE")
+                    .map(|block| block.into_contents()),
+            );
+        } else {
+            certs.push(bytes);
+        }
+    }
+    Ok(certs)
+}
+
+fn string_property(value: &JsValue, name: &str) -> Option<String> {
+    Reflect::get(value, &name.into()).ok()?.as_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_dedicated_worker);
+
+    async fn generate_key(algorithm: &Object) -> JsValue {
+        let crypto = Reflect::get(&js_sys::global(), &"crypto".into()).unwrap();
+        let subtle = Reflect::get(&crypto, &"subtle".into()).unwrap();
+        let generate: JsFunction = Reflect::get(&subtle, &"generateKey".into())
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+        let usages = Array::of2(&"sign".into(), &"verify".into());
+        let promise: JsPromise = generate
+            .call3(&subtle, algorithm, &false.into(), &usages)
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+        let key_pair = JsFuture::from(promise).await.unwrap();
+        Reflect::get(&key_pair, &"privateKey".into()).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    async fn derives_the_algorithm_from_the_key() {
+        let algorithm = Object::new();
+        Reflect::set(&algorithm, &"name".into(), &"ECDSA".into()).unwrap();
+        Reflect::set(&algorithm, &"namedCurve".into(), &"P-384".into()).unwrap();
+        let key = generate_key(&algorithm).await;
+
+        assert_eq!(key_alg(&key).unwrap(), SigningAlg::Es384);
+    }
+}
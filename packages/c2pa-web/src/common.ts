@@ -14,10 +14,24 @@ export type { Reader, ReaderFactory } from './lib/reader.js';
 export type {
   Builder,
   BuilderFactory,
-  ManifestAndAssetBytes
+  ManifestAndAssetBytes,
+  SignedFragments
 } from './lib/builder.js';
 
-export type { Signer, SigningAlg } from './lib/signer.js';
+export type { Context, ContextFactory } from './lib/context.js';
+
+export type {
+  ProgressOptions,
+  ProgressPhase,
+  ProgressReport
+} from './lib/progress.js';
+
+export type {
+  Signer,
+  SignerCertificateError,
+  SigningAlg,
+  WebCryptoSigner
+} from './lib/signer.js';
 
 export {
   isSupportedReaderFormat,
@@ -9,15 +9,22 @@
 
 import { test, describe, expect } from 'test/methods.js';
 import { ManifestDefinition, Ingredient, Action } from '@contentauth/c2pa-types';
-import { getBlobForAsset, createTestSigner } from 'test/utils.js';
+import {
+  getBlobForAsset,
+  createTestSigner,
+  createWebCryptoSigner
+} from 'test/utils.js';
 import { Settings } from '@contentauth/c2pa-utilities';
 import { createC2pa } from './c2pa.js';
+import type { ProgressReport } from './progress.js';
 import wasmSrc from '@contentauth/c2pa-web/resources/c2pa.wasm?url';
 
 import C_JPG from 'test/assets/C.jpg';
 import PirateShip_cloud from 'test/assets/PirateShip_save_credentials_to_cloud.jpg';
 import C_with_CAWG_data from 'test/assets/C_with_CAWG_data.jpg';
 import SAMPLE1_JXL from 'test/assets/sample1.jxl';
+import unsigned_dashinit from 'test/assets/unsigned_dashinit.mp4';
+import unsigned_dash1 from 'test/assets/unsigned_dash1.m4s?url';
 
 describe('builder', () => {
   describe('creation', () => {
@@ -1255,5 +1262,125 @@ describe('builder', () => {
         expect(manifestStore.active_manifest).toBeDefined();
       });
     });
+
+    describe('sign with a Web Crypto key', () => {
+      test('should sign with a CryptoKey and embed its certificate chain', async ({
+        c2pa
+      }) => {
+        const blob = await getBlobForAsset(C_JPG);
+
+        const builder = await c2pa.builder.new();
+        const signer = await createWebCryptoSigner();
+        const signedBytes = await builder.sign(signer, 'image/jpeg', blob);
+
+        const reader = await c2pa.reader.fromBlob(
+          'image/jpeg',
+          new Blob([signedBytes], { type: 'image/jpeg' })
+        );
+        const manifestStore = await reader!.manifestStore();
+        const codes = manifestStore.validation_status?.map(({ code }) => code);
+        expect(codes).toEqual(['signingCredential.untrusted']);
+      });
+
+      test('should reject a key that does not match the certificates', async ({
+        c2pa
+      }) => {
+        const blob = await getBlobForAsset(C_JPG);
+        const { certs } = await createWebCryptoSigner();
+        const { privateKey } = await crypto.subtle.generateKey(
+          { name: 'ECDSA', namedCurve: 'P-384' },
+          false,
+          ['sign', 'verify']
+        );
+
+        const builder = await c2pa.builder.new();
+        await expect(
+          builder.sign({ key: privateKey, certs }, 'image/jpeg', blob)
+        ).rejects.toThrow('cannot sign with es384');
+      });
+    });
+
+    describe('signFragmented', () => {
+      test('should sign an init segment and bind each fragment to it', async ({
+        c2pa
+      }) => {
+        const init = await getBlobForAsset(unsigned_dashinit);
+        const fragment = await getBlobForAsset(unsigned_dash1);
+
+        const builder = await c2pa.builder.new();
+        const signer = await createWebCryptoSigner();
+        const signed = await builder.signFragmented(signer, 'video/mp4', init, [
+          fragment,
+          fragment
+        ]);
+
+        expect(signed.fragments).toHaveLength(2);
+        for (const signedFragment of signed.fragments) {
+          expect(signedFragment.byteLength).toBeGreaterThan(fragment.size);
+
+          const reader = await c2pa.reader.fromBlobFragment(
+            'video/mp4',
+            new Blob([signed.init], { type: 'video/mp4' }),
+            new Blob([signedFragment])
+          );
+          const manifestStore = await reader!.manifestStore();
+          const codes = manifestStore.validation_status?.map(({ code }) => code);
+          expect(codes).toContain('signingCredential.untrusted');
+          expect(codes).not.toContain('assertion.bmffHash.mismatch');
+        }
+      });
+
+      test('should report a fragment that does not match the manifest', async ({
+        c2pa
+      }) => {
+        const init = await getBlobForAsset(unsigned_dashinit);
+        const fragment = await getBlobForAsset(unsigned_dash1);
+
+        const builder = await c2pa.builder.new();
+        const signer = await createWebCryptoSigner();
+        const signed = await builder.signFragmented(signer, 'video/mp4', init, [
+          fragment
+        ]);
+        const tampered = signed.fragments[0];
+        tampered[tampered.length - 1] ^= 1;
+
+        const reader = await c2pa.reader.fromBlobFragment(
+          'video/mp4',
+          new Blob([signed.init], { type: 'video/mp4' }),
+          new Blob([tampered])
+        );
+        const manifestStore = await reader!.manifestStore();
+        const codes = manifestStore.validation_status?.map(({ code }) => code);
+        expect(codes).toContain('assertion.bmffHash.mismatch');
+      });
+    });
+
+    describe('sign with onProgress', () => {
+      test('should report hashing, signing and writing progress', async ({
+        c2pa
+      }) => {
+        const blob = await getBlobForAsset(C_JPG);
+
+        const builder = await c2pa.builder.new();
+        const signer = await createTestSigner();
+        const reports: ProgressReport[] = [];
+        const signedBytes = await builder.sign(signer, 'image/jpeg', blob, {
+          onProgress: (report) => reports.push(report)
+        });
+
+        const phases = new Set(reports.map((report) => report.phase));
+        expect(phases).toEqual(new Set(['hashing', 'signing', 'writing']));
+
+        const hashing = reports.filter((report) => report.phase === 'hashing');
+        expect(hashing.at(-1)).toEqual({
+          phase: 'hashing',
+          bytes: blob.size,
+          totalBytes: blob.size
+        });
+
+        const writing = reports.filter((report) => report.phase === 'writing');
+        expect(writing.at(-1)!.bytes).toBe(signedBytes.byteLength);
+      });
+    });
   });
 });
@@ -8,14 +8,22 @@
  */
 
 import { WorkerManager } from './worker/workerManager.js';
-import { getSerializablePayload, type Signer } from './signer.js';
+import { Context, getContextId } from './context.js';
+import type { ProgressOptions } from './progress.js';
+import {
+  getSerializablePayload,
+  isWebCryptoSigner,
+  type Signer,
+  type WebCryptoSigner
+} from './signer.js';
 import type {
   Action,
   AssertionDefinition,
   BuilderIntent,
   C2paReason,
   Ingredient,
-  ManifestDefinition
+  ManifestDefinition,
+  TrustConfig
 } from '@contentauth/c2pa-types';
 import { Settings, resolveSettings } from '@contentauth/c2pa-utilities';
 
@@ -25,31 +33,42 @@ import { Settings, resolveSettings } from '@contentauth/c2pa-utilities';
 export interface BuilderFactory {
   /**
    * Create a {@link Builder} with a minimal manifest definition as its initial state.
-   * @param settings Optional context settings for the builder. Will override any values inherited by the top-level settings passed to createC2pa.
+   * @param settings Optional context settings for the builder, or a shared {@link Context}. Settings will override any values inherited by the top-level settings passed to createC2pa.
+   * @param trust Optional trust lists for the builder, validated up front and applied on top of the settings.
    * @returns A {@link Builder} object.
    */
-  new: (settings?: Settings) => Promise<Builder>;
+  new: (
+    settings?: Settings | Context,
+    trust?: TrustConfig
+  ) => Promise<Builder>;
 
   /**
    * Create a {@link Builder} from a {@link ManifestDefinition}.
    *
    * @param definition The {@link ManifestDefinition} to be used as the builder's initial state.
-   * @param settings Optional context settings for the builder. Will override any values inherited by the top-level settings passed to createC2pa.
+   * @param settings Optional context settings for the builder, or a shared {@link Context}. Settings will override any values inherited by the top-level settings passed to createC2pa.
+   * @param trust Optional trust lists for the builder, validated up front and applied on top of the settings.
    * @returns A {@link Builder} object.
    */
   fromDefinition: (
     definition: ManifestDefinition,
-    settings?: Settings
+    settings?: Settings | Context,
+    trust?: TrustConfig
   ) => Promise<Builder>;
 
   /**
    * Create a {@link Builder} from a builder archive (created from {@link Builder.toArchive}).
    *
    * @param archive Builder archive as a blob.
-   * @param settings Optional context settings for the builder. Will override any values inherited by the top-level settings passed to createC2pa.
+   * @param settings Optional context settings for the builder, or a shared {@link Context}. Settings will override any values inherited by the top-level settings passed to createC2pa.
+   * @param trust Optional trust lists for the builder, validated up front and applied on top of the settings.
    * @returns A {@link Builder} object.
    */
-  fromArchive: (archive: Blob, settings?: Settings) => Promise<Builder>;
+  fromArchive: (
+    archive: Blob,
+    settings?: Settings | Context,
+    trust?: TrustConfig
+  ) => Promise<Builder>;
 }
 
 /**
@@ -227,25 +246,49 @@ export interface Builder {
   /**
    * Sign an asset.
    *
+   * @param options Optional `onProgress` callback, called as the asset is hashed, signed and written.
    * @todo Docs coming soon
    */
   sign: (
-    signer: Signer,
+    signer: Signer | WebCryptoSigner,
     format: string,
-    blob: Blob
+    blob: Blob,
+    options?: ProgressOptions
   ) => Promise<Uint8Array<ArrayBuffer>>;
 
   /**
    * Sign an asset and get both the signed asset bytes and the manifest bytes.
    *
+   * @param options Optional `onProgress` callback, called as the asset is hashed, signed and written.
    * @todo Docs coming soon
    */
   signAndGetManifestBytes: (
-    signer: Signer,
+    signer: Signer | WebCryptoSigner,
     format: string,
-    blob: Blob
+    blob: Blob,
+    options?: ProgressOptions
   ) => Promise<ManifestAndAssetBytes>;
 
+  /**
+   * Sign a fragmented MP4 (for instance DASH or HLS fMP4). The manifest is embedded in the init segment
+   * and binds every fragment, which each receive a C2PA Merkle box.
+   *
+   * @param signer Signer used to sign the manifest. It is called once, after the init segment has been
+   * hashed with a placeholder manifest of the final size in place.
+   * @param format Format of the init segment, such as `video/mp4`.
+   * @param init Blob of the init segment.
+   * @param fragments Blobs of the fragments, in presentation order.
+   * @param options Optional `onProgress` callback.
+   * @returns The signed init segment and fragments, in the order given.
+   */
+  signFragmented: (
+    signer: Signer | WebCryptoSigner,
+    format: string,
+    init: Blob,
+    fragments: Blob[],
+    options?: ProgressOptions
+  ) => Promise<SignedFragments>;
+
   /**
    * Dispose of this Builder, freeing the memory it occupied and preventing further use. Call this whenever the Builder is no longer needed.
    */
@@ -284,11 +327,29 @@ function getActionGroupsFromDefinition(
     });
 }
 
+/**
+ * Registers the callback of a callback signer so the worker can call it. Web Crypto signers sign
+ * in the worker, so there is nothing to register for them.
+ */
+function registerSigner(
+  worker: WorkerManager,
+  signer: Signer | WebCryptoSigner
+): number | undefined {
+  return isWebCryptoSigner(signer)
+    ? undefined
+    : worker.registerSignReceiver(signer.sign);
+}
+
 export interface ManifestAndAssetBytes {
   manifest: Uint8Array<ArrayBuffer>;
   asset: Uint8Array<ArrayBuffer>;
 }
 
+export interface SignedFragments {
+  init: Uint8Array<ArrayBuffer>;
+  fragments: Uint8Array<ArrayBuffer>[];
+}
+
 /**
  * @param worker - Worker (via WorkerManager) to be associated with this reader factory.
  * @param settings - Optional settings to be used for all builders.
@@ -303,9 +364,15 @@ export function createBuilderFactory(worker: WorkerManager, settings?: Settings)
   });
 
   return {
-    async new(settings?: Settings) {
-      const settingsJson = await resolveSettings(baseSettings, settings);
-      const builderId = await tx.builder_new(settingsJson);
+    async new(settings?: Settings | Context, trust?: TrustConfig) {
+      const contextId = getContextId(settings, trust);
+      const builderId =
+        contextId === undefined
+          ? await tx.builder_new(
+              await resolveSettings(baseSettings, settings as Settings),
+              trust
+            )
+          : await tx.builder_newWithContext(contextId);
 
       const builder = createBuilder(worker, builderId, () => {
         registry.unregister(builder);
@@ -315,10 +382,21 @@ export function createBuilderFactory(worker: WorkerManager, settings?: Settings)
       return builder;
     },
 
-    async fromDefinition(definition: ManifestDefinition, settings?: Settings) {
+    async fromDefinition(
+      definition: ManifestDefinition,
+      settings?: Settings | Context,
+      trust?: TrustConfig
+    ) {
       const json = JSON.stringify(definition);
-      const settingsJson = await resolveSettings(baseSettings, settings);
-      const builderId = await tx.builder_fromJson(json, settingsJson);
+      const contextId = getContextId(settings, trust);
+      const builderId =
+        contextId === undefined
+          ? await tx.builder_fromJson(
+              json,
+              await resolveSettings(baseSettings, settings as Settings),
+              trust
+            )
+          : await tx.builder_fromJsonWithContext(json, contextId);
 
       const builder = createBuilder(worker, builderId, () => {
         registry.unregister(builder);
@@ -328,9 +406,20 @@ export function createBuilderFactory(worker: WorkerManager, settings?: Settings)
       return builder;
     },
 
-    async fromArchive(archive: Blob, settings?: Settings) {
-      const settingsJson = await resolveSettings(baseSettings, settings);
-      const builderId = await tx.builder_fromArchive(archive, settingsJson);
+    async fromArchive(
+      archive: Blob,
+      settings?: Settings | Context,
+      trust?: TrustConfig
+    ) {
+      const contextId = getContextId(settings, trust);
+      const builderId =
+        contextId === undefined
+          ? await tx.builder_fromArchive(
+              archive,
+              await resolveSettings(baseSettings, settings as Settings),
+              trust
+            )
+          : await tx.builder_fromArchiveWithContext(archive, contextId);
 
       const builder = createBuilder(worker, builderId, () => {
         registry.unregister(builder);
@@ -476,34 +565,70 @@ function createBuilder(
     },
 
     async sign(
-      signer: Signer,
+      signer: Signer | WebCryptoSigner,
       format: string,
-      blob: Blob
+      blob: Blob,
+      options?: ProgressOptions
     ): Promise<Uint8Array<ArrayBuffer>> {
       const payload = await getSerializablePayload(signer);
-      const requestId = worker.registerSignReceiver(signer.sign);
+      const requestId = registerSigner(worker, signer);
 
-      const result = await tx.builder_sign(
-        id,
-        requestId,
-        payload,
-        format,
-        blob
+      const result = await worker.withProgress(
+        options?.onProgress,
+        (progressId) =>
+          tx.builder_sign(id, requestId, payload, format, blob, progressId)
       );
 
       return result;
     },
 
-    async signAndGetManifestBytes(signer: Signer, format: string, blob: Blob) {
+    async signAndGetManifestBytes(
+      signer: Signer | WebCryptoSigner,
+      format: string,
+      blob: Blob,
+      options?: ProgressOptions
+    ) {
       const payload = await getSerializablePayload(signer);
-      const requestId = worker.registerSignReceiver(signer.sign);
+      const requestId = registerSigner(worker, signer);
+
+      const result = await worker.withProgress(
+        options?.onProgress,
+        (progressId) =>
+          tx.builder_signAndGetManifestBytes(
+            id,
+            requestId,
+            payload,
+            format,
+            blob,
+            progressId
+          )
+      );
 
-      const result = await tx.builder_signAndGetManifestBytes(
-        id,
-        requestId,
-        payload,
-        format,
-        blob
+      return result;
+    },
+
+    async signFragmented(
+      signer: Signer | WebCryptoSigner,
+      format: string,
+      init: Blob,
+      fragments: Blob[],
+      options?: ProgressOptions
+    ): Promise<SignedFragments> {
+      const payload = await getSerializablePayload(signer);
+      const requestId = registerSigner(worker, signer);
+
+      const result = await worker.withProgress(
+        options?.onProgress,
+        (progressId) =>
+          tx.builder_signFragmented(
+            id,
+            requestId,
+            payload,
+            format,
+            init,
+            fragments,
+            progressId
+          )
       );
 
       return result;
@@ -11,6 +11,7 @@ import { createReaderFactory, ReaderFactory } from './reader.js';
 import { WASM_SRI } from '@contentauth/c2pa-wasm';
 import { Settings, resolveSettings } from '@contentauth/c2pa-utilities';
 import { BuilderFactory, createBuilderFactory } from './builder.js';
+import { ContextFactory, createContextFactory } from './context.js';
 
 export interface Config {
   /**
@@ -45,6 +46,11 @@ export interface C2paSdk {
    */
   builder: BuilderFactory;
 
+  /**
+   * Contains methods for creating Context objects shared by readers and builders.
+   */
+  context: ContextFactory;
+
   /**
    * Terminates the SDK's underlying web worker.
    */
@@ -76,6 +82,7 @@ export async function createC2pa(config: Config): Promise<C2paSdk> {
   return {
     reader: createReaderFactory(worker, settings),
     builder: createBuilderFactory(worker, settings),
+    context: createContextFactory(worker, settings),
     dispose: worker.terminate
   };
 }
@@ -0,0 +1,103 @@
+/**
+ * Copyright 2025 Adobe
+ * All Rights Reserved.
+ *
+ * NOTICE: Adobe permits you to use, modify, and distribute this file in
+ * accordance with the terms of the Adobe license agreement accompanying
+ * it.
+ */
+
+import { TrustConfig } from '@contentauth/c2pa-types';
+import type { WorkerManager } from './worker/workerManager.js';
+import { Settings, resolveSettings } from '@contentauth/c2pa-utilities';
+
+/**
+ * Contains methods for creating Context objects.
+ */
+export interface ContextFactory {
+  /**
+   * Create a {@link Context} whose settings are parsed once and shared by every reader and builder it is passed to.
+   *
+   * @param settings Optional context settings. Will override any values inherited by the top-level settings passed to createC2pa.
+   * @param trust Optional trust lists, validated up front and applied on top of the settings.
+   * @returns A {@link Context} object.
+   */
+  create: (settings?: Settings, trust?: TrustConfig) => Promise<Context>;
+}
+
+/**
+ * Settings parsed once in the worker, to be passed in place of settings when creating readers and builders.
+ *
+ * @example Reading many assets with the same trust lists:
+ * ```
+ * const context = await c2pa.context.create(settings, trust);
+ *
+ * for (const blob of blobs) {
+ *   const reader = await c2pa.reader.fromBlob(blob.type, blob, context);
+ * }
+ * ```
+ */
+export interface Context {
+  /**
+   * Dispose of this Context, freeing the memory it occupied. Readers and builders already created from it are unaffected.
+   */
+  free: () => Promise<void>;
+}
+
+const contextIds = new WeakMap<Context, number>();
+
+/**
+ * Returns the worker ID of the context passed in place of settings, or undefined for settings.
+ *
+ * @throws When a trust config is passed along with a context, since trust lists belong to the context.
+ */
+export function getContextId(
+  settings?: Settings | Context,
+  trust?: TrustConfig
+): number | undefined {
+  const contextId = settings ? contextIds.get(settings as Context) : undefined;
+
+  if (contextId !== undefined && trust) {
+    throw new Error(
+      'A trust config cannot be combined with a shared Context; pass it to context.create instead'
+    );
+  }
+
+  return contextId;
+}
+
+/**
+ * @param worker - Worker (via WorkerManager) to be associated with this context factory.
+ * @param settings - Optional settings to be used for all contexts.
+ * @returns A {@link ContextFactory} object containing context creation methods.
+ */
+export function createContextFactory(
+  worker: WorkerManager,
+  settings?: Settings
+): ContextFactory {
+  const { tx } = worker;
+  const baseSettings = settings;
+
+  const registry = new FinalizationRegistry<number>((id) => {
+    tx.context_free(id);
+  });
+
+  return {
+    async create(settings?: Settings, trust?: TrustConfig) {
+      const settingsJson = await resolveSettings(baseSettings, settings);
+      const contextId = await tx.context_new(settingsJson, trust);
+
+      const context: Context = {
+        async free() {
+          registry.unregister(context);
+          contextIds.delete(context);
+          await tx.context_free(contextId);
+        }
+      };
+      contextIds.set(context, contextId);
+      registry.register(context, contextId, context);
+
+      return context;
+    }
+  };
+}
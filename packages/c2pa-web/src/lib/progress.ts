@@ -0,0 +1,36 @@
+/**
+ * Copyright 2025 Adobe
+ * All Rights Reserved.
+ *
+ * NOTICE: Adobe permits you to use, modify, and distribute this file in
+ * accordance with the terms of the Adobe license agreement accompanying
+ * it.
+ */
+
+/**
+ * The stage a signing or reading operation is in.
+ */
+export type ProgressPhase = 'hashing' | 'signing' | 'writing';
+
+/**
+ * Reported to an `onProgress` callback as an operation advances.
+ */
+export interface ProgressReport {
+  phase: ProgressPhase;
+  /**
+   * Bytes of the asset hashed or written so far. Always 0 for the signing phase.
+   */
+  bytes: number;
+  /**
+   * Size of the source asset, reported while hashing.
+   */
+  totalBytes?: number;
+}
+
+export interface ProgressOptions {
+  /**
+   * Called as the asset is hashed, signed and written. Reports are throttled, so large assets
+   * report roughly every 256 KiB.
+   */
+  onProgress?: (progress: ProgressReport) => void;
+}
@@ -13,6 +13,7 @@ import { AssetTooLargeError, Settings } from '@contentauth/c2pa-utilities';
 import { UnsupportedFormatError } from './error.js';
 import { getBlobForAsset } from 'test/utils.js';
 import { MAX_SIZE_IN_BYTES } from './reader.js';
+import type { ProgressReport } from './progress.js';
 
 import wasmSrc from '@contentauth/c2pa-web/resources/c2pa.wasm?url';
 
@@ -60,6 +61,30 @@ describe('reader', () => {
         expect(reader).toBeNull();
       });
 
+      test('should report hashing progress up to the size of the blob', async ({
+        c2pa
+      }) => {
+        const blob = await getBlobForAsset(C_with_CAWG_data);
+        const reports: ProgressReport[] = [];
+
+        const reader = await c2pa.reader.fromBlob(
+          blob.type,
+          blob,
+          undefined,
+          undefined,
+          { onProgress: (report) => reports.push(report) }
+        );
+
+        expect(reader).not.toBeNull();
+        expect(reports.length).toBeGreaterThan(0);
+        expect(reports.every((report) => report.phase === 'hashing')).toBe(true);
+        expect(reports.at(-1)).toEqual({
+          phase: 'hashing',
+          bytes: blob.size,
+          totalBytes: blob.size
+        });
+      });
+
       test('should throw UnsupportedFormatError for an unsupported format', async ({
         c2pa
       }) => {
@@ -367,6 +392,138 @@ describe('reader', () => {
         expect(activeManifest).toEqual(expectedActiveManifest);
       });
     });
+
+    describe('validationReport', () => {
+      test('should summarize the validation results when trust is not evaluated', async () => {
+        const c2pa = await createC2pa({
+          wasmSrc,
+          settings: {
+            verify: { verifyTrust: false },
+            cawgTrust: { verifyTrustList: false }
+          }
+        });
+
+        const blob = await getBlobForAsset(C_with_CAWG_data);
+
+        const reader = await c2pa.reader.fromBlob(blob.type, blob);
+
+        const report = await reader!.validationReport();
+
+        expect(report.state).toEqual('Valid');
+        expect(report.trust_evaluated).toBe(false);
+        expect(report.manifests).toHaveLength(1);
+        expect(report.manifests[0].label).toEqual(
+          (C_with_CAWG_data_ManifestStore as ManifestStore).active_manifest
+        );
+        expect(report.manifests[0].success).toContain(
+          'claimSignature.validated'
+        );
+
+        c2pa.dispose();
+      });
+
+      test('should report failing assertions of an untrusted asset', async () => {
+        const c2pa = await createC2pa({
+          wasmSrc,
+          settings: {
+            trust: { trustAnchors: anchor_incorrect },
+            verify: { verifyTrust: true }
+          }
+        });
+
+        const blob = await getBlobForAsset(C_with_CAWG_data);
+
+        const reader = await c2pa.reader.fromBlob(blob.type, blob);
+
+        const report = await reader!.validationReport();
+
+        expect(report.trust_evaluated).toBe(true);
+        expect(report.state).not.toEqual('Trusted');
+        expect(report.manifests[0].failure).toContain(
+          'signingCredential.untrusted'
+        );
+        expect(report.manifests[0].failed_assertions).toEqual([
+          'self#jumbf=/c2pa/urn:c2pa:822f2ec0-ef27-4d95-88b4-74586c12873d/c2pa.assertions/cawg.identity'
+        ]);
+
+        c2pa.dispose();
+      });
+    });
+  });
+
+  describe('trust configuration', () => {
+    test('should apply a trust configuration passed to fromBlob', async ({
+      c2pa
+    }) => {
+      const blob = await getBlobForAsset(C_with_CAWG_data);
+
+      const reader = await c2pa.reader.fromBlob(blob.type, blob, undefined, {
+        anchors: anchor_correct,
+        verifyTrust: true
+      });
+
+      expect(reader).not.toBeNull();
+
+      const report = await reader!.validationReport();
+
+      expect(report.trust_evaluated).toBe(true);
+      expect(report.manifests[0].success).toContain(
+        'signingCredential.trusted'
+      );
+    });
+
+    test('should reject anchors without any PEM certificates', async ({
+      c2pa
+    }) => {
+      const blob = await getBlobForAsset(C_with_CAWG_data);
+
+      const readerPromise = c2pa.reader.fromBlob(blob.type, blob, undefined, {
+        anchors: 'not a certificate'
+      });
+
+      await expect(readerPromise).rejects.toThrowError(
+        'TrustConfig(NoCertificates { field: "anchors" })'
+      );
+    });
+  });
+
+  describe('shared context', () => {
+    test('should share one context across readers', async ({ c2pa }) => {
+      const blob = await getBlobForAsset(C_with_CAWG_data);
+      const context = await c2pa.context.create(undefined, {
+        anchors: anchor_correct,
+        verifyTrust: true
+      });
+
+      const first = await c2pa.reader.fromBlob(blob.type, blob, context);
+      const second = await c2pa.reader.fromBlob(blob.type, blob, context);
+
+      for (const reader of [first, second]) {
+        const report = await reader!.validationReport();
+        expect(report.manifests[0].success).toContain(
+          'signingCredential.trusted'
+        );
+      }
+
+      await context.free();
+    });
+
+    test('should reject a trust config combined with a context', async ({
+      c2pa
+    }) => {
+      const blob = await getBlobForAsset(C_with_CAWG_data);
+      const context = await c2pa.context.create();
+
+      const readerPromise = c2pa.reader.fromBlob(blob.type, blob, context, {
+        verifyTrust: false
+      });
+
+      await expect(readerPromise).rejects.toThrowError(
+        'A trust config cannot be combined with a shared Context'
+      );
+
+      await context.free();
+    });
   });
 
   describe('errors', () => {
@@ -7,10 +7,17 @@
  * it.
  */
 
-import { Manifest, ManifestStore } from '@contentauth/c2pa-types';
+import {
+  Manifest,
+  ManifestStore,
+  TrustConfig,
+  ValidationReport
+} from '@contentauth/c2pa-types';
 import { UnsupportedFormatError } from './error.js';
 import { isSupportedReaderFormat } from './supportedFormats.js';
 import type { WorkerManager } from './worker/workerManager.js';
+import { Context, getContextId } from './context.js';
+import type { ProgressOptions } from './progress.js';
 import {
   Settings,
   resolveSettings,
@@ -29,13 +36,17 @@ export interface ReaderFactory {
    *
    * @param format Asset format.
    * @param blob Blob of asset bytes.
-   * @param settings Optional context settings for the reader. Will override any values inherited by the top-level settings passed to createC2pa.
+   * @param settings Optional context settings for the reader, or a shared {@link Context}. Settings will override any values inherited by the top-level settings passed to createC2pa.
+   * @param trust Optional trust lists for the reader, validated up front and applied on top of the settings.
+   * @param options Optional `onProgress` callback, called as the asset is read and hashed.
    * @returns A {@link Reader} object or null if no C2PA metadata was found.
    */
   fromBlob: (
     format: string,
     blob: Blob,
-    settings?: Settings
+    settings?: Settings | Context,
+    trust?: TrustConfig,
+    options?: ProgressOptions
   ) => Promise<Reader | null>;
 
   /**
@@ -43,14 +54,16 @@ export interface ReaderFactory {
    * @param format Asset format.
    * @param init Blob of initial fragment bytes.
    * @param fragment Blob of fragment bytes.
-   * @param settings Optional context settings for the reader. Will override any values inherited by the top-level settings passed to createC2pa.
+   * @param settings Optional context settings for the reader, or a shared {@link Context}. Settings will override any values inherited by the top-level settings passed to createC2pa.
+   * @param trust Optional trust lists for the reader, validated up front and applied on top of the settings.
    * @returns A {@link Reader} object or null if no C2PA metadata was found.
    */
   fromBlobFragment: (
     format: string,
     init: Blob,
     fragment: Blob,
-    settings?: Settings
+    settings?: Settings | Context,
+    trust?: TrustConfig
   ) => Promise<Reader | null>;
 }
 
@@ -80,6 +93,22 @@ export interface Reader {
    */
   activeManifest: () => Promise<Manifest>;
 
+  /**
+   * @returns A {@link ValidationReport} summarizing the validation state of the asset, the status codes reported for each manifest, and whether trust was evaluated.
+   *
+   * @example Checking whether an asset is trusted:
+   * ```
+   * const reader = await c2pa.reader.fromBlob(blob.type, blob);
+   *
+   * const { state, manifests } = await reader.validationReport();
+   *
+   * if (state === 'Invalid') {
+   *   console.log(manifests[0].failure, manifests[0].failed_assertions);
+   * }
+   * ```
+   */
+  validationReport: () => Promise<ValidationReport>;
+
   /**
    * @returns The asset's full {@link ManifestStore}.
    *
@@ -132,7 +161,9 @@ export function createReaderFactory(worker: WorkerManager, settings?: Settings):
     async fromBlob(
       format: string,
       blob: Blob,
-      settings?: Settings
+      settings?: Settings | Context,
+      trust?: TrustConfig,
+      options?: ProgressOptions
     ): Promise<Reader | null> {
       if (!isSupportedReaderFormat(format)) {
         throw new UnsupportedFormatError(format);
@@ -141,9 +172,31 @@ export function createReaderFactory(worker: WorkerManager, settings?: Settings):
       validateAssetSize(blob.size, MAX_SIZE_IN_BYTES);
 
       try {
-        const settingsJson = await resolveSettings(baseSettings, settings);
-
-        const readerId = await tx.reader_fromBlob(format, blob, settingsJson);
+        const contextId = getContextId(settings, trust);
+
+        const contextJson =
+          contextId === undefined
+            ? await resolveSettings(baseSettings, settings as Settings)
+            : undefined;
+
+        const readerId = await worker.withProgress(
+          options?.onProgress,
+          (progressId) =>
+            contextId === undefined
+              ? tx.reader_fromBlob(
+                  format,
+                  blob,
+                  contextJson,
+                  trust,
+                  progressId
+                )
+              : tx.reader_fromBlobWithContext(
+                  format,
+                  blob,
+                  contextId,
+                  progressId
+                )
+        );
 
         const reader = createReader(worker, readerId, () => {
           registry.unregister(reader);
@@ -160,7 +213,8 @@ export function createReaderFactory(worker: WorkerManager, settings?: Settings):
       format: string,
       init: Blob,
       fragment: Blob,
-      settings?: Settings
+      settings?: Settings | Context,
+      trust?: TrustConfig
     ) {
       if (!isSupportedReaderFormat(format)) {
         throw new UnsupportedFormatError(format);
@@ -170,14 +224,23 @@ export function createReaderFactory(worker: WorkerManager, settings?: Settings):
       validateAssetSize(fragment.size, MAX_SIZE_IN_BYTES);
 
       try {
-        const settingsJson = await resolveSettings(baseSettings, settings);
-
-        const readerId = await tx.reader_fromBlobFragment(
-          format,
-          init,
-          fragment,
-          settingsJson
-        );
+        const contextId = getContextId(settings, trust);
+
+        const readerId =
+          contextId === undefined
+            ? await tx.reader_fromBlobFragment(
+                format,
+                init,
+                fragment,
+                await resolveSettings(baseSettings, settings as Settings),
+                trust
+              )
+            : await tx.reader_fromBlobFragmentWithContext(
+                format,
+                init,
+                fragment,
+                contextId
+              );
 
         const reader = createReader(worker, readerId, () => {
           registry.unregister(reader);
@@ -224,6 +287,10 @@ function createReader(
 
       return activeManifest;
     },
+    async validationReport(): Promise<ValidationReport> {
+      const report = await tx.reader_validationReport(id);
+      return report;
+    },
     async json(): Promise<any> {
       const json = await tx.reader_json(id);
 
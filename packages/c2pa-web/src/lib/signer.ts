@@ -7,6 +7,7 @@
  * it.
  */
 
+import type { SignerDiagnostics } from '@contentauth/c2pa-types';
 import type { SigningAlg } from '@contentauth/c2pa-utilities';
 
 export type { SigningAlg };
@@ -18,21 +19,76 @@ export interface Signer {
   ) => Promise<Uint8Array<ArrayBuffer>>;
   reserveSize: () => Promise<number>;
   alg: SigningAlg;
+  /**
+   * PEM certificate chain of the signing key, leaf first. When given, the chain is checked
+   * against `alg` before anything is signed, so a mismatched key, a misordered chain, an expired
+   * certificate or one that is not allowed to sign claims is reported up front.
+   */
+  certs?: Uint8Array<ArrayBuffer>;
 }
 
-export interface SerializableSigningPayload {
+/**
+ * Signs with a Web Crypto key instead of a callback. The key is passed to the worker, which signs
+ * with `crypto.subtle` and embeds the certificate chain, so the key can be non-extractable.
+ */
+export interface WebCryptoSigner {
+  /**
+   * Private key with the `sign` usage: ECDSA on P-256, P-384 or P-521, RSA-PSS, or Ed25519.
+   */
+  key: CryptoKey;
+  /**
+   * Certificate chain of the key, leaf first: a PEM bundle, or an array of PEM or DER
+   * certificates. It is checked against the key before anything is signed.
+   */
+  certs: Uint8Array<ArrayBuffer> | Uint8Array<ArrayBuffer>[];
+  /**
+   * Derived from the key when not given. When given, it must match the key.
+   */
+  alg?: SigningAlg;
+  /**
+   * Bytes to reserve for the signature and certificate chain. Defaults to 10000.
+   */
+  reserveSize?: number;
+}
+
+/**
+ * Thrown when signing with certificates that cannot sign C2PA manifests. `diagnostics` lists
+ * every issue found, as it does for the signers of `@contentauth/c2pa-node`.
+ */
+export interface SignerCertificateError extends Error {
+  diagnostics: SignerDiagnostics;
+}
+
+export interface CallbackSigningPayload {
   reserveSize: number;
   alg: SigningAlg;
+  certs?: Uint8Array<ArrayBuffer>;
+}
+
+export type SerializableSigningPayload =
+  | CallbackSigningPayload
+  | WebCryptoSigner;
+
+export function isWebCryptoSigner(
+  signer: Signer | WebCryptoSigner
+): signer is WebCryptoSigner {
+  return 'key' in signer;
 }
 
 export async function getSerializablePayload(
-  signer: Signer
+  signer: Signer | WebCryptoSigner
 ): Promise<SerializableSigningPayload> {
-  const { alg } = signer;
+  if (isWebCryptoSigner(signer)) {
+    const { key, certs, alg, reserveSize } = signer;
+    return { key, certs, alg, reserveSize };
+  }
+
+  const { alg, certs } = signer;
   const reserveSize = await signer.reserveSize();
 
   return {
     reserveSize,
-    alg
+    alg,
+    certs
   };
 }
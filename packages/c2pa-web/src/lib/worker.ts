@@ -13,18 +13,63 @@ import {
   WasmReader,
   initSync,
   loadSettings,
-  WasmBuilder
+  WasmBuilder,
+  WasmContext
 } from '@contentauth/c2pa-wasm';
 import { createWorkerObjectMap } from './worker/workerObjectMap.js';
 import { createWorkerTx, rx } from './worker/rpc.js';
 import { sanitizeManifestStore } from './worker/sanitizeManifestStore.js';
 import { transfer } from 'highgain';
+import type { ProgressReport } from './progress.js';
+import type {
+  SerializableSigningPayload,
+  SignerCertificateError
+} from './signer.js';
 
 const readerMap = createWorkerObjectMap<WasmReader>();
 const builderMap = createWorkerObjectMap<WasmBuilder>();
+const contextMap = createWorkerObjectMap<WasmContext>();
 
 const tx = createWorkerTx();
 
+function progressCallback(progressId?: number) {
+  if (progressId === undefined) {
+    return undefined;
+  }
+  return (report: ProgressReport) => {
+    void tx.progress(progressId, report);
+  };
+}
+
+/**
+ * Web Crypto signers sign in the worker. Callback signers are called back on the main thread
+ * through `requestId`.
+ */
+function signerDefinition(
+  requestId: number | undefined,
+  payload: SerializableSigningPayload
+) {
+  if ('key' in payload) {
+    return payload;
+  }
+  if (requestId === undefined) {
+    throw new Error('No signer registered for request');
+  }
+  return {
+    reserveSize: payload.reserveSize,
+    alg: payload.alg,
+    certs: payload.certs,
+    sign: async (bytes: Uint8Array<ArrayBuffer>) => {
+      const result = await tx.sign(
+        requestId,
+        transfer(bytes, bytes.buffer),
+        payload.reserveSize
+      );
+      return result;
+    }
+  };
+}
+
 rx(
   wrapFunctionsForErrorHandling({
     async initWorker(module, settings) {
@@ -33,17 +78,59 @@ rx(
         loadSettings(settings);
       }
     },
-    async reader_fromBlob(format, blob, contextJson) {
-      const reader = await WasmReader.fromBlob(format, blob, contextJson);
+    context_new(contextJson, trust) {
+      const context = WasmContext.new(contextJson, trust);
+      const contextId = contextMap.add(context);
+      return contextId;
+    },
+    context_free(contextId) {
+      const context = contextMap.get(contextId);
+      context.free();
+      contextMap.remove(contextId);
+    },
+    async reader_fromBlob(format, blob, contextJson, trust, progressId) {
+      const reader = await WasmReader.fromBlob(
+        format,
+        blob,
+        contextJson,
+        trust,
+        progressCallback(progressId)
+      );
       const readerId = readerMap.add(reader);
       return readerId;
     },
-    async reader_fromBlobFragment(format, init, fragment, contextJson) {
+    async reader_fromBlobFragment(format, init, fragment, contextJson, trust) {
       const reader = await WasmReader.fromBlobFragment(
         format,
         init,
         fragment,
-        contextJson
+        contextJson,
+        trust
+      );
+      const readerId = readerMap.add(reader);
+      return readerId;
+    },
+    async reader_fromBlobWithContext(format, blob, contextId, progressId) {
+      const reader = await WasmReader.fromBlobWithContext(
+        format,
+        blob,
+        contextMap.get(contextId),
+        progressCallback(progressId)
+      );
+      const readerId = readerMap.add(reader);
+      return readerId;
+    },
+    async reader_fromBlobFragmentWithContext(
+      format,
+      init,
+      fragment,
+      contextId
+    ) {
+      const reader = await WasmReader.fromBlobFragmentWithContext(
+        format,
+        init,
+        fragment,
+        contextMap.get(contextId)
       );
       const readerId = readerMap.add(reader);
       return readerId;
@@ -60,6 +147,10 @@ rx(
       const reader = readerMap.get(readerId);
       return reader.activeManifest();
     },
+    reader_validationReport(readerId) {
+      const reader = readerMap.get(readerId);
+      return reader.validationReport();
+    },
     reader_json(readerId) {
       const reader = readerMap.get(readerId);
       return reader.json();
@@ -78,18 +169,39 @@ rx(
       reader.free();
       readerMap.remove(readerId);
     },
-    builder_new(contextJson) {
-      const builder = WasmBuilder.new(contextJson);
+    builder_new(contextJson, trust) {
+      const builder = WasmBuilder.new(contextJson, trust);
+      const builderId = builderMap.add(builder);
+      return builderId;
+    },
+    builder_fromJson(json: string, contextJson, trust) {
+      const builder = WasmBuilder.fromJson(json, contextJson, trust);
       const builderId = builderMap.add(builder);
       return builderId;
     },
-    builder_fromJson(json: string, contextJson) {
-      const builder = WasmBuilder.fromJson(json, contextJson);
+    builder_fromArchive(archive, contextJson, trust) {
+      const builder = WasmBuilder.fromArchive(archive, contextJson, trust);
       const builderId = builderMap.add(builder);
       return builderId;
     },
-    builder_fromArchive(archive, contextJson) {
-      const builder = WasmBuilder.fromArchive(archive, contextJson);
+    builder_newWithContext(contextId) {
+      const builder = WasmBuilder.newWithContext(contextMap.get(contextId));
+      const builderId = builderMap.add(builder);
+      return builderId;
+    },
+    builder_fromJsonWithContext(json, contextId) {
+      const builder = WasmBuilder.fromJsonWithContext(
+        json,
+        contextMap.get(contextId)
+      );
+      const builderId = builderMap.add(builder);
+      return builderId;
+    },
+    builder_fromArchiveWithContext(archive, contextId) {
+      const builder = WasmBuilder.fromArchiveWithContext(
+        archive,
+        contextMap.get(contextId)
+      );
       const builderId = builderMap.add(builder);
       return builderId;
     },
@@ -165,23 +277,20 @@ rx(
       const archive = builder.toArchive() as Uint8Array<ArrayBuffer>;
       return transfer(archive, archive.buffer);
     },
-    async builder_sign(builderId, requestId, payload, format, blob) {
+    async builder_sign(
+      builderId,
+      requestId,
+      payload,
+      format,
+      blob,
+      progressId
+    ) {
       const builder = builderMap.get(builderId);
       const signedBytes = (await builder.sign(
-        {
-          reserveSize: payload.reserveSize,
-          alg: payload.alg,
-          sign: async (bytes) => {
-            const result = await tx.sign(
-              requestId,
-              transfer(bytes, bytes.buffer),
-              payload.reserveSize
-            );
-            return result;
-          }
-        },
+        signerDefinition(requestId, payload),
         format,
-        blob
+        blob,
+        progressCallback(progressId)
       )) as Uint8Array<ArrayBuffer>;
       return transfer(signedBytes, signedBytes.buffer);
     },
@@ -190,24 +299,15 @@ rx(
       requestId,
       payload,
       format,
-      blob
+      blob,
+      progressId
     ) {
       const builder = builderMap.get(builderId);
       const { manifest, asset } = await builder.signAndGetManifestBytes(
-        {
-          reserveSize: payload.reserveSize,
-          alg: payload.alg,
-          sign: async (bytes) => {
-            const result = await tx.sign(
-              requestId,
-              transfer(bytes, bytes.buffer),
-              payload.reserveSize
-            );
-            return result;
-          }
-        },
+        signerDefinition(requestId, payload),
         format,
-        blob
+        blob,
+        progressCallback(progressId)
       );
 
       return transfer(
@@ -218,6 +318,38 @@ rx(
         [manifest.buffer, asset.buffer]
       );
     },
+    async builder_signFragmented(
+      builderId,
+      requestId,
+      payload,
+      format,
+      init,
+      fragments,
+      progressId
+    ) {
+      const builder = builderMap.get(builderId);
+      const { init: signedInit, fragments: signedFragments } =
+        await builder.signFragmented(
+          signerDefinition(requestId, payload),
+          format,
+          init,
+          fragments,
+          progressCallback(progressId)
+        );
+
+      return transfer(
+        {
+          init: signedInit,
+          fragments: signedFragments
+        },
+        [
+          signedInit.buffer,
+          ...signedFragments.map(
+            (fragment: Uint8Array<ArrayBuffer>) => fragment.buffer
+          )
+        ]
+      );
+    },
     builder_free(builderId) {
       const builder = builderMap.get(builderId);
       builder.free();
@@ -230,7 +362,8 @@ rx(
  * Wraps all functions with additional error-handling code that converts any thrown strings into Error objects.
  * This is only necessary because a bug (likely in wasm-bindgen, see https://github.com/wasm-bindgen/wasm-bindgen/issues/4961)
  * prevents the proper handling of Error objects. As a workaround, we "throw" strings from our wasm-bindgen
- * functions and convert them into errors here.
+ * functions and convert them into errors here. Signers throw `{ message, diagnostics }` objects instead,
+ * which become errors with a `diagnostics` property.
  */
 function wrapFunctionsForErrorHandling<
   T extends Record<string, (...args: any[]) => any>
@@ -246,6 +379,12 @@ function wrapFunctionsForErrorHandling<
           throw new Error(e);
         }
 
+        if (isSignerCertificateError(e)) {
+          throw Object.assign(new Error(e.message), {
+            diagnostics: e.diagnostics
+          });
+        }
+
         throw e;
       }
     };
@@ -253,3 +392,15 @@ function wrapFunctionsForErrorHandling<
 
   return wrappedFunctions as T;
 }
+
+function isSignerCertificateError(
+  e: unknown
+): e is Pick<SignerCertificateError, 'message' | 'diagnostics'> {
+  return (
+    typeof e === 'object' &&
+    e !== null &&
+    !(e instanceof Error) &&
+    typeof (e as { message?: unknown }).message === 'string' &&
+    'diagnostics' in e
+  );
+}
@@ -7,8 +7,14 @@
  * it.
  */
 
-import { Action, BuilderIntent, C2paReason } from '@contentauth/c2pa-types';
-import { ManifestAndAssetBytes } from '../builder.js';
+import {
+  Action,
+  BuilderIntent,
+  C2paReason,
+  TrustConfig
+} from '@contentauth/c2pa-types';
+import { ManifestAndAssetBytes, SignedFragments } from '../builder.js';
+import type { ProgressReport } from '../progress.js';
 import type { SerializableSigningPayload } from '../signer.js';
 
 import { channel } from 'highgain';
@@ -17,23 +23,43 @@ import { channel } from 'highgain';
 const { createTx, rx } = channel<{
   initWorker: (module: WebAssembly.Module, settings?: string) => void;
 
+  // Context methods
+  context_new: (contextJson?: string, trust?: TrustConfig) => number;
+  context_free: (contextId: number) => void;
+
   // Reader construction methods
   reader_fromBlob: (
     format: string,
     blob: Blob,
-    contextJson?: string
+    contextJson?: string,
+    trust?: TrustConfig,
+    progressId?: number
   ) => Promise<number>;
   reader_fromBlobFragment: (
     format: string,
     init: Blob,
     fragment: Blob,
-    contextJson?: string
+    contextJson?: string,
+    trust?: TrustConfig
+  ) => Promise<number>;
+  reader_fromBlobWithContext: (
+    format: string,
+    blob: Blob,
+    contextId: number,
+    progressId?: number
+  ) => Promise<number>;
+  reader_fromBlobFragmentWithContext: (
+    format: string,
+    init: Blob,
+    fragment: Blob,
+    contextId: number
   ) => Promise<number>;
 
   // Reader methods
   reader_activeLabel: (readerId: number) => string | null;
   reader_manifestStore: (readerId: number) => any;
   reader_activeManifest: (readerId: number) => any;
+  reader_validationReport: (readerId: number) => any;
   reader_json: (readerId: number) => string;
   reader_crJson: (readerId: number) => string;
   reader_resourceToBytes: (
@@ -43,9 +69,20 @@ const { createTx, rx } = channel<{
   reader_free: (readerId: number) => void;
 
   // Builder construction methods
-  builder_new: (contextJson?: string) => number;
-  builder_fromJson: (json: string, contextJson?: string) => number;
-  builder_fromArchive: (archive: Blob, contextJson?: string) => number;
+  builder_new: (contextJson?: string, trust?: TrustConfig) => number;
+  builder_fromJson: (
+    json: string,
+    contextJson?: string,
+    trust?: TrustConfig
+  ) => number;
+  builder_fromArchive: (
+    archive: Blob,
+    contextJson?: string,
+    trust?: TrustConfig
+  ) => number;
+  builder_newWithContext: (contextId: number) => number;
+  builder_fromJsonWithContext: (json: string, contextId: number) => number;
+  builder_fromArchiveWithContext: (archive: Blob, contextId: number) => number;
 
   // Builder methods
   builder_setIntent: (builderId: number, intent: BuilderIntent) => void;
@@ -87,18 +124,29 @@ const { createTx, rx } = channel<{
   builder_toArchive: (builderId: number) => Uint8Array<ArrayBuffer>;
   builder_sign: (
     builderId: number,
-    requestId: number,
+    requestId: number | undefined,
     payload: SerializableSigningPayload,
     format: string,
-    blob: Blob
+    blob: Blob,
+    progressId?: number
   ) => Promise<Uint8Array<ArrayBuffer>>;
   builder_signAndGetManifestBytes: (
     builderId: number,
-    requestId: number,
+    requestId: number | undefined,
     payload: SerializableSigningPayload,
     format: string,
-    blob: Blob
+    blob: Blob,
+    progressId?: number
   ) => Promise<ManifestAndAssetBytes>;
+  builder_signFragmented: (
+    builderId: number,
+    requestId: number | undefined,
+    payload: SerializableSigningPayload,
+    format: string,
+    init: Blob,
+    fragments: Blob[],
+    progressId?: number
+  ) => Promise<SignedFragments>;
   builder_free: (builderId: number) => void;
 }>();
 
@@ -109,6 +157,7 @@ const { createTx: createWorkerTx, rx: workerRx } = channel<{
     bytes: Uint8Array<ArrayBuffer>,
     reserveSize: number
   ) => Promise<Uint8Array<ArrayBuffer>>;
+  progress: (progressId: number, report: ProgressReport) => void;
 }>('worker');
 
 export { createTx, rx, createWorkerTx, workerRx };
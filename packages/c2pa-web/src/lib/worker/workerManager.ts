@@ -7,6 +7,7 @@
  * it.
  */
 
+import type { ProgressOptions } from '../progress.js';
 import { Signer } from '../signer.js';
 import { createTx, workerRx } from './rpc.js';
 import InlineWorker from '../worker?worker&inline';
@@ -15,6 +16,10 @@ import { transfer } from 'highgain';
 export interface WorkerManager {
   tx: ReturnType<typeof createTx>;
   registerSignReceiver: (signFn: Signer['sign']) => number;
+  withProgress: <T>(
+    onProgress: ProgressOptions['onProgress'],
+    run: (progressId?: number) => Promise<T>
+  ) => Promise<T>;
   terminate: () => void;
 }
 
@@ -56,6 +61,7 @@ export async function createWorkerManager(
 ): Promise<WorkerManager> {
   const { wasm, settingsString, workerSrc } = config;
   let signerRequestId = 0;
+  let progressRequestId = 0;
 
   const worker = workerSrc
     ? new Worker(validateWorkerSrc(workerSrc), { type: 'module' })
@@ -64,6 +70,10 @@ export async function createWorkerManager(
   const tx = createTx(worker);
 
   const signingRequestMap = new Map<number, Signer['sign']>();
+  const progressRequestMap = new Map<
+    number,
+    NonNullable<ProgressOptions['onProgress']>
+  >();
 
   workerRx(
     {
@@ -75,6 +85,9 @@ export async function createWorkerManager(
         }
         const result = await signFn(bytes, reserveSize);
         return transfer(result, result.buffer);
+      },
+      progress: (id, report) => {
+        progressRequestMap.get(id)?.(report);
       }
     },
     worker
@@ -86,11 +99,28 @@ export async function createWorkerManager(
     return id;
   }
 
+  async function withProgress<T>(
+    onProgress: ProgressOptions['onProgress'],
+    run: (progressId?: number) => Promise<T>
+  ): Promise<T> {
+    if (!onProgress) {
+      return run();
+    }
+    const id = progressRequestId++;
+    progressRequestMap.set(id, onProgress);
+    try {
+      return await run(id);
+    } finally {
+      progressRequestMap.delete(id);
+    }
+  }
+
   await tx.initWorker(wasm, settingsString);
 
   return {
     tx,
     registerSignReceiver,
+    withProgress,
     terminate: () => worker.terminate()
   };
 }
@@ -7,7 +7,13 @@
  * it.
  */
 
-import type { Signer, SigningAlg } from '../src/lib/signer.js';
+import type {
+  Signer,
+  SigningAlg,
+  WebCryptoSigner
+} from '../src/lib/signer.js';
+import es256Certs from './certs/es256.pub?raw';
+import es256Key from './certs/es256.pem?raw';
 
 export async function getBlobForAsset(src: string): Promise<Blob> {
   const response = await fetch(src);
@@ -29,3 +35,24 @@ export async function createTestSigner(): Promise<Signer> {
       new Uint8Array(reserveSize) as Uint8Array<ArrayBuffer>
   };
 }
+
+/**
+ * Creates a Web Crypto signer from the ES256 test key and its certificate chain, so the signed
+ * asset can be validated.
+ */
+export async function createWebCryptoSigner(): Promise<WebCryptoSigner> {
+  const base64 = es256Key.replace(/-----[^-]+-----|\s/g, '');
+  const der = Uint8Array.from(atob(base64), (char) => char.charCodeAt(0));
+  const key = await crypto.subtle.importKey(
+    'pkcs8',
+    der,
+    { name: 'ECDSA', namedCurve: 'P-256' },
+    false,
+    ['sign']
+  );
+
+  return {
+    key,
+    certs: new TextEncoder().encode(es256Certs) as Uint8Array<ArrayBuffer>
+  };
+}